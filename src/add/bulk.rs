@@ -0,0 +1,80 @@
+use std::io::{self, Read};
+
+use crate::error::GclipError;
+use crate::history::{History, HistoryFormat};
+use crate::registry::add::normalize_command;
+
+/// 一括登録の入力を分類した結果。
+///
+/// - `accepted`: 新たに登録するコマンド（入力順、重複なし）
+/// - `duplicate`: 登録済み、または入力内で重複していた件数
+/// - `invalid`: 正規化できなかった（空の）件数
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(super) struct BulkInput {
+    pub(super) accepted: Vec<String>,
+    pub(super) duplicate: usize,
+    pub(super) invalid: usize,
+}
+
+/// 標準入力を最後まで読み込む。
+///
+/// 非UTF-8のバイトが混ざっていても読めるよう、損失変換で読み込む。
+pub(super) fn read_stdin() -> Result<String, GclipError> {
+    let mut bytes = Vec::new();
+    io::stdin()
+        .read_to_end(&mut bytes)
+        .map_err(|err| GclipError::io("failed to read stdin", err))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// 入力を1件ずつのコマンドに分ける。
+///
+/// NUL区切りでなければ、`format`（省略時は内容から推測）が1行1コマンド以外の履歴の書式なら
+/// 履歴として解析し、`history` の番号やタイムスタンプを取り除く。
+pub(super) fn parse_entries(input: &str, nul: bool, format: Option<HistoryFormat>) -> Vec<String> {
+    let history_format = if nul {
+        HistoryFormat::Plain
+    } else {
+        format.unwrap_or_else(|| History::detect_format(input))
+    };
+    match history_format {
+        HistoryFormat::Plain => split_entries(input, nul)
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+        other => History::recent_commands_in(input, other, usize::MAX),
+    }
+}
+
+/// 入力を1件ずつに分ける。
+///
+/// `nul` なら NUL 区切り、そうでなければ行区切りとする。
+/// 末尾の区切り文字の後ろにある空の要素は数えない。
+pub(super) fn split_entries(input: &str, nul: bool) -> Vec<&str> {
+    let separator = if nul { '\0' } else { '\n' };
+    let mut entries: Vec<&str> = input
+        .split(separator)
+        .map(|entry| entry.strip_suffix('\r').unwrap_or(entry))
+        .collect();
+    if entries.last().is_some_and(|entry| entry.is_empty()) {
+        entries.pop();
+    }
+    entries
+}
+
+/// 入力を正規化し、登録するもの・重複・不正に分類する。
+pub(super) fn classify_entries(entries: &[String], registered: &[String]) -> BulkInput {
+    let mut result = BulkInput::default();
+    for entry in entries {
+        let Ok(command) = normalize_command(entry) else {
+            result.invalid += 1;
+            continue;
+        };
+        if registered.contains(&command) || result.accepted.contains(&command) {
+            result.duplicate += 1;
+        } else {
+            result.accepted.push(command);
+        }
+    }
+    result
+}
@@ -0,0 +1,46 @@
+use crate::error::GclipError;
+use crate::history::History;
+use crate::suggest;
+
+/// 履歴の末尾から `nth` 番目（1始まり）のコマンドを返す。
+///
+/// gclip自身の呼び出しは数えずに飛ばす。
+pub(crate) fn last_command_from_history(nth: usize) -> Result<String, GclipError> {
+    if nth == 0 {
+        return Err(GclipError::InvalidInput(
+            "--last must be 1 or greater".to_string(),
+        ));
+    }
+    let history_path = suggest::resolve_history_path()?;
+    let contents = suggest::load_history_contents(&history_path)?;
+    let commands = History::recent_commands(&contents, usize::MAX);
+    pick_last_command(&commands, nth)
+        .map(str::to_string)
+        .ok_or_else(|| {
+            GclipError::NoMatches(format!(
+                "history has fewer than {nth} command(s) other than gclip"
+            ))
+        })
+}
+
+/// 古い順に並んだコマンドから、末尾から `nth` 番目のものを選ぶ。
+///
+/// gclip自身の呼び出しは数えない。
+pub(super) fn pick_last_command(commands: &[String], nth: usize) -> Option<&str> {
+    commands
+        .iter()
+        .rev()
+        .filter(|command| !is_gclip_invocation(command))
+        .nth(nth.checked_sub(1)?)
+        .map(String::as_str)
+}
+
+/// gclip自身を呼び出したコマンドかを返す。
+///
+/// 先頭の環境変数の代入と `command` / `builtin` は読み飛ばして判定する。
+pub(super) fn is_gclip_invocation(command: &str) -> bool {
+    command
+        .split_whitespace()
+        .find(|word| !word.contains('=') && !matches!(*word, "command" | "builtin" | "noglob"))
+        .is_some_and(|word| word.rsplit('/').next() == Some("gclip"))
+}
@@ -1,22 +1,296 @@
-use crate::registry::Registry;
-use std::path::Path;
+mod bulk;
+mod last;
+
+use crate::config::Config;
+use crate::error::GclipError;
+use crate::output::display_safe;
+use crate::history::HistoryFormat;
+use crate::registry::{normalize_tags, CommandDetails, Condition, Registry};
+use crate::secret;
+use crate::selection;
+use std::path::{self, Path, PathBuf};
+
+/// `gclip add` の登録オプション。
+///
+/// - `key`: 短縮キー（`--key`）
+/// - `description` / `tags`: 説明とタグ（`--description` / `--tag`）
+/// - `when`: 候補として有効にする条件（`--when`）
+/// - `cwd`: 実行する作業ディレクトリ（`--cwd`）
+/// - `ask`: 未指定の説明とタグを対話的に入力する（`--ask`）
+/// - `assume_yes`: 履歴から登録する際の確認を省略する（`--yes`）
+#[derive(Debug, Clone, Default)]
+pub struct AddOptions {
+    pub key: Option<String>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub when: Condition,
+    pub clear_when: bool,
+    pub cwd: Option<PathBuf>,
+    pub ask: bool,
+    pub assume_yes: bool,
+}
 
 /// コマンド文字列を手動で登録する機能。
 ///
 /// `gclip --add "command"` で登録する。
-pub fn run(command: &str) -> Result<(), String> {
-    let (registry_path, added) = Registry::add_command(command)?;
-    print_result(command, added, &registry_path);
+/// `--key` が指定された場合は短縮キーも割り当てる。
+/// 説明とタグ、条件、作業ディレクトリが指定された場合は、登録と同じ1回の書き込みで付ける。
+/// 秘密情報らしき文字列を含む場合は、設定に従って警告/置き換え/拒否を行う。
+pub fn run(command: &str, options: &AddOptions) -> Result<(), GclipError> {
+    let workdir = resolve_workdir(options)?;
+    let config = Config::load()?;
+    let Some(reviewed) = secret::review_for_registration(command.trim(), config.secrets.mode)?
+    else {
+        print_skipped();
+        return Ok(());
+    };
+
+    let extra = collect_extra(options, workdir)?;
+    let registry = Registry::open()?;
+    let (registry_path, added) = registry.add_command_with_details(
+        &reviewed,
+        options.key.as_deref(),
+        &extra,
+        options.clear_when,
+    )?;
+    print_result(&reviewed, added, &registry_path);
+    if let Some(short_key) = options.key.as_deref() {
+        print_key_result(short_key, &reviewed);
+    }
+    print_extra(options, &extra);
+    Ok(())
+}
+
+/// 複数の手順からなるワークフローを登録する。
+///
+/// `gclip add --step CMD --step CMD ...` で実行する。
+/// 手順を `&&` でつないだものを1件のコマンドとして登録し、手順そのものも保存する。
+/// 秘密情報の確認は手順ごとに行い、どれかをスキップした場合は登録しない。
+pub fn run_workflow(steps: &[String], options: &AddOptions) -> Result<(), GclipError> {
+    let workdir = resolve_workdir(options)?;
+    let config = Config::load()?;
+    let mut reviewed_steps = Vec::new();
+    for step in steps.iter().map(|step| step.trim()).filter(|step| !step.is_empty()) {
+        let Some(reviewed) = secret::review_for_registration(step, config.secrets.mode)? else {
+            print_skipped();
+            return Ok(());
+        };
+        reviewed_steps.push(reviewed);
+    }
+
+    let extra = collect_extra(options, workdir)?;
+    let registry = Registry::open()?;
+    let (registry_path, added, command) = registry.add_workflow(
+        &reviewed_steps,
+        options.key.as_deref(),
+        &extra,
+        options.clear_when,
+    )?;
+    print_result(&command, added, &registry_path);
+    println!("Steps: {}", reviewed_steps.len());
+    if let Some(short_key) = options.key.as_deref() {
+        print_key_result(short_key, &command);
+    }
+    print_extra(options, &extra);
     Ok(())
 }
 
+/// 登録時に付ける説明とタグ、条件、作業ディレクトリをまとめる。
+fn collect_extra(options: &AddOptions, workdir: Option<String>) -> Result<CommandDetails, GclipError> {
+    let (description, tags) = collect_details(options)?;
+    Ok(CommandDetails {
+        description,
+        tags,
+        when: Some(options.when.clone()).filter(|condition| !condition.is_empty()),
+        cwd: workdir,
+        ..Default::default()
+    })
+}
+
+/// 付けた説明とタグ、条件、作業ディレクトリを表示する。
+fn print_extra(options: &AddOptions, extra: &CommandDetails) {
+    if extra.description.is_some() || !extra.tags.is_empty() {
+        print_details_result(extra.description.as_deref(), &extra.tags);
+    }
+    if options.clear_when || !options.when.is_empty() {
+        print_condition_result(&options.when);
+    }
+    if let Some(dir) = extra.cwd.as_deref() {
+        print_workdir_result(dir);
+    }
+}
+
+/// 標準入力から複数のコマンドをまとめて登録する。
+///
+/// `gclip add -` で実行し、1行に1件（`-0` ならNUL区切り）で読み込む。
+/// `history` の番号付き出力などの履歴は、書式（`--history-format`、省略時は内容から推測）に従って解析する。
+/// 各コマンドは登録時と同じく正規化し、登録と付加情報の設定は1回の書き込みで行う。
+/// 結果は追加・重複・不正の件数でまとめて表示する。
+pub fn run_bulk(
+    nul: bool,
+    format: Option<HistoryFormat>,
+    options: &AddOptions,
+) -> Result<(), GclipError> {
+    if options.key.is_some() || options.ask || options.clear_when {
+        return Err(GclipError::InvalidInput(
+            "--key, --ask and --clear-when cannot be used when adding from stdin".to_string(),
+        ));
+    }
+
+    let workdir = resolve_workdir(options)?;
+    let input = bulk::read_stdin()?;
+    let entries = bulk::parse_entries(&input, nul, format);
+    let registry = Registry::open()?;
+    let classified = bulk::classify_entries(&entries, &registry.list_commands()?);
+
+    let config = Config::load()?;
+    let mut commands = Vec::new();
+    let mut skipped = 0;
+    for command in &classified.accepted {
+        match secret::review_for_registration(command, config.secrets.mode) {
+            Ok(Some(reviewed)) => commands.push(reviewed),
+            Ok(None) | Err(GclipError::Refused(_)) => skipped += 1,
+            Err(err) => return Err(err),
+        }
+    }
+
+    let extra = collect_extra(options, workdir)?;
+    let (registry_path, added) = registry.register_commands_with_details(&commands, &extra)?;
+    let duplicate = classified.duplicate + (commands.len() - added);
+    print_bulk_result(added, duplicate, classified.invalid, skipped, &registry_path);
+    Ok(())
+}
+
+/// 履歴の末尾から `nth` 番目のコマンドを登録する。
+///
+/// `gclip add --last [N]` で実行する。gclip自身の呼び出しは数えない。
+/// 引用符などを含めて履歴どおりのコマンドを表示し、確認してから登録する。
+pub fn run_last(nth: usize, options: &AddOptions) -> Result<(), GclipError> {
+    let command = last::last_command_from_history(nth)?;
+    if !options.assume_yes {
+        eprintln!("Command from history:");
+        eprintln!("  {}", display_safe(&command));
+        if !selection::confirm("Register it?")? {
+            return Err(GclipError::Cancelled);
+        }
+    }
+    run(&command, options)
+}
+
+/// 説明とタグを決める。
+///
+/// `--ask` の場合、フラグで指定されていないものだけを入力してもらう。
+fn collect_details(options: &AddOptions) -> Result<(Option<String>, Vec<String>), GclipError> {
+    let mut description = options
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string);
+    let mut tags = normalize_tags(&options.tags);
+    if !options.ask {
+        return Ok((description, tags));
+    }
+
+    if description.is_none() {
+        let input = selection::prompt_line("Description (empty to skip)")?;
+        description = Some(input.trim().to_string()).filter(|text| !text.is_empty());
+    }
+    if tags.is_empty() {
+        let input = selection::prompt_line("Tags (comma-separated, empty to skip)")?;
+        tags = normalize_tags(&[input]);
+    }
+    Ok((description, tags))
+}
+
+/// `--cwd` のディレクトリを絶対パスにする。
+///
+/// 存在しないディレクトリや、UTF-8で表せないパスはエラーにする。
+fn resolve_workdir(options: &AddOptions) -> Result<Option<String>, GclipError> {
+    let Some(dir) = &options.cwd else {
+        return Ok(None);
+    };
+    let absolute = path::absolute(dir)
+        .map_err(|err| GclipError::io(&format!("failed to resolve {}", dir.display()), err))?;
+    if !absolute.is_dir() {
+        return Err(GclipError::InvalidInput(format!(
+            "not a directory: {}",
+            absolute.display()
+        )));
+    }
+    let normalized: PathBuf = absolute.components().collect();
+    normalized
+        .to_str()
+        .map(|text| Some(text.to_string()))
+        .ok_or_else(|| {
+            GclipError::InvalidInput(format!("directory is not valid UTF-8: {}", normalized.display()))
+        })
+}
+
 /// 登録結果を標準出力へ表示する。
 ///
 /// 追加件数と登録先を明示する。
 fn print_result(command: &str, added: usize, registry_path: &Path) {
+    let shown = display_safe(command);
     if added == 0 {
-        println!("Already registered: \"{command}\"");
+        println!("Already registered: \"{shown}\"");
     } else {
-        println!("Registered \"{command}\" to {}", registry_path.display());
+        println!("Registered \"{shown}\" to {}", registry_path.display());
+    }
+}
+
+/// 短縮キーの割り当て結果を標準出力へ表示する。
+fn print_key_result(key: &str, command: &str) {
+    println!(
+        "Assigned key \"{}\" to \"{}\"",
+        key.trim(),
+        display_safe(command.trim())
+    );
+}
+
+/// 一括登録の結果を標準出力へ表示する。
+fn print_bulk_result(
+    added: usize,
+    duplicate: usize,
+    invalid: usize,
+    skipped: usize,
+    registry_path: &Path,
+) {
+    println!("Added {added} command(s) to {}", registry_path.display());
+    println!("Duplicate: {duplicate}, invalid: {invalid}");
+    if skipped > 0 {
+        println!("Skipped (secrets): {skipped}");
+    }
+}
+
+/// 説明とタグの設定結果を標準出力へ表示する。
+fn print_details_result(description: Option<&str>, tags: &[String]) {
+    if let Some(text) = description {
+        println!("Description: {}", display_safe(text));
+    }
+    if !tags.is_empty() {
+        println!("Tags: {}", display_safe(&tags.join(", ")));
     }
 }
+
+/// 設定した条件を標準出力へ表示する。
+fn print_condition_result(condition: &Condition) {
+    if condition.is_empty() {
+        println!("When: always");
+        return;
+    }
+    println!("When: {}", display_safe(&condition.to_string()));
+}
+
+/// 作業ディレクトリの設定結果を標準出力へ表示する。
+fn print_workdir_result(dir: &str) {
+    println!("Runs in: {}", display_safe(dir));
+}
+
+/// 登録をスキップした場合のメッセージを標準エラーに出力する。
+fn print_skipped() {
+    eprintln!("Skipped. Nothing was registered.");
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,43 @@
+use super::super::bulk::{classify_entries, parse_entries, split_entries, BulkInput};
+use crate::history::HistoryFormat;
+
+#[test]
+fn split_entries_handles_lines_and_nul() {
+    // 行区切りではCRLFも扱い、末尾の区切りの後ろは数えない。
+    assert_eq!(split_entries("ls\r\n\npwd\n", false), vec!["ls", "", "pwd"]);
+    assert_eq!(split_entries("echo 'a\nb'\0ls\0", true), vec!["echo 'a\nb'", "ls"]);
+    assert!(split_entries("", false).is_empty());
+}
+
+#[test]
+fn classify_counts_duplicates_and_invalid() {
+    // 登録済みと入力内の重複は重複、空は不正として数える。
+    let registered = vec!["ls".to_string()];
+    let entries = ["  docker ps ", "ls", "", "docker ps", "docker images"].map(String::from);
+    assert_eq!(
+        classify_entries(&entries, &registered),
+        BulkInput {
+            accepted: vec!["docker ps".to_string(), "docker images".to_string()],
+            duplicate: 2,
+            invalid: 1,
+        }
+    );
+}
+
+#[test]
+fn numbered_history_output_is_stripped() {
+    // `history` の出力は番号を取り除き、書式を指定すればそれに従って解析する。
+    assert_eq!(
+        parse_entries("  501  docker ps\n  502* git status\n", false, None),
+        vec!["docker ps", "git status"]
+    );
+    assert_eq!(
+        parse_entries("501  docker ps\n", false, Some(HistoryFormat::Plain)),
+        vec!["501  docker ps"]
+    );
+    assert_eq!(
+        parse_entries(": 1700000000:0;make test\n", false, None),
+        vec!["make test"]
+    );
+    assert_eq!(parse_entries("ls\n\npwd\n", false, None), vec!["ls", "", "pwd"]);
+}
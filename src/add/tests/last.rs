@@ -0,0 +1,22 @@
+use super::super::last::{is_gclip_invocation, pick_last_command};
+
+#[test]
+fn gclip_invocations_are_detected() {
+    // 環境変数の代入やパス指定があってもgclip自身の呼び出しと判定する。
+    assert!(is_gclip_invocation("gclip add --last"));
+    assert!(is_gclip_invocation("HOME=/tmp command /usr/local/bin/gclip ls"));
+    assert!(!is_gclip_invocation("git status"));
+    assert!(!is_gclip_invocation("echo gclip"));
+}
+
+#[test]
+fn pick_last_skips_gclip_and_counts_from_end() {
+    // 末尾から数え、gclip自身の呼び出しは数えないことを確認する。
+    let commands: Vec<String> = ["ls", "git status", "gclip add --last", "make test", "gclip add --last 2"]
+        .map(String::from)
+        .to_vec();
+    assert_eq!(pick_last_command(&commands, 1), Some("make test"));
+    assert_eq!(pick_last_command(&commands, 2), Some("git status"));
+    assert_eq!(pick_last_command(&commands, 4), None);
+    assert_eq!(pick_last_command(&commands, 0), None);
+}
@@ -0,0 +1,2 @@
+mod last;
+mod bulk;
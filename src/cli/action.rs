@@ -0,0 +1,203 @@
+use super::command::{self, Command, FormatArgs};
+use super::Cli;
+use crate::add::AddOptions;
+use crate::history::HistoryFormat;
+use crate::output::OutputOptions;
+use crate::record::RecordOptions;
+use crate::suggest::HistorySource;
+
+/// 解析済みの引数から決まる実行内容。
+///
+/// サブコマンドと従来フラグのどちらで指定されても同じ値になるため、
+/// 呼び出し側はこの列挙だけを見て処理を振り分ければよい。
+#[derive(Debug)]
+pub enum Action {
+    Search { query: String, options: OutputOptions },
+    Recent { options: OutputOptions },
+    Add { command: String, options: AddOptions },
+    AddLast { nth: usize, options: AddOptions },
+    AddBulk { nul: bool, format: Option<HistoryFormat>, options: AddOptions },
+    AddWorkflow { steps: Vec<String>, options: AddOptions },
+    List { options: OutputOptions },
+    Remove { query: String, options: OutputOptions },
+    Suggest { options: OutputOptions, source: HistorySource },
+    Doctor { options: OutputOptions, fix: bool },
+    Stats { options: OutputOptions },
+    Prune { unused_for: Option<u64>, options: OutputOptions },
+    Restore { query: Option<String>, options: OutputOptions },
+    Workflow { cancel: bool },
+    Init { record: bool },
+    ZshWidget,
+    ExportAliases { shell: String },
+    Completions { shell: String },
+    Complete { word: String },
+    Serve,
+    Record { command: String, options: RecordOptions },
+}
+
+impl Cli {
+    /// 解析済みの引数を実行内容へ変換する。
+    ///
+    /// サブコマンドを優先し、なければ従来フラグ、検索クエリの順に見る。
+    /// どれもなければ直近使用コマンドからの選択になる。
+    pub fn into_action(self) -> Action {
+        if let Some(subcommand) = self.command {
+            return from_command(subcommand);
+        }
+
+        let options = command::output_options(&self.format, &self.select);
+        if self.init {
+            Action::Init { record: false }
+        } else if self.zsh_widget {
+            Action::ZshWidget
+        } else if let Some(shell) = self.completions {
+            Action::Completions { shell }
+        } else if let Some(word) = self.complete {
+            Action::Complete { word }
+        } else if self.serve {
+            Action::Serve
+        } else if self.suggest {
+            Action::Suggest {
+                options,
+                source: HistorySource {
+                    path: self.history,
+                    format: self.history_format,
+                },
+            }
+        } else if self.doctor {
+            Action::Doctor {
+                options,
+                fix: self.fix,
+            }
+        } else if self.stats {
+            Action::Stats { options }
+        } else if self.prune {
+            Action::Prune {
+                unused_for: self.unused_for,
+                options,
+            }
+        } else if self.list {
+            Action::List { options }
+        } else if let Some(query) = self.remove {
+            Action::Remove { query, options }
+        } else if let Some(shell) = self.export_aliases {
+            Action::ExportAliases { shell }
+        } else if let Some(nth) = self.add_last {
+            Action::AddLast {
+                nth,
+                options: AddOptions {
+                    assume_yes: self.select.yes,
+                    ..Default::default()
+                },
+            }
+        } else if self.add.as_deref() == Some("-") {
+            Action::AddBulk {
+                nul: self.null,
+                format: self.history_format,
+                options: AddOptions::default(),
+            }
+        } else if let Some(command) = self.add {
+            Action::Add {
+                command,
+                options: AddOptions {
+                    key: self.key,
+                    ..Default::default()
+                },
+            }
+        } else if let Some(query) = self.query {
+            Action::Search { query, options }
+        } else {
+            Action::Recent { options }
+        }
+    }
+}
+
+/// サブコマンドを実行内容へ変換する。
+fn from_command(subcommand: Command) -> Action {
+    match subcommand {
+        Command::Add(args) => {
+            let options = AddOptions {
+                when: args.to_condition(),
+                clear_when: args.clear_when,
+                cwd: args.cwd,
+                key: args.key,
+                description: args.description,
+                tags: args.tags,
+                ask: args.ask,
+                assume_yes: args.yes,
+            };
+            if !args.steps.is_empty() {
+                return Action::AddWorkflow {
+                    steps: args.steps,
+                    options,
+                };
+            }
+            match (args.last, args.command) {
+                (Some(nth), _) => Action::AddLast { nth, options },
+                (None, Some(command)) if command == "-" => Action::AddBulk {
+                    nul: args.null,
+                    format: args.history_format,
+                    options,
+                },
+                (None, command) => Action::Add {
+                    command: command.unwrap_or_default(),
+                    options,
+                },
+            }
+        }
+        Command::Ls(format) => Action::List {
+            options: OutputOptions {
+                format: format.to_format(),
+                ..Default::default()
+            },
+        },
+        Command::Rm(args) => Action::Remove {
+            query: args.query,
+            options: command::output_options(&FormatArgs::default(), &args.select),
+        },
+        Command::Suggest(args) => Action::Suggest {
+            options: command::output_options(&args.format, &args.select),
+            source: args.to_source(),
+        },
+        Command::Doctor(args) => Action::Doctor {
+            options: OutputOptions {
+                format: args.format.to_format(),
+                ..Default::default()
+            },
+            fix: args.fix,
+        },
+        Command::Stats(format) => Action::Stats {
+            options: OutputOptions {
+                format: format.to_format(),
+                ..Default::default()
+            },
+        },
+        Command::Prune(args) => Action::Prune {
+            unused_for: args.unused_for,
+            options: command::output_options(&FormatArgs::default(), &args.select),
+        },
+        Command::Restore(args) => Action::Restore {
+            query: args.query,
+            options: command::output_options(&FormatArgs::default(), &args.select),
+        },
+        Command::Workflow(args) => Action::Workflow {
+            cancel: args.cancel,
+        },
+        Command::Init(args) => Action::Init {
+            record: args.record,
+        },
+        Command::Widget => Action::ZshWidget,
+        Command::ExportAliases(args) => Action::ExportAliases { shell: args.shell },
+        Command::Completions(args) => Action::Completions { shell: args.shell },
+        Command::Complete(args) => Action::Complete { word: args.word },
+        Command::Serve => Action::Serve,
+        Command::Record(args) => Action::Record {
+            command: args.command,
+            options: RecordOptions {
+                cwd: args.cwd,
+                exit: args.exit,
+                duration_ms: args.duration,
+            },
+        },
+    }
+}
@@ -0,0 +1,404 @@
+use clap::{Args, Subcommand};
+use std::path::PathBuf;
+
+use crate::history::HistoryFormat;
+use crate::output::{OutputFormat, OutputOptions};
+use crate::registry::Condition;
+use crate::suggest::HistorySource;
+
+/// サブコマンドの定義。
+///
+/// 各サブコマンドは自身のオプションだけを持ち、
+/// 従来の `--add` などのフラグは `Cli` 側に隠しオプションとして残す。
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// コマンド文字列を手動で登録する。
+    #[command(about = "Add a command to the registry", long_about = None)]
+    Add(AddArgs),
+
+    /// 登録済みコマンドを一覧表示する。
+    #[command(visible_alias = "list", about = "List registered commands", long_about = None)]
+    Ls(FormatArgs),
+
+    /// 登録済みコマンドを部分一致検索し、選択して削除する。
+    #[command(visible_alias = "remove", about = "Remove a command from the registry", long_about = None)]
+    Rm(RemoveArgs),
+
+    /// 直近のコマンド履歴から頻出のものを推薦し、登録まで行う。
+    #[command(about = "Recommend frequently used commands from recent history", long_about = None)]
+    Suggest(SuggestArgs),
+
+    /// 設定/保存場所と整合性を確認する。
+    #[command(about = "Show configuration paths and registry integrity", long_about = None)]
+    Doctor(DoctorArgs),
+
+    /// 登録済みコマンドの使用状況を集計して表示する。
+    #[command(about = "Show usage statistics for registered commands", long_about = None)]
+    Stats(FormatArgs),
+
+    /// 使われなくなった登録済みコマンドをゴミ箱へ移す。
+    #[command(about = "Move unused or broken commands to the trash", long_about = None)]
+    Prune(PruneArgs),
+
+    /// ゴミ箱に移したコマンドを登録し直す。
+    #[command(about = "Restore commands moved to the trash by prune", long_about = None)]
+    Restore(RestoreArgs),
+
+    /// 1段階ずつ挿入している途中のワークフローを表示、または破棄する。
+    #[command(about = "Show or cancel the workflow being inserted step by step", long_about = None)]
+    Workflow(WorkflowArgs),
+
+    /// セットアップ用のスクリプトを出力する。
+    #[command(about = "Print a setup script for shell initialization", long_about = None)]
+    Init(InitArgs),
+
+    /// zsh用の挿入ウィジェットを出力する。
+    #[command(about = "Print a zsh widget script for line insertion", long_about = None)]
+    Widget,
+
+    /// 短縮キー付きのコマンドをエイリアスや関数の定義として出力する。
+    #[command(about = "Print aliases (or functions) for keyed commands", long_about = None)]
+    ExportAliases(ShellArgs),
+
+    /// シェル補完スクリプトを出力する。
+    #[command(about = "Print a shell completion script", long_about = None)]
+    Completions(ShellArgs),
+
+    /// 標準入出力で行区切りJSONのリクエストを処理し続ける。
+    #[command(about = "Serve line-delimited JSON requests on stdin/stdout", long_about = None)]
+    Serve,
+
+    /// 補完スクリプトから呼び出す動的補完の入口。
+    #[command(hide = true, about = "Print completion candidates for a word", long_about = None)]
+    Complete(CompleteArgs),
+
+    /// `gclip init --record` のフックから実行したコマンドを記録する入口。
+    #[command(hide = true, about = "Record an executed command (used by the init hooks)", long_about = None)]
+    Record(RecordArgs),
+}
+
+/// `gclip add` のオプション。
+#[derive(Args, Debug)]
+pub struct AddArgs {
+    /// 登録するコマンド文字列。
+    #[arg(
+        value_name = "COMMAND",
+        help = "Command to add",
+        required_unless_present_any = ["last", "steps"],
+        conflicts_with_all = ["last", "steps"]
+    )]
+    pub command: Option<String>,
+
+    /// ワークフローの手順。指定した順に2つ以上並べる。
+    #[arg(
+        long = "step",
+        value_name = "COMMAND",
+        conflicts_with = "last",
+        help = "Add a workflow made of these steps, in order (repeat for each step)"
+    )]
+    pub steps: Vec<String>,
+
+    /// 履歴の末尾からN番目（省略時は1）のコマンドを登録する。
+    #[arg(
+        long = "last",
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "1",
+        help = "Add the last (or Nth-from-last) command from history, skipping gclip itself"
+    )]
+    pub last: Option<usize>,
+
+    /// 登録するコマンドに短縮キーを割り当てる。
+    #[arg(short = 'k', long = "key", value_name = "KEY", help = "Assign a short key to the command")]
+    pub key: Option<String>,
+
+    /// 登録するコマンドに説明を付ける。
+    #[arg(long = "description", value_name = "TEXT", help = "Describe what the command does")]
+    pub description: Option<String>,
+
+    /// 登録するコマンドにタグを付ける。複数指定やカンマ区切りもできる。
+    #[arg(short = 't', long = "tag", value_name = "TAG", help = "Tag the command (repeatable, or comma-separated)")]
+    pub tags: Vec<String>,
+
+    /// 候補として有効にする条件。`KIND=VALUE` の形で、複数指定するとすべてを満たすときに有効になる。
+    ///
+    /// 例: `--when cwd=~/src/infra`、`--when file=Cargo.toml`、`--when env=KUBECONFIG`、`--when host=work-*`
+    #[arg(
+        long = "when",
+        value_name = "KIND=VALUE",
+        help = "Only offer the command when cwd=GLOB, file=NAME (in an ancestor), env=VAR or host=GLOB holds (repeatable)",
+        long_help = None
+    )]
+    pub when: Vec<Condition>,
+
+    /// 登録済みの条件を消す。`--when` と一緒に指定すると、条件を置き換える。
+    #[arg(
+        long = "clear-when",
+        help = "Remove the command's conditions (with --when, replace them)"
+    )]
+    pub clear_when: bool,
+
+    /// コマンドを実行する作業ディレクトリ。別のディレクトリで挿入すると `cd DIR && ` を付ける。
+    ///
+    /// 相対パスは絶対パスにして保存する。カレントディレクトリなら `--cwd .` とする。
+    #[arg(
+        long = "cwd",
+        value_name = "DIR",
+        help = "Bind the command to DIR; inserting it elsewhere prefixes `cd DIR && `",
+        long_help = None
+    )]
+    pub cwd: Option<PathBuf>,
+
+    /// 説明とタグを対話的に入力する。
+    #[arg(long = "ask", help = "Prompt for a description and tags")]
+    pub ask: bool,
+
+    /// `-` で標準入力から読む際に、NUL区切りとして扱う。
+    #[arg(short = '0', long = "null", help = "With COMMAND '-', read NUL-separated commands from stdin")]
+    pub null: bool,
+
+    /// `-` で標準入力から読む際の履歴の書式。省略時は内容から推測する。
+    #[arg(
+        long = "history-format",
+        value_name = "FORMAT",
+        conflicts_with = "null",
+        help = "With COMMAND '-', parse stdin as zsh, bash, fish, numbered or plain history (detected if omitted)"
+    )]
+    pub history_format: Option<HistoryFormat>,
+
+    /// 履歴から登録する際の確認を省略する。
+    #[arg(short = 'y', long = "yes", help = "Register without confirming the command from history")]
+    pub yes: bool,
+}
+
+/// `gclip init` のオプション。
+#[derive(Args, Debug)]
+pub struct InitArgs {
+    /// 実行したコマンドを終了コードと所要時間つきで記録するフックも出力する。
+    #[arg(long = "record", help = "Also install preexec/precmd hooks that record exit status and duration")]
+    pub record: bool,
+}
+
+/// `gclip record` のオプション。
+#[derive(Args, Debug)]
+pub struct RecordArgs {
+    /// 実行したコマンド。
+    #[arg(value_name = "COMMAND", help = "The executed command")]
+    pub command: String,
+
+    /// 実行したディレクトリ。
+    #[arg(long = "cwd", value_name = "DIR", help = "Directory the command ran in")]
+    pub cwd: Option<String>,
+
+    /// 終了コード。
+    #[arg(
+        long = "exit",
+        value_name = "STATUS",
+        default_value_t = 0,
+        allow_negative_numbers = true,
+        help = "Exit status of the command"
+    )]
+    pub exit: i32,
+
+    /// 所要時間（ミリ秒）。
+    #[arg(long = "duration", value_name = "MS", default_value_t = 0, help = "Duration in milliseconds")]
+    pub duration: u64,
+}
+
+/// `gclip rm` のオプション。
+#[derive(Args, Debug)]
+pub struct RemoveArgs {
+    /// 削除候補を絞り込む部分一致の検索語。
+    #[arg(value_name = "QUERY", help = "Search registered commands by substring")]
+    pub query: String,
+
+    #[command(flatten)]
+    pub select: SelectArgs,
+}
+
+/// `gclip doctor` のオプション。
+#[derive(Args, Debug)]
+pub struct DoctorArgs {
+    /// 見つかった問題を修復してから確認する。
+    #[arg(long = "fix", help = "Repair the registry and recent files before checking")]
+    pub fix: bool,
+
+    #[command(flatten)]
+    pub format: FormatArgs,
+}
+
+/// `gclip restore` のオプション。
+#[derive(Args, Debug)]
+pub struct RestoreArgs {
+    #[arg(value_name = "QUERY", help = "Only offer trashed commands containing this substring")]
+    pub query: Option<String>,
+
+    #[command(flatten)]
+    pub select: SelectArgs,
+}
+
+/// `gclip workflow` のオプション。
+#[derive(Args, Debug)]
+pub struct WorkflowArgs {
+    /// 途中のワークフローを破棄する。
+    #[arg(long = "cancel", help = "Stop the workflow so that gclip shows recent commands again")]
+    pub cancel: bool,
+}
+
+/// `gclip prune` のオプション。
+#[derive(Args, Debug)]
+pub struct PruneArgs {
+    /// この期間に挿入されていないコマンドを候補にする。
+    #[arg(
+        long = "unused-for",
+        value_name = "PERIOD",
+        value_parser = crate::prune::parse_days,
+        help = "Also prune commands not inserted for this period (e.g. 90d, 12w, 6m)"
+    )]
+    pub unused_for: Option<u64>,
+
+    #[command(flatten)]
+    pub select: SelectArgs,
+}
+
+/// `gclip suggest` のオプション。
+#[derive(Args, Debug)]
+pub struct SuggestArgs {
+    /// 履歴の読み込み元。`-` なら標準入力から読む。
+    ///
+    /// 例: `fc -ln 1 | gclip suggest --history -`
+    #[arg(
+        long = "history",
+        value_name = "PATH",
+        help = "Read history from PATH instead of $HISTFILE or ~/.zsh_history ('-' for stdin)",
+        long_help = None
+    )]
+    pub history: Option<PathBuf>,
+
+    /// 履歴の書式。省略時は内容から推測する。
+    #[arg(
+        long = "history-format",
+        value_name = "FORMAT",
+        help = "History format: zsh, bash, fish, numbered or plain (detected if omitted)"
+    )]
+    pub history_format: Option<HistoryFormat>,
+
+    #[command(flatten)]
+    pub format: FormatArgs,
+
+    #[command(flatten)]
+    pub select: SelectArgs,
+}
+
+/// シェル名だけを受け取るサブコマンドのオプション。
+#[derive(Args, Debug)]
+pub struct ShellArgs {
+    /// 出力先のシェル。
+    #[arg(value_name = "SHELL", value_parser = ["zsh", "bash", "fish"], help = "Target shell (zsh, bash or fish)")]
+    pub shell: String,
+}
+
+/// `gclip complete` のオプション。
+#[derive(Args, Debug)]
+pub struct CompleteArgs {
+    /// 入力中の語。省略時は全件を候補にする。
+    #[arg(value_name = "WORD", default_value = "", allow_hyphen_values = true, help = "Word being completed")]
+    pub word: String,
+}
+
+/// 機械処理向けの出力形式を選ぶオプション。
+///
+/// 一覧や結果を出力するサブコマンドで共通に使う。
+#[derive(Args, Debug, Default)]
+pub struct FormatArgs {
+    /// 一覧や結果をJSONで出力する。
+    #[arg(long = "json", help = "Print results as JSON instead of prompting", conflicts_with_all = ["print0", "format"])]
+    pub json: bool,
+
+    /// 一覧や結果のコマンドをNUL区切りで出力する。
+    #[arg(long = "print0", help = "Print commands separated by NUL instead of prompting", conflicts_with_all = ["json", "format"])]
+    pub print0: bool,
+
+    /// 一覧や結果をテンプレートで整形して出力する。
+    ///
+    /// 例: `--format '{index}\t{command}'`
+    #[arg(
+        long = "format",
+        value_name = "TEMPLATE",
+        help = "Print each result with a template such as '{index}\\t{command}'",
+        conflicts_with_all = ["json", "print0"],
+        long_help = None
+    )]
+    pub format: Option<String>,
+}
+
+/// 選択プロンプトを省略するためのオプション。
+#[derive(Args, Debug, Default)]
+pub struct SelectArgs {
+    /// 選択プロンプトの代わりに番号を指定する。
+    ///
+    /// 例: `--select 2`、`gclip suggest --select 1,3`
+    #[arg(
+        long = "select",
+        value_name = "N",
+        help = "Select by number instead of prompting",
+        conflicts_with = "first",
+        long_help = None
+    )]
+    pub select: Option<String>,
+
+    /// 選択プロンプトを出さずに先頭を選ぶ。
+    #[arg(long = "first", help = "Select the first result instead of prompting")]
+    pub first: bool,
+
+    /// 危険なコマンドの確認などを省略し、同意したものとして扱う。
+    #[arg(short = 'y', long = "yes", help = "Answer yes to confirmation prompts")]
+    pub yes: bool,
+}
+
+impl AddArgs {
+    /// `--when` の指定を1つの条件にまとめる。
+    pub fn to_condition(&self) -> Condition {
+        self.when.iter().fold(Condition::default(), |mut merged, condition| {
+            merged.merge(condition);
+            merged
+        })
+    }
+}
+
+impl SuggestArgs {
+    /// `--history` / `--history-format` を履歴の読み込み元にまとめる。
+    pub fn to_source(&self) -> HistorySource {
+        HistorySource {
+            path: self.history.clone(),
+            format: self.history_format,
+        }
+    }
+}
+
+impl FormatArgs {
+    /// フラグから出力形式を決める。
+    pub fn to_format(&self) -> OutputFormat {
+        OutputFormat::from_flags(self.json, self.print0, self.format.clone())
+    }
+}
+
+impl SelectArgs {
+    /// `--select` / `--first` を選択入力の文字列にまとめる。
+    ///
+    /// `--first` は `--select 1` と同じ扱いにする。
+    pub fn to_select(&self) -> Option<String> {
+        self.select
+            .clone()
+            .or_else(|| self.first.then(|| "1".to_string()))
+    }
+}
+
+/// 出力形式と選択の指定をまとめる。
+pub(crate) fn output_options(format: &FormatArgs, select: &SelectArgs) -> OutputOptions {
+    OutputOptions {
+        format: format.to_format(),
+        select: select.to_select(),
+        assume_yes: select.yes,
+    }
+}
@@ -1,18 +1,38 @@
-use clap::Parser;
+mod action;
+mod command;
+
+use clap::{ArgGroup, Parser};
+use std::path::PathBuf;
+
+use crate::history::HistoryFormat;
+
+pub use action::Action;
+pub use command::{
+    AddArgs, Command, CompleteArgs, DoctorArgs, FormatArgs, InitArgs, PruneArgs, RecordArgs, RemoveArgs,
+    RestoreArgs, SelectArgs, ShellArgs, SuggestArgs, WorkflowArgs,
+};
 
 /// CLIの引数定義をまとめるモジュール。
 ///
 /// `clap`の自動ヘルプ生成により、
 /// `gclip --help`でこの構造体の定義内容が表示される。
 /// そのため、ヘルプの表現はここで集中管理する。
+//
+// 機能ごとの入口はサブコマンド（`gclip add` など）とし、
+// 従来の `--add` などのフラグは既存のシェル設定を壊さないよう隠しオプションとして残す。
+// 検索クエリと従来フラグは `mode` グループで互いに排他にする。
 #[derive(Parser, Debug)]
-#[command(name = "gclip", version, about = "Clipboard helper CLI")]
+#[command(
+    name = "gclip",
+    version,
+    about = "Clipboard helper CLI",
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    group(ArgGroup::new("mode").multiple(false))
+)]
 pub struct Cli {
-    /// 直近のコマンド履歴から頻出のものを推薦し、登録まで行う。
-    ///
-    /// `--suggest`が指定されたときのみ履歴を読み取って処理する。
-    #[arg(short = 's', long, help = "Recommend frequently used commands from recent history")]
-    pub suggest: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
 
     /// 登録済みコマンドから部分一致で検索する。
     ///
@@ -20,65 +40,186 @@ pub struct Cli {
     #[arg(
         value_name = "QUERY",
         help = "Search registered commands by substring",
-        conflicts_with_all = ["add", "suggest", "list", "remove", "zsh_widget", "init", "doctor"]
+        group = "mode",
+        long_help = None
     )]
     pub query: Option<String>,
 
-    /// コマンド文字列を手動で登録する。
-    ///
-    /// 例: `gclip --add "git status"` で登録する。
+    #[command(flatten)]
+    pub format: FormatArgs,
+
+    #[command(flatten)]
+    pub select: SelectArgs,
+
+    /// `gclip suggest` の従来フラグ。
+    #[arg(
+        short = 's',
+        long = "suggest",
+        hide = true,
+        group = "mode",
+        help = "Legacy form of `gclip suggest`"
+    )]
+    pub suggest: bool,
+
+    /// `gclip suggest --history` の従来フラグ。
+    #[arg(
+        long = "history",
+        value_name = "PATH",
+        hide = true,
+        requires = "suggest",
+        help = "Legacy form of `gclip suggest --history`"
+    )]
+    pub history: Option<PathBuf>,
+
+    /// `gclip suggest --history-format` の従来フラグ。
+    #[arg(
+        long = "history-format",
+        value_name = "FORMAT",
+        hide = true,
+        requires = "suggest",
+        help = "Legacy form of `gclip suggest --history-format`"
+    )]
+    pub history_format: Option<HistoryFormat>,
+
+    /// `gclip add` の従来フラグ。
     #[arg(
         short = 'a',
         long = "add",
         value_name = "COMMAND",
-        help = "Add a command to the registry",
-        conflicts_with_all = ["query", "suggest", "list", "remove", "zsh_widget", "init", "doctor"]
+        hide = true,
+        group = "mode",
+        help = "Legacy form of `gclip add`"
     )]
     pub add: Option<String>,
 
-    /// 登録済みコマンドを一覧表示する。
-    ///
-    /// 例: `gclip --list` で一覧表示する。
+    /// `gclip add - -0` の従来フラグ。
     #[arg(
-        short = 'l',
-        long = "list",
-        help = "List registered commands",
-        conflicts_with_all = ["query", "add", "suggest", "remove", "zsh_widget", "init", "doctor"]
+        short = '0',
+        long = "null",
+        hide = true,
+        requires = "add",
+        help = "Legacy form of `gclip add - -0`"
     )]
+    pub null: bool,
+
+    /// `gclip add --last` の従来フラグ。
+    #[arg(
+        long = "add-last",
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "1",
+        hide = true,
+        group = "mode",
+        help = "Legacy form of `gclip add --last`"
+    )]
+    pub add_last: Option<usize>,
+
+    /// `gclip add --key` の従来フラグ。
+    #[arg(
+        short = 'k',
+        long = "key",
+        value_name = "KEY",
+        hide = true,
+        requires = "add",
+        help = "Legacy form of `gclip add --key`"
+    )]
+    pub key: Option<String>,
+
+    /// `gclip ls` の従来フラグ。
+    #[arg(short = 'l', long = "list", hide = true, group = "mode", help = "Legacy form of `gclip ls`")]
     pub list: bool,
 
-    /// 登録済みコマンドを削除する。
-    ///
-    /// 例: `gclip --rm "git status"` で削除する。
+    /// `gclip rm` の従来フラグ。
     #[arg(
         short = 'r',
         long = "rm",
-        value_name = "COMMAND",
-        help = "Remove a command from the registry",
-        conflicts_with_all = ["query", "add", "suggest", "list", "zsh_widget", "init", "doctor"]
+        value_name = "QUERY",
+        hide = true,
+        group = "mode",
+        help = "Legacy form of `gclip rm`"
     )]
     pub remove: Option<String>,
 
-    /// 設定/保存場所の確認を行う。
-    ///
-    /// 例: `gclip --doctor` で現在のパスと整合性を確認する。
+    /// `gclip doctor` の従来フラグ。
+    #[arg(short = 'd', long = "doctor", hide = true, group = "mode", help = "Legacy form of `gclip doctor`")]
+    pub doctor: bool,
+
+    /// `gclip doctor --fix` の従来フラグ。
+    #[arg(long = "fix", hide = true, requires = "doctor", help = "Legacy form of `gclip doctor --fix`")]
+    pub fix: bool,
+
+    /// `gclip stats` の従来フラグ。
+    #[arg(long = "stats", hide = true, group = "mode", help = "Legacy form of `gclip stats`")]
+    pub stats: bool,
+
+    /// `gclip prune` の従来フラグ。
+    #[arg(long = "prune", hide = true, group = "mode", help = "Legacy form of `gclip prune`")]
+    pub prune: bool,
+
+    /// `gclip prune --unused-for` の従来フラグ。
     #[arg(
-        short = 'd',
-        long = "doctor",
-        help = "Show configuration paths and registry integrity",
-        conflicts_with_all = ["query", "add", "suggest", "list", "remove", "zsh_widget", "init"]
+        long = "unused-for",
+        value_name = "PERIOD",
+        value_parser = crate::prune::parse_days,
+        hide = true,
+        requires = "prune",
+        help = "Legacy form of `gclip prune --unused-for`"
     )]
-    pub doctor: bool,
+    pub unused_for: Option<u64>,
 
-    /// zsh用の挿入ウィジェットを出力する。
-    ///
-    /// `gclip --zsh-widget` の出力を `.zshrc` から読み込む。
-    #[arg(short = 'w', long, help = "Print a zsh widget script for line insertion")]
+    /// `gclip export-aliases` の従来フラグ。
+    #[arg(
+        long = "export-aliases",
+        value_name = "SHELL",
+        value_parser = ["zsh", "bash", "fish"],
+        hide = true,
+        group = "mode",
+        help = "Legacy form of `gclip export-aliases`"
+    )]
+    pub export_aliases: Option<String>,
+
+    /// `gclip serve` の従来フラグ。
+    #[arg(long = "serve", hide = true, group = "mode", help = "Legacy form of `gclip serve`")]
+    pub serve: bool,
+
+    /// `gclip completions` の従来フラグ。
+    #[arg(
+        long = "completions",
+        value_name = "SHELL",
+        value_parser = ["zsh", "bash", "fish"],
+        hide = true,
+        group = "mode",
+        help = "Legacy form of `gclip completions`"
+    )]
+    pub completions: Option<String>,
+
+    /// `gclip complete` の従来フラグ。
+    #[arg(
+        long = "complete",
+        value_name = "WORD",
+        hide = true,
+        num_args = 0..=1,
+        default_missing_value = "",
+        allow_hyphen_values = true,
+        group = "mode",
+        help = "Legacy form of `gclip complete`"
+    )]
+    pub complete: Option<String>,
+
+    /// `gclip widget` の従来フラグ。
+    #[arg(
+        short = 'w',
+        long = "zsh-widget",
+        hide = true,
+        group = "mode",
+        help = "Legacy form of `gclip widget`"
+    )]
     pub zsh_widget: bool,
 
-    /// セットアップ用のスクリプトを出力する。
-    ///
-    /// `gclip --init` の出力を `.zshrc` で評価する。
-    #[arg(short = 'i', long, help = "Print a setup script for shell initialization")]
+    /// `gclip init` の従来フラグ。
+    #[arg(short = 'i', long = "init", hide = true, group = "mode", help = "Legacy form of `gclip init`")]
     pub init: bool,
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,31 @@
+use super::super::Cli;
+use clap::CommandFactory;
+
+/// 日本語（ひらがな、カタカナ、漢字）を含むかを返す。
+fn has_japanese(text: &str) -> bool {
+    text.chars()
+        .any(|ch| matches!(ch, '\u{3040}'..='\u{30ff}' | '\u{4e00}'..='\u{9fff}'))
+}
+
+#[test]
+fn help_never_shows_doc_comments() {
+    // 開発者向けのdocコメントが `--help` や補完の説明に出ないことを確認する。
+    let mut root = Cli::command();
+    root.build();
+    let mut commands = vec![root.clone()];
+    commands.extend(root.get_subcommands().cloned());
+    for mut command in commands {
+        let name = command.get_name().to_string();
+        let help = command.render_long_help().to_string();
+        assert!(!has_japanese(&help), "{name} --help:\n{help}");
+        for arg in command.get_arguments() {
+            let texts = [arg.get_help(), arg.get_long_help()];
+            for text in texts.into_iter().flatten() {
+                assert!(!has_japanese(&text.to_string()), "{name} {:?}: {text}", arg.get_id());
+            }
+        }
+        if let Some(about) = command.get_about() {
+            assert!(!has_japanese(&about.to_string()), "{name}: {about}");
+        }
+    }
+}
@@ -0,0 +1,2 @@
+mod help;
+mod parse;
@@ -0,0 +1,251 @@
+use super::super::{Action, Cli};
+use crate::history::HistoryFormat;
+use clap::{CommandFactory, Parser};
+
+fn parse(args: &[&str]) -> Action {
+    Cli::try_parse_from(args)
+        .expect("arguments should parse")
+        .into_action()
+}
+
+#[test]
+fn subcommand_and_legacy_flag_resolve_to_same_action() {
+    // `gclip add` と従来の `--add` が同じ実行内容になることを確認する。
+    let subcommand = parse(&["gclip", "add", "git status", "--key", "gs"]);
+    let legacy = parse(&["gclip", "--add", "git status", "--key", "gs"]);
+    for action in [subcommand, legacy] {
+        match action {
+            Action::Add { command, options } => {
+                assert_eq!(command, "git status");
+                assert_eq!(options.key.as_deref(), Some("gs"));
+            }
+            other => panic!("unexpected action: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn prune_period_is_parsed_in_both_forms() {
+    // `gclip prune --unused-for` と従来の `--prune --unused-for` で期間が日数になる。
+    let subcommand = parse(&["gclip", "prune", "--unused-for", "90d", "--yes"]);
+    let legacy = parse(&["gclip", "--prune", "--unused-for", "12w"]);
+    match (subcommand, legacy) {
+        (
+            Action::Prune {
+                unused_for: Some(90),
+                options,
+            },
+            Action::Prune {
+                unused_for: Some(84),
+                ..
+            },
+        ) => assert!(options.assume_yes),
+        other => panic!("unexpected actions: {other:?}"),
+    }
+    assert!(Cli::try_parse_from(["gclip", "prune", "--unused-for", "soon"]).is_err());
+}
+
+#[test]
+fn restore_takes_optional_query() {
+    // `gclip restore` はクエリなしでも、クエリと `--select` 付きでも解析できる。
+    match parse(&["gclip", "restore"]) {
+        Action::Restore { query: None, .. } => {}
+        other => panic!("unexpected action: {other:?}"),
+    }
+    match parse(&["gclip", "restore", "docker", "--select", "1,2"]) {
+        Action::Restore { query, options } => {
+            assert_eq!(query.as_deref(), Some("docker"));
+            assert_eq!(options.select.as_deref(), Some("1,2"));
+        }
+        other => panic!("unexpected action: {other:?}"),
+    }
+}
+
+#[test]
+fn bare_word_is_search_query() {
+    // サブコマンド名でない単語は検索クエリとして扱う。
+    match parse(&["gclip", "git"]) {
+        Action::Search { query, .. } => assert_eq!(query, "git"),
+        other => panic!("unexpected action: {other:?}"),
+    }
+}
+
+#[test]
+fn no_arguments_is_recent() {
+    // 引数がなければ直近使用コマンドからの選択になる。
+    assert!(matches!(parse(&["gclip"]), Action::Recent { .. }));
+}
+
+#[test]
+fn subcommand_options_are_applied() {
+    // サブコマンド固有のオプションが反映されることを確認する。
+    match parse(&["gclip", "rm", "git", "--first"]) {
+        Action::Remove { query, options } => {
+            assert_eq!(query, "git");
+            assert_eq!(options.select.as_deref(), Some("1"));
+        }
+        other => panic!("unexpected action: {other:?}"),
+    }
+    match parse(&["gclip", "ls", "--json"]) {
+        Action::List { options } => assert!(!options.format.is_text()),
+        other => panic!("unexpected action: {other:?}"),
+    }
+}
+
+#[test]
+fn legacy_flags_conflict_with_each_other() {
+    // 従来フラグ同士や検索クエリとの併用はエラーにする。
+    assert!(Cli::try_parse_from(["gclip", "--list", "--doctor"]).is_err());
+    assert!(Cli::try_parse_from(["gclip", "git", "--list"]).is_err());
+    assert!(Cli::try_parse_from(["gclip", "--key", "gs"]).is_err());
+}
+
+#[test]
+fn command_definition_is_consistent() {
+    // clapの定義に矛盾（重複した短縮名など）がないことを確認する。
+    Cli::command().debug_assert();
+}
+
+#[test]
+fn add_details_are_collected() {
+    // 説明・複数のタグ・対話入力の指定が実行内容に渡ることを確認する。
+    let action = parse(&[
+        "gclip", "add", "--description", "Show status", "-t", "git", "--tag", "vcs", "--ask", "--",
+        "git status",
+    ]);
+    match action {
+        Action::Add { command, options } => {
+            assert_eq!(command, "git status");
+            assert_eq!(options.description.as_deref(), Some("Show status"));
+            assert_eq!(options.tags, vec!["git".to_string(), "vcs".to_string()]);
+            assert!(options.ask);
+        }
+        other => panic!("unexpected action: {other:?}"),
+    }
+}
+
+#[test]
+fn add_last_defaults_to_one_in_both_forms() {
+    // `--last` の値は省略すると1になり、従来の `--add-last` でも同じになる。
+    let cases = [
+        (vec!["gclip", "add", "--last"], 1),
+        (vec!["gclip", "add", "--last", "3", "--yes"], 3),
+        (vec!["gclip", "--add-last"], 1),
+        (vec!["gclip", "--add-last", "2"], 2),
+    ];
+    for (args, expected) in cases {
+        match parse(&args) {
+            Action::AddLast { nth, .. } => assert_eq!(nth, expected),
+            other => panic!("unexpected action: {other:?}"),
+        }
+    }
+    assert!(Cli::try_parse_from(["gclip", "add"]).is_err());
+    assert!(Cli::try_parse_from(["gclip", "add", "ls", "--last"]).is_err());
+}
+
+#[test]
+fn dash_reads_commands_from_stdin() {
+    // `-` は標準入力からの一括登録になり、`-0` でNUL区切りになる。
+    for (args, expected) in [
+        (vec!["gclip", "add", "-"], false),
+        (vec!["gclip", "add", "-", "-0", "--tag", "docker"], true),
+        (vec!["gclip", "--add", "-", "-0"], true),
+    ] {
+        match parse(&args) {
+            Action::AddBulk { nul, .. } => assert_eq!(nul, expected),
+            other => panic!("unexpected action: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn suggest_history_source_in_both_forms() {
+    // `gclip suggest --history` と従来の `--suggest --history` で読み込み元と書式が渡る。
+    let subcommand = parse(&["gclip", "suggest", "--history", "-", "--history-format", "bash"]);
+    let legacy = parse(&["gclip", "--suggest", "--history", "/tmp/history"]);
+    match (subcommand, legacy) {
+        (Action::Suggest { source: piped, .. }, Action::Suggest { source: file, .. }) => {
+            assert!(piped.is_stdin());
+            assert_eq!(piped.format, Some(HistoryFormat::Bash));
+            assert_eq!(file.path.as_deref(), Some(std::path::Path::new("/tmp/history")));
+            assert_eq!(file.format, None);
+        }
+        other => panic!("unexpected actions: {other:?}"),
+    }
+    assert!(Cli::try_parse_from(["gclip", "suggest", "--history-format", "csh"]).is_err());
+    assert!(Cli::try_parse_from(["gclip", "--history", "-"]).is_err());
+}
+
+#[test]
+fn record_hook_arguments_are_parsed() {
+    // フックからの `gclip record` と `gclip init --record` を受け付ける。
+    match parse(&["gclip", "record", "--exit", "-1", "--duration", "250", "--cwd", "/work", "--", "make -j"]) {
+        Action::Record { command, options } => {
+            assert_eq!(command, "make -j");
+            assert_eq!(options.exit, -1);
+            assert_eq!(options.duration_ms, 250);
+            assert_eq!(options.cwd.as_deref(), Some("/work"));
+        }
+        other => panic!("unexpected action: {other:?}"),
+    }
+    assert!(matches!(parse(&["gclip", "init", "--record"]), Action::Init { record: true }));
+    assert!(matches!(parse(&["gclip", "--init"]), Action::Init { record: false }));
+}
+
+#[test]
+fn add_when_conditions_are_merged() {
+    // 複数の `--when` は1つの条件にまとまり、不正な指定は拒否される。
+    match parse(&["gclip", "add", "kubectl get pods", "--when", "env=KUBECONFIG", "--when", "host=work-*"]) {
+        Action::Add { options, .. } => {
+            assert_eq!(options.when.env.as_deref(), Some("KUBECONFIG"));
+            assert_eq!(options.when.host.as_deref(), Some("work-*"));
+        }
+        other => panic!("unexpected action: {other:?}"),
+    }
+    assert!(Cli::try_parse_from(["gclip", "add", "ls", "--when", "os=linux"]).is_err());
+    match parse(&["gclip", "add", "ls", "--clear-when"]) {
+        Action::Add { options, .. } => {
+            assert!(options.clear_when);
+            assert!(options.when.is_empty());
+        }
+        other => panic!("unexpected action: {other:?}"),
+    }
+}
+
+#[test]
+fn add_cwd_is_passed_through() {
+    // `--cwd` はそのまま渡し、絶対パスへの変換は登録時に行う。
+    match parse(&["gclip", "add", "make deploy", "--cwd", "."]) {
+        Action::Add { options, .. } => {
+            assert_eq!(options.cwd.as_deref(), Some(std::path::Path::new(".")));
+        }
+        other => panic!("unexpected action: {other:?}"),
+    }
+}
+
+#[test]
+fn add_steps_make_a_workflow() {
+    // `--step` を並べるとワークフローとして登録し、コマンドや `--last` とは併用できない。
+    match parse(&["gclip", "add", "--step", "cargo build", "--step", "cargo test", "--key", "rel"]) {
+        Action::AddWorkflow { steps, options } => {
+            assert_eq!(steps, ["cargo build", "cargo test"]);
+            assert_eq!(options.key.as_deref(), Some("rel"));
+        }
+        other => panic!("unexpected action: {other:?}"),
+    }
+    assert!(Cli::try_parse_from(["gclip", "add", "ls", "--step", "make"]).is_err());
+    assert!(Cli::try_parse_from(["gclip", "add", "--last", "--step", "make"]).is_err());
+}
+
+#[test]
+fn workflow_cancel_is_parsed() {
+    // `gclip workflow --cancel` で途中のワークフローを破棄する。
+    assert!(matches!(
+        parse(&["gclip", "workflow", "--cancel"]),
+        Action::Workflow { cancel: true }
+    ));
+    assert!(matches!(
+        parse(&["gclip", "workflow"]),
+        Action::Workflow { cancel: false }
+    ));
+}
@@ -0,0 +1,79 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 1日の秒数。
+pub const SECONDS_PER_DAY: u64 = 86_400;
+
+/// 現在時刻をUNIX秒で返す。
+///
+/// システム時刻がエポックより前の場合は0とみなす。
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// UNIX秒をエポックからの日数に変換する。
+pub fn days_from_secs(secs: u64) -> i64 {
+    (secs / SECONDS_PER_DAY) as i64
+}
+
+/// エポックからの日数を `YYYY-MM-DD`（UTC）に変換する。
+pub fn format_days(days: i64) -> String {
+    let (year, month, day) = civil_from_days(days);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// UNIX秒を `YYYY-MM-DD`（UTC）に変換する。
+pub fn format_date(secs: u64) -> String {
+    format_days(days_from_secs(secs))
+}
+
+/// `YYYY-MM-DD` をエポックからの日数に変換する。
+///
+/// 形式が正しくない場合は `None` を返す。
+pub fn parse_date(date: &str) -> Option<i64> {
+    let mut parts = date.split('-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day))
+}
+
+/// 日数から暦日を求める（グレゴリオ暦、UTC）。
+///
+/// Howard Hinnantの `civil_from_days` のアルゴリズムに従う。
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let shifted = days + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// 暦日から日数を求める（`civil_from_days` の逆変換）。
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let adjusted_year = if month <= 2 { year - 1 } else { year };
+    let era = adjusted_year.div_euclid(400);
+    let year_of_era = adjusted_year.rem_euclid(400);
+    let month_index = i64::from(if month > 2 { month - 3 } else { month + 9 });
+    let day_of_year = (153 * month_index + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,25 @@
+use super::super::{format_date, format_days, parse_date};
+
+#[test]
+fn formats_known_dates() {
+    // エポックとうるう日の変換が正しいことを確認する。
+    assert_eq!(format_date(0), "1970-01-01");
+    assert_eq!(format_date(951_782_400), "2000-02-29");
+    assert_eq!(format_date(1_760_832_000), "2025-10-19");
+}
+
+#[test]
+fn parse_is_inverse_of_format() {
+    // 日付文字列と日数が相互に変換できることを確認する。
+    for days in [0, 11_016, 20_380, -1] {
+        assert_eq!(parse_date(&format_days(days)), Some(days));
+    }
+}
+
+#[test]
+fn rejects_invalid_dates() {
+    // 形式が異なる文字列は `None` になる。
+    assert_eq!(parse_date("2025-13-01"), None);
+    assert_eq!(parse_date("2025/10/19"), None);
+    assert_eq!(parse_date("2025-10-19-1"), None);
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use clap_complete::Shell;
+
+use crate::registry::CommandDetails;
+
+/// clapが生成したzshの補完関数の定義の先頭。
+const ZSH_STATIC_FUNCTION: &str = "\n_gclip() {\n";
+
+/// clapが生成したzshの補完スクリプトの末尾にある、関数を呼び出すか登録する部分。
+const ZSH_STATIC_DISPATCH: &str = "if [ \"$funcstack[1]\" = \"_gclip\" ]; then";
+
+/// 静的補完と動的補完を組み合わせた補完スクリプトを返す。
+///
+/// `rm` / `--rm` の値は `gclip --complete` の結果だけで補完し、
+/// 先頭の位置ではサブコマンド名に加えて登録済みコマンドも候補にする。
+/// それ以外はclapが生成した補完関数に委譲する。
+///
+/// zshでは `fpath` から読み込まれても動くよう、clapの関数を `_gclip_static` に改名し、
+/// `#compdef gclip` の `_gclip` を動的補完の関数にする。
+pub(super) fn complete_script(shell: Shell, static_script: &str) -> String {
+    match shell {
+        Shell::Zsh => zsh_script(static_script),
+        Shell::Bash => format!("{static_script}{BASH_DYNAMIC}"),
+        Shell::Fish => format!("{static_script}{FISH_DYNAMIC}"),
+        _ => static_script.to_string(),
+    }
+}
+
+/// zshの補完スクリプトを組み立てる。
+///
+/// clapの関数を改名し、末尾の呼び出し部分を動的補完の関数の後ろへ移す。
+pub(super) fn zsh_script(static_script: &str) -> String {
+    let renamed = static_script.replacen(ZSH_STATIC_FUNCTION, "\n_gclip_static() {\n", 1);
+    let functions = match renamed.find(ZSH_STATIC_DISPATCH) {
+        Some(position) => &renamed[..position],
+        None => renamed.as_str(),
+    };
+    format!("{}{ZSH_DYNAMIC}", functions.trim_end())
+}
+
+/// 入力中の語に対する候補を登録順に返す。
+///
+/// 語が空なら全件、そうでなければ次のいずれかに当てはまる登録済みコマンドを返す。
+/// - コマンドが語を含む
+/// - 短縮キーが語で始まる
+/// - タグが語で始まる（先頭の `#` は除いて比べる）
+pub(super) fn candidates(
+    commands: &[String],
+    keyed: &[(String, String)],
+    details: &BTreeMap<String, CommandDetails>,
+    word: &str,
+) -> Vec<String> {
+    let needle = word.trim();
+    if needle.is_empty() {
+        return commands.to_vec();
+    }
+    let tag_word = needle.strip_prefix('#').unwrap_or(needle);
+    commands
+        .iter()
+        .filter(|command| {
+            command.contains(needle)
+                || keyed
+                    .iter()
+                    .any(|(key, keyed_command)| keyed_command == *command && key.starts_with(needle))
+                || details.get(command.as_str()).is_some_and(|detail| {
+                    detail.tags.iter().any(|tag| tag.starts_with(tag_word))
+                })
+        })
+        .cloned()
+        .collect()
+}
+
+const ZSH_DYNAMIC: &str = r#"
+
+# 登録済みコマンドを候補にする動的補完。clapが生成した補完は _gclip_static に委譲する。
+_gclip() {
+  local prev="${words[CURRENT-1]}"
+  local cur="${words[CURRENT]}"
+  local -a candidates
+  if [[ "$prev" == "--rm" || "$prev" == "-r" ]] || { (( CURRENT == 3 )) && [[ "$prev" == "rm" || "$prev" == "remove" ]]; }; then
+    candidates=("${(@f)$(command gclip --complete "$cur" 2>/dev/null)}")
+    compadd -U -- "${candidates[@]}"
+    return
+  fi
+  _gclip_static "$@"
+  if (( CURRENT == 2 )) && [[ "$cur" != -* ]]; then
+    candidates=("${(@f)$(command gclip --complete "$cur" 2>/dev/null)}")
+    compadd -U -- "${candidates[@]}"
+  fi
+}
+
+if [ "$funcstack[1]" = "_gclip" ]; then
+  _gclip "$@"
+else
+  compdef _gclip gclip
+fi
+"#;
+
+const BASH_DYNAMIC: &str = r#"
+# 登録済みコマンドを候補にする動的補完。
+_gclip_dynamic_candidates() {
+  local line
+  while IFS= read -r line; do
+    COMPREPLY+=("$(printf '%q' "$line")")
+  done < <(command gclip --complete "$1" 2>/dev/null)
+}
+
+_gclip_dynamic() {
+  local cur="${COMP_WORDS[COMP_CWORD]}"
+  local prev="${COMP_WORDS[COMP_CWORD-1]}"
+  if [[ "$prev" == "--rm" || "$prev" == "-r" ]] || { [[ $COMP_CWORD -eq 2 ]] && [[ "$prev" == "rm" || "$prev" == "remove" ]]; }; then
+    COMPREPLY=()
+    _gclip_dynamic_candidates "$cur"
+    return 0
+  fi
+  _gclip "$@"
+  if [[ $COMP_CWORD -eq 1 && "$cur" != -* ]]; then
+    _gclip_dynamic_candidates "$cur"
+  fi
+}
+complete -F _gclip_dynamic -o bashdefault -o default gclip
+"#;
+
+const FISH_DYNAMIC: &str = r#"
+# 登録済みコマンドを候補にする動的補完。
+complete -c gclip -s r -l rm -x -a '(command gclip --complete (commandline -ct) 2>/dev/null)'
+complete -c gclip -n '__fish_seen_subcommand_from rm remove' -f -a '(command gclip --complete (commandline -ct) 2>/dev/null)'
+complete -c gclip -n 'test (count (commandline -opc)) -eq 1; and not string match -q -- "-*" (commandline -ct)' -f -a '(command gclip --complete (commandline -ct) 2>/dev/null)'
+"#;
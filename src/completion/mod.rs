@@ -0,0 +1,50 @@
+mod dynamic;
+
+use clap::CommandFactory;
+use clap_complete::{generate, Shell};
+
+use crate::cli::Cli;
+use crate::error::GclipError;
+use crate::export;
+use crate::registry::Registry;
+
+/// シェル補完スクリプトを標準出力へ出力する。
+///
+/// `gclip --completions zsh|bash|fish` で実行する。
+/// clapが生成する静的な補完に、登録済みコマンドを候補にする動的補完を組み合わせる。
+pub fn print_completions(shell: &str) -> Result<(), GclipError> {
+    let target = match export::Shell::parse(shell)? {
+        export::Shell::Zsh => Shell::Zsh,
+        export::Shell::Bash => Shell::Bash,
+        export::Shell::Fish => Shell::Fish,
+    };
+    let mut command = Cli::command();
+    let name = command.get_name().to_string();
+    let mut generated = Vec::new();
+    generate(target, &mut command, name, &mut generated);
+    let static_script = String::from_utf8_lossy(&generated);
+    print!("{}", dynamic::complete_script(target, &static_script));
+    Ok(())
+}
+
+/// 動的補完の候補を1行ずつ出力する。
+///
+/// 補完スクリプトから `gclip --complete WORD` として呼び出される。
+/// 入力中の語が空なら全件、そうでなければコマンド、短縮キー、タグのいずれかが当てはまる
+/// 登録済みコマンドを返す。
+pub fn run_complete(word: &str) -> Result<(), GclipError> {
+    let registry = Registry::open()?;
+    let candidates = dynamic::candidates(
+        &registry.list_commands()?,
+        &registry.keyed_commands()?,
+        &registry.command_details()?,
+        word,
+    );
+    for candidate in candidates {
+        println!("{candidate}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
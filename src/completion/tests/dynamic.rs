@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use clap_complete::Shell;
+
+use super::super::dynamic::{candidates, complete_script, zsh_script};
+use crate::registry::CommandDetails;
+
+fn commands() -> Vec<String> {
+    ["git status", "cargo test", "docker compose up"]
+        .map(String::from)
+        .to_vec()
+}
+
+fn keyed() -> Vec<(String, String)> {
+    vec![("ct".to_string(), "cargo test".to_string())]
+}
+
+fn details() -> BTreeMap<String, CommandDetails> {
+    let mut details = BTreeMap::new();
+    details.insert(
+        "docker compose up".to_string(),
+        CommandDetails {
+            tags: vec!["infra".to_string()],
+            ..CommandDetails::default()
+        },
+    );
+    details
+}
+
+#[test]
+fn empty_word_lists_every_command() {
+    assert_eq!(candidates(&commands(), &keyed(), &details(), " "), commands());
+}
+
+#[test]
+fn candidates_match_command_key_and_tag() {
+    // コマンドの部分一致、短縮キーの前方一致、タグの前方一致のいずれでも候補になる。
+    assert_eq!(
+        candidates(&commands(), &keyed(), &details(), "status"),
+        vec!["git status"]
+    );
+    assert_eq!(
+        candidates(&commands(), &keyed(), &details(), "c"),
+        vec!["cargo test", "docker compose up"]
+    );
+    assert_eq!(
+        candidates(&commands(), &keyed(), &details(), "ct"),
+        vec!["cargo test"]
+    );
+    assert_eq!(
+        candidates(&commands(), &keyed(), &details(), "#inf"),
+        vec!["docker compose up"]
+    );
+    assert!(candidates(&commands(), &keyed(), &details(), "nothing").is_empty());
+}
+
+#[test]
+fn zsh_script_wires_a_single_entry_function() {
+    // fpathから読み込まれたときに動的補完が呼ばれるよう、入口の関数は1つだけにする。
+    let static_script = "#compdef gclip\n\nautoload -U is-at-least\n\n_gclip() {\n  _arguments\n}\n\n(( $+functions[_gclip_commands] )) ||\n_gclip_commands() {\n}\n\nif [ \"$funcstack[1]\" = \"_gclip\" ]; then\n    _gclip \"$@\"\nelse\n    compdef _gclip gclip\nfi\n";
+    let script = zsh_script(static_script);
+    assert!(script.starts_with("#compdef gclip\n"));
+    assert_eq!(script.matches("\n_gclip() {\n").count(), 1);
+    assert_eq!(script.matches("compdef _gclip gclip").count(), 1);
+    assert!(script.contains("\n_gclip_static() {\n"));
+    assert!(script.contains("_gclip_static \"$@\""));
+    assert!(script.contains("_gclip_commands() {"));
+    assert!(!script.contains("_gclip_dynamic"));
+}
+
+#[test]
+fn bash_script_appends_dynamic_completion() {
+    let script = complete_script(Shell::Bash, "complete -F _gclip gclip\n");
+    assert!(script.starts_with("complete -F _gclip gclip\n"));
+    assert!(script.contains("gclip --complete"));
+}
@@ -0,0 +1,167 @@
+mod path;
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::error::GclipError;
+
+/// 利用者が編集する設定ファイルのスキーマ。
+///
+/// `~/.gclip/config.toml` に置き、未作成の場合はすべて既定値で動く。
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub guard: GuardConfig,
+    #[serde(default)]
+    pub secrets: SecretsConfig,
+    #[serde(default)]
+    pub workdir: WorkdirConfig,
+    #[serde(default)]
+    pub workflow: WorkflowConfig,
+}
+
+/// 危険なコマンドの挿入前確認に関する設定。
+///
+/// ```toml
+/// [guard]
+/// builtin = true
+/// patterns = ["terraform apply -auto-approve", "helm uninstall"]
+/// ```
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GuardConfig {
+    /// 組み込みのルール（`rm -rf` や `git push --force` など）を使うか。
+    #[serde(default = "default_true")]
+    pub builtin: bool,
+    /// 追加で危険とみなすパターン。
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+impl Default for GuardConfig {
+    fn default() -> Self {
+        Self {
+            builtin: true,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+/// 登録時の秘密情報検出に関する設定。
+///
+/// ```toml
+/// [secrets]
+/// mode = "prompt"  # "prompt" / "warn" / "refuse"
+/// ```
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SecretsConfig {
+    #[serde(default)]
+    pub mode: SecretMode,
+}
+
+/// 秘密情報らしき文字列を含むコマンドを登録しようとしたときの扱い。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretMode {
+    /// 置き換え/そのまま登録/スキップを対話的に選ぶ。
+    #[default]
+    Prompt,
+    /// 警告だけを表示してそのまま登録する。
+    Warn,
+    /// 登録を拒否する。
+    Refuse,
+}
+
+/// 作業ディレクトリが設定されたコマンドを、別のディレクトリで挿入するときの設定。
+///
+/// ```toml
+/// [workdir]
+/// mode = "prefix"  # "prefix" / "ask" / "ignore"
+/// ```
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WorkdirConfig {
+    #[serde(default)]
+    pub mode: WorkdirMode,
+}
+
+/// 作業ディレクトリの外でコマンドを挿入するときの扱い。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkdirMode {
+    /// 先頭に `cd <dir> && ` を付ける。
+    #[default]
+    Prefix,
+    /// `cd <dir> && ` を付けるか確認する。
+    Ask,
+    /// そのまま挿入する。
+    Ignore,
+}
+
+/// ワークフローを選んだときの挿入方法の設定。
+///
+/// ```toml
+/// [workflow]
+/// mode = "joined"  # "joined" / "steps" / "checklist"
+/// ```
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WorkflowConfig {
+    #[serde(default)]
+    pub mode: WorkflowMode,
+}
+
+/// ワークフローを選んだときの挿入方法。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkflowMode {
+    /// 手順を `&&` でつないだ1行を挿入する。
+    #[default]
+    Joined,
+    /// 最初の手順を挿入し、以降は `gclip` を呼ぶたびに次の手順を挿入する。
+    Steps,
+    /// 手順をチェックリストとして表示する。
+    Checklist,
+}
+
+impl Config {
+    /// 設定ファイルを読み込む。
+    ///
+    /// 未作成または空の場合は既定値を返す。
+    pub fn load() -> Result<Self, GclipError> {
+        let Some(config_path) = Self::path() else {
+            return Ok(Self::default());
+        };
+        if !config_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&config_path)
+            .map_err(|err| GclipError::io("failed to read config file", err))?;
+        Self::parse(&contents)
+    }
+
+    /// 設定ファイルの文字列を解析する。
+    ///
+    /// 空文字列は既定値として扱う。
+    /// 解析できない場合は `GclipError::Corrupt` を返す。
+    pub fn parse(contents: &str) -> Result<Self, GclipError> {
+        if contents.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        toml::from_str::<Self>(contents).map_err(|err| {
+            let config_path = Self::path().unwrap_or_else(|| PathBuf::from("config.toml"));
+            GclipError::corrupt(config_path, err.message())
+        })
+    }
+
+    /// 設定ファイルのパスを返す。
+    ///
+    /// `HOME` が取得できない場合は `None` を返す。
+    pub fn path() -> Option<PathBuf> {
+        path::config_path()
+    }
+}
+
+/// serdeの既定値に使う `true`。
+fn default_true() -> bool {
+    true
+}
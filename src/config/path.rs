@@ -0,0 +1,11 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// 設定ファイルのパスを解決する。
+///
+/// 登録ファイルと同じく `~/.gclip` 配下の `config.toml` とする。
+pub(super) fn config_path() -> Option<PathBuf> {
+    env::var("HOME")
+        .ok()
+        .map(|home| Path::new(&home).join(".gclip").join("config.toml"))
+}
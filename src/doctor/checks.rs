@@ -0,0 +1,265 @@
+use serde::Serialize;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::path::PathBuf;
+
+use super::shell_rc::{self, RcInspection};
+use crate::config::Config;
+use crate::error::GclipError;
+use crate::history::History;
+
+/// 環境チェックの結果。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    /// 動作はするが確認したほうがよいもの。
+    Warn,
+    /// 修正が必要なもの。doctorの終了コードを0以外にする。
+    Fail,
+}
+
+/// 1件分の環境チェック。
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl DoctorCheck {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// 検査したシェルの設定ファイル。
+///
+/// 読めなかった場合 `inspection` は `None` になる。
+pub(super) struct RcFile {
+    pub(super) path: PathBuf,
+    pub(super) inspection: Option<RcInspection>,
+}
+
+impl RcFile {
+    fn read(path: PathBuf) -> Self {
+        let inspection = fs::read_to_string(&path)
+            .ok()
+            .map(|contents| shell_rc::inspect_rc(&contents));
+        Self { path, inspection }
+    }
+}
+
+/// `source` を辿るファイル数の上限。循環や深い入れ子で止まらないようにする。
+const MAX_SOURCED_FILES: usize = 32;
+
+/// シェル連携と実行環境のチェックをまとめて行う。
+pub(super) fn run_checks() -> Vec<DoctorCheck> {
+    let zshrc = zshrc_path().map(RcFile::read);
+    let sourced = zshrc.as_ref().map(follow_sources).unwrap_or_default();
+    let rc_files: Vec<&RcFile> = zshrc.iter().chain(&sourced).collect();
+
+    vec![
+        check_shell_init(&rc_files),
+        check_ctrl_g(zshrc.as_ref()),
+        check_tty(),
+        check_history(),
+        check_config(),
+    ]
+}
+
+/// `.zshrc` から `source` で読み込まれているファイルを、入れ子も含めて順に読む。
+///
+/// `.zshrc` 自身は含めない。辿れないパスや一度読んだファイルは飛ばす。
+fn follow_sources(zshrc: &RcFile) -> Vec<RcFile> {
+    let home = home_dir().unwrap_or_default();
+    let zdotdir = zshrc.path.parent().map_or_else(|| home.clone(), PathBuf::from);
+    let sourced_by = |rc_file: &RcFile| {
+        rc_file
+            .inspection
+            .as_ref()
+            .map(|found| found.sourced.clone())
+            .unwrap_or_default()
+    };
+    let mut seen = vec![zshrc.path.clone()];
+    let mut pending = sourced_by(zshrc);
+    let mut files = Vec::new();
+    while !pending.is_empty() && files.len() < MAX_SOURCED_FILES {
+        let raw = pending.remove(0);
+        let Some(path) = shell_rc::resolve_sourced(&raw, &home, &zdotdir) else {
+            continue;
+        };
+        if seen.contains(&path) {
+            continue;
+        }
+        seen.push(path.clone());
+        let rc_file = RcFile::read(path);
+        pending.extend(sourced_by(&rc_file));
+        files.push(rc_file);
+    }
+    files
+}
+
+/// セットアップスクリプトが設定ファイルから読み込まれているかを確認する。
+///
+/// プラグインマネージャーなど辿れない方法で読み込んでいる場合もあるため、
+/// 見つからなくても失敗ではなく警告にとどめる。
+fn check_shell_init(rc_files: &[&RcFile]) -> DoctorCheck {
+    for rc_file in rc_files {
+        if let Some(line) = rc_file.inspection.as_ref().and_then(|found| found.init_line) {
+            return DoctorCheck::new(
+                "shell-init",
+                CheckStatus::Ok,
+                format!("loaded from {}:{line}", rc_file.path.display()),
+            );
+        }
+    }
+    DoctorCheck::new(
+        "shell-init",
+        CheckStatus::Warn,
+        "not found in .zshrc or the files it sources; add `eval \"$(gclip init)\"` to ~/.zshrc unless a plugin manager loads it",
+    )
+}
+
+/// `^g` がgclip以外に割り当てられていないかを確認する。
+///
+/// gclipの読み込みより後で割り当てている場合はgclipが上書きされるため失敗とし、
+/// 前で割り当てている場合はgclipが上書きするため警告にとどめる。
+/// `.zshrc` 以外（`source` したファイルやプラグインマネージャー）で読み込んでいる場合は
+/// 前後を判断できないため、割り当てがあれば警告にする。
+pub(super) fn check_ctrl_g(zshrc: Option<&RcFile>) -> DoctorCheck {
+    let Some(RcFile {
+        path,
+        inspection: Some(inspection),
+    }) = zshrc
+    else {
+        return DoctorCheck::new("ctrl-g", CheckStatus::Ok, "no zsh config to inspect");
+    };
+    let Some(init_line) = inspection.init_line else {
+        if let Some((line, text)) = inspection.ctrl_g_bindings.first() {
+            return DoctorCheck::new(
+                "ctrl-g",
+                CheckStatus::Warn,
+                format!(
+                    "{}:{line} binds ^g ({text}); make sure gclip is loaded after it",
+                    path.display()
+                ),
+            );
+        }
+        return DoctorCheck::new("ctrl-g", CheckStatus::Ok, "not bound to anything else");
+    };
+    let after_init = inspection
+        .ctrl_g_bindings
+        .iter()
+        .find(|(line, _)| *line > init_line);
+    if let Some((line, text)) = after_init {
+        return DoctorCheck::new(
+            "ctrl-g",
+            CheckStatus::Fail,
+            format!("^g is bound to something else at {}:{line}: {text}", path.display()),
+        );
+    }
+    if let Some((line, text)) = inspection.ctrl_g_bindings.first() {
+        return DoctorCheck::new(
+            "ctrl-g",
+            CheckStatus::Warn,
+            format!(
+                "{}:{line} binds ^g ({text}) but gclip overrides it",
+                path.display()
+            ),
+        );
+    }
+    DoctorCheck::new("ctrl-g", CheckStatus::Ok, "not bound to anything else")
+}
+
+/// 選択プロンプトで使う `/dev/tty` が開けるかを確認する。
+fn check_tty() -> DoctorCheck {
+    match OpenOptions::new().read(true).write(true).open("/dev/tty") {
+        Ok(_) => DoctorCheck::new("tty", CheckStatus::Ok, "/dev/tty is available"),
+        Err(err) => DoctorCheck::new(
+            "tty",
+            CheckStatus::Fail,
+            format!("cannot open /dev/tty: {err}"),
+        ),
+    }
+}
+
+/// 使用する履歴ファイルとその有無、形式を確認する。
+fn check_history() -> DoctorCheck {
+    let Some(history_file) = History::locate_history_file() else {
+        return DoctorCheck::new(
+            "history",
+            CheckStatus::Warn,
+            "HOME not set; `gclip suggest` cannot find the history file",
+        );
+    };
+    let location = format!(
+        "{} (from {})",
+        history_file.path.display(),
+        history_file.origin.label()
+    );
+    if !history_file.path.exists() {
+        return DoctorCheck::new(
+            "history",
+            CheckStatus::Warn,
+            format!("{location} not found; set HISTFILE or use `gclip suggest --history`"),
+        );
+    }
+    let contents = match fs::read(&history_file.path) {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).to_string(),
+        Err(err) => {
+            return DoctorCheck::new(
+                "history",
+                CheckStatus::Fail,
+                format!("cannot read {location}: {err}"),
+            );
+        }
+    };
+    let format = if History::uses_extended_format(&contents) {
+        "extended format"
+    } else {
+        "plain format; `setopt EXTENDED_HISTORY` adds timestamps"
+    };
+    DoctorCheck::new("history", CheckStatus::Ok, format!("{location}, {format}"))
+}
+
+/// 設定ファイルが解析できるかを確認する。
+fn check_config() -> DoctorCheck {
+    let location = Config::path()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| "config.toml".to_string());
+    match Config::load() {
+        Ok(_) if Config::path().is_some_and(|path| path.exists()) => {
+            DoctorCheck::new("config", CheckStatus::Ok, location)
+        }
+        Ok(_) => DoctorCheck::new(
+            "config",
+            CheckStatus::Ok,
+            format!("{location} not created; using defaults"),
+        ),
+        Err(GclipError::Corrupt { message, .. }) => {
+            DoctorCheck::new("config", CheckStatus::Fail, format!("{location}: {message}"))
+        }
+        Err(err) => DoctorCheck::new("config", CheckStatus::Fail, format!("{location}: {err}")),
+    }
+}
+
+/// zshの設定ファイルのパスを返す。
+///
+/// `ZDOTDIR` があればその下の `.zshrc` を使う。
+fn zshrc_path() -> Option<PathBuf> {
+    env::var_os("ZDOTDIR")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(home_dir)
+        .map(|dir| dir.join(".zshrc"))
+}
+
+/// `HOME` 環境変数からホームディレクトリを取得する。
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME").map(PathBuf::from)
+}
@@ -1,13 +1,50 @@
+mod checks;
+mod shell_rc;
+
+use crate::error::GclipError;
+use crate::output::{self, display_safe, OutputFormat};
 use crate::registry::Registry;
+use serde::Serialize;
 use std::path::PathBuf;
 
+pub use checks::{CheckStatus, DoctorCheck};
+
 /// 設定/保存場所と整合性を確認する。
 ///
 /// `gclip --doctor` で実行する。
-pub fn run() -> Result<(), String> {
-    let report = Registry::doctor_report()?;
-    print_report(&report);
-    Ok(())
+/// `fix` が指定された場合は先に修復を行い、行った処理を結果に含める。
+/// 失敗したチェックがあれば、結果を出力した後にエラーを返す。
+pub fn run(format: &OutputFormat, fix: bool) -> Result<(), GclipError> {
+    let registry = Registry::open()?;
+    let actions = if fix { registry.repair()? } else { Vec::new() };
+    let mut report = registry.doctor_report()?;
+    report.actions = actions;
+    report.checks = checks::run_checks();
+    if format.is_text() {
+        if fix {
+            print_actions(&report.actions);
+        }
+        print_report(&report);
+    } else {
+        output::print_item(format, &report)?;
+    }
+
+    match report.failures() {
+        0 => Ok(()),
+        failures => Err(GclipError::ChecksFailed(failures)),
+    }
+}
+
+/// 修復で行った処理を標準出力へ表示する。
+fn print_actions(actions: &[String]) {
+    if actions.is_empty() {
+        println!("Nothing to fix.");
+        return;
+    }
+    println!("Fixed:");
+    for action in actions {
+        println!("- {action}");
+    }
 }
 
 /// 診断結果を標準出力へ表示する。
@@ -21,6 +58,17 @@ fn print_report(report: &DoctorReport) {
     println!("Command count: {}", report.command_count);
     println!("Empty commands: {}", report.empty_commands);
     println!("Duplicate commands: {}", report.duplicate_commands);
+    println!("Commands with secrets: {}", report.secret_commands.len());
+    for command in &report.secret_commands {
+        println!("- {}", display_safe(command));
+    }
+    println!("Recent file: {}", report.recent_path.display());
+    println!("Recent file valid: {}", yes_no(report.recent_valid));
+
+    println!("Checks:");
+    for check in &report.checks {
+        println!("  {:<6} {}: {}", status_label(check.status), check.name, check.detail);
+    }
 
     if !report.errors.is_empty() {
         println!("Errors:");
@@ -30,6 +78,15 @@ fn print_report(report: &DoctorReport) {
     }
 }
 
+/// チェック結果の表示用ラベル。
+fn status_label(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Ok => "[ok]",
+        CheckStatus::Warn => "[warn]",
+        CheckStatus::Fail => "[fail]",
+    }
+}
+
 /// 表示用に真偽値をyes/noへ変換する。
 fn yes_no(value: bool) -> &'static str {
     if value {
@@ -40,6 +97,7 @@ fn yes_no(value: bool) -> &'static str {
 }
 
 /// doctorで使う診断結果の構造体。
+#[derive(Debug, Serialize)]
 pub struct DoctorReport {
     pub data_dir: PathBuf,
     pub registry_path: PathBuf,
@@ -50,5 +108,32 @@ pub struct DoctorReport {
     pub command_count: usize,
     pub empty_commands: usize,
     pub duplicate_commands: usize,
+    /// 秘密情報らしき文字列を含む登録済みコマンド（伏せ字済み）。
+    pub secret_commands: Vec<String>,
+    pub recent_path: PathBuf,
+    pub recent_valid: bool,
+    /// シェル連携と実行環境のチェック。
+    pub checks: Vec<DoctorCheck>,
     pub errors: Vec<String>,
+    /// `--fix` で行った修復。
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<String>,
 }
+
+impl DoctorReport {
+    /// 失敗したチェックの数を返す。
+    ///
+    /// 登録ファイルと直近使用コマンドのファイルが読めない場合も失敗に数える。
+    pub fn failures(&self) -> usize {
+        let registry_failed = !(self.registry_readable && self.registry_valid);
+        let checks_failed = self
+            .checks
+            .iter()
+            .filter(|check| check.status == CheckStatus::Fail)
+            .count();
+        usize::from(registry_failed) + usize::from(!self.recent_valid) + checks_failed
+    }
+}
+
+#[cfg(test)]
+mod tests;
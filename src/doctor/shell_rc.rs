@@ -0,0 +1,105 @@
+use std::path::{Path, PathBuf};
+
+/// シェルの設定ファイルを読んで分かったこと。
+///
+/// 行番号は1始まり。
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(super) struct RcInspection {
+    /// gclipのセットアップスクリプトを読み込んでいる行。
+    pub(super) init_line: Option<usize>,
+    /// gclip以外に `^g` を割り当てている行と、その内容。
+    pub(super) ctrl_g_bindings: Vec<(usize, String)>,
+    /// `source` / `.` で読み込んでいるファイル。書かれたままのパスで持つ。
+    pub(super) sourced: Vec<String>,
+}
+
+/// gclipのセットアップスクリプトを出力するサブコマンドとフラグ。
+const INIT_WORDS: &[&str] = &["init", "--init", "-i", "widget", "--zsh-widget", "-w"];
+
+/// 設定ファイルの内容から、gclipの読み込みと `^g` の割り当てを探す。
+///
+/// コメント行は無視する。
+pub(super) fn inspect_rc(contents: &str) -> RcInspection {
+    let mut inspection = RcInspection::default();
+    for (index, line) in contents.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if inspection.init_line.is_none() && loads_gclip(trimmed) {
+            inspection.init_line = Some(index + 1);
+        } else if binds_ctrl_g(trimmed) && !trimmed.contains("gclip") {
+            inspection.ctrl_g_bindings.push((index + 1, trimmed.to_string()));
+        } else {
+            let commands = trimmed.split([';', '&', '|']);
+            inspection
+                .sourced
+                .extend(commands.filter_map(sourced_path).map(String::from));
+        }
+    }
+    inspection
+}
+
+/// `source ~/.zsh/gclip.zsh` のように別のファイルを読み込むコマンドなら、そのパスを返す。
+///
+/// `[[ -f x ]] && source x` のように区切られた1コマンド分を受け取る。
+/// コマンド置換やワイルドカードを含むパスは辿れないため除く。
+fn sourced_path(command: &str) -> Option<&str> {
+    let trimmed = command.trim();
+    let body = trimmed
+        .strip_prefix("then ")
+        .map_or(trimmed, str::trim_start);
+    let rest = body
+        .strip_prefix("source ")
+        .or_else(|| body.strip_prefix(". "))?;
+    let path = rest
+        .split_whitespace()
+        .next()?
+        .trim_matches(|ch| ch == '"' || ch == '\'');
+    let traceable = !path.is_empty() && !path.contains(['(', '*', '?', '`']);
+    traceable.then_some(path)
+}
+
+/// 読み込み先のパスの `~`、`$HOME`、`$ZDOTDIR` を展開する。
+///
+/// 相対パスはシェルの起動時と同じくホームディレクトリを基準にする。
+/// 展開できない変数を含む場合は `None` を返す。
+pub(super) fn resolve_sourced(raw: &str, home: &Path, zdotdir: &Path) -> Option<PathBuf> {
+    let home_text = home.to_string_lossy();
+    let zdotdir_text = zdotdir.to_string_lossy();
+    let expanded = match raw.strip_prefix("~/") {
+        Some(rest) => format!("{home_text}/{rest}"),
+        None => raw
+            .replace("${HOME}", &home_text)
+            .replace("$HOME", &home_text)
+            .replace("${ZDOTDIR}", &zdotdir_text)
+            .replace("$ZDOTDIR", &zdotdir_text),
+    };
+    if expanded.contains('$') || expanded.starts_with('~') {
+        return None;
+    }
+    Some(home.join(expanded))
+}
+
+/// `eval "$(gclip init)"` のようにセットアップスクリプトを読み込む行かを返す。
+fn loads_gclip(line: &str) -> bool {
+    let words: Vec<&str> = line
+        .split(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '-' || ch == '_'))
+        .filter(|word| !word.is_empty())
+        .collect();
+    words
+        .windows(2)
+        .any(|pair| pair[0] == "gclip" && INIT_WORDS.contains(&pair[1]))
+}
+
+/// `^g` にキーを割り当てる行かを返す。
+///
+/// `^x^g` のように `^g` で終わる別のキー列は含めない。
+fn binds_ctrl_g(line: &str) -> bool {
+    let lower = line.to_ascii_lowercase();
+    let bindkey_ctrl_g = lower.starts_with("bindkey")
+        && lower
+            .split_whitespace()
+            .any(|word| word.trim_matches(|ch| ch == '\'' || ch == '"') == "^g");
+    bindkey_ctrl_g || (lower.starts_with("bind ") && lower.contains("\"\\c-g\""))
+}
@@ -0,0 +1,30 @@
+use std::path::PathBuf;
+
+use super::super::checks::{check_ctrl_g, CheckStatus, RcFile};
+use super::super::shell_rc::inspect_rc;
+
+/// 内容から検査済みの `.zshrc` を作る。
+fn zshrc(contents: &str) -> RcFile {
+    RcFile {
+        path: PathBuf::from("/home/user/.zshrc"),
+        inspection: Some(inspect_rc(contents)),
+    }
+}
+
+#[test]
+fn ctrl_g_after_init_fails() {
+    // `.zshrc` でgclipを読み込んだ後に `^g` を割り当てると失敗、前なら警告になる。
+    let after = zshrc("eval \"$(gclip init)\"\nbindkey '^g' fzf-cd-widget\n");
+    assert_eq!(check_ctrl_g(Some(&after)).status, CheckStatus::Fail);
+    let before = zshrc("bindkey '^g' fzf-cd-widget\neval \"$(gclip init)\"\n");
+    assert_eq!(check_ctrl_g(Some(&before)).status, CheckStatus::Warn);
+}
+
+#[test]
+fn ctrl_g_warns_when_init_is_elsewhere() {
+    // `source` やプラグインマネージャーで読み込む場合は前後が分からないため警告にとどめる。
+    let sourced = zshrc("source ~/.zsh/gclip.zsh\nbindkey '^g' fzf-cd-widget\n");
+    assert_eq!(check_ctrl_g(Some(&sourced)).status, CheckStatus::Warn);
+    let unbound = zshrc("source ~/.zsh/gclip.zsh\n");
+    assert_eq!(check_ctrl_g(Some(&unbound)).status, CheckStatus::Ok);
+}
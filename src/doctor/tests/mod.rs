@@ -0,0 +1,2 @@
+mod checks;
+mod shell_rc;
@@ -0,0 +1,56 @@
+use std::path::{Path, PathBuf};
+
+use super::super::shell_rc::{inspect_rc, resolve_sourced};
+
+#[test]
+fn finds_init_line() {
+    // `gclip init` を読み込む行をコメントを除いて探す。
+    let contents = "# eval \"$(gclip init)\"\nexport PATH=$HOME/bin:$PATH\neval \"$(gclip init)\"\n";
+    assert_eq!(inspect_rc(contents).init_line, Some(3));
+    assert_eq!(inspect_rc("source <(gclip --init)\n").init_line, Some(1));
+    assert_eq!(inspect_rc("alias g=gclip\n").init_line, None);
+}
+
+#[test]
+fn collects_other_ctrl_g_bindings() {
+    // gclip以外の `^g` 割り当てだけを行番号付きで集め、`^x^g` などは含めない。
+    let contents = "bindkey '^G' fzf-cd-widget\neval \"$(gclip init)\"\nbindkey '^g' gclip_insert\nbindkey '^r' history-search\nbind '\"\\C-g\": abort'\nbindkey '^x^g' gclip_save\n";
+    let inspection = inspect_rc(contents);
+    assert_eq!(inspection.init_line, Some(2));
+    let lines: Vec<usize> = inspection.ctrl_g_bindings.iter().map(|(line, _)| *line).collect();
+    assert_eq!(lines, vec![1, 5]);
+}
+
+#[test]
+fn collects_sourced_files() {
+    // `source` と `.` で読み込むファイルを集め、辿れないパスは除く。
+    let contents = "source ~/.zsh/aliases.zsh\n. \"$ZDOTDIR/plugins.zsh\"\n[[ -f ~/.zshrc.local ]] && source ~/.zshrc.local\nsource <(kubectl completion zsh)\nfor f in ~/.zsh/*.zsh; do source $f; done\n";
+    assert_eq!(
+        inspect_rc(contents).sourced,
+        vec![
+            "~/.zsh/aliases.zsh",
+            "$ZDOTDIR/plugins.zsh",
+            "~/.zshrc.local",
+        ]
+    );
+}
+
+#[test]
+fn resolves_sourced_paths() {
+    // `~` と `$HOME`、`$ZDOTDIR` を展開し、相対パスはホームを基準にする。
+    let home = Path::new("/home/me");
+    let zdotdir = Path::new("/home/me/.config/zsh");
+    assert_eq!(
+        resolve_sourced("~/.zsh/a.zsh", home, zdotdir),
+        Some(PathBuf::from("/home/me/.zsh/a.zsh"))
+    );
+    assert_eq!(
+        resolve_sourced("${ZDOTDIR}/b.zsh", home, zdotdir),
+        Some(PathBuf::from("/home/me/.config/zsh/b.zsh"))
+    );
+    assert_eq!(
+        resolve_sourced(".zsh/c.zsh", home, zdotdir),
+        Some(PathBuf::from("/home/me/.zsh/c.zsh"))
+    );
+    assert_eq!(resolve_sourced("$f", home, zdotdir), None);
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// gclipの処理で起きるエラーの種類。
+///
+/// 種類ごとに終了コードが決まっており、シェルのウィジェットやスクリプトは
+/// メッセージを解析しなくても「キャンセル」「一致なし」「保存ファイルの破損」などを区別できる。
+///
+/// | 終了コード | 種類 |
+/// |---|---|
+/// | 1 | `Other` |
+/// | 2 | `InvalidInput`（引数の解析エラーと同じ） |
+/// | 3 | `NoMatches` |
+/// | 4 | `Cancelled` |
+/// | 5 | `Corrupt` |
+/// | 6 | `Io` |
+/// | 7 | `Refused` |
+/// | 8 | `ChecksFailed` |
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GclipError {
+    /// 利用者が選択や確認をキャンセルした。
+    Cancelled,
+    /// 検索や推薦の結果が0件だった。
+    NoMatches(String),
+    /// 入力が不正（空のコマンド、使えないキー、範囲外の番号など）。
+    InvalidInput(String),
+    /// 保存ファイルや設定ファイルが解析できない。
+    Corrupt { path: PathBuf, message: String },
+    /// ファイルや端末の読み書きに失敗した。
+    Io(String),
+    /// 秘密情報を含むコマンドの登録を設定に従って拒否した。
+    Refused(String),
+    /// doctorのチェックに失敗したものがある（失敗した件数）。
+    ChecksFailed(usize),
+    /// その他の失敗。
+    Other(String),
+}
+
+impl GclipError {
+    /// プロセスの終了コードを返す。
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Other(_) => 1,
+            Self::InvalidInput(_) => 2,
+            Self::NoMatches(_) => 3,
+            Self::Cancelled => 4,
+            Self::Corrupt { .. } => 5,
+            Self::Io(_) => 6,
+            Self::Refused(_) => 7,
+            Self::ChecksFailed(_) => 8,
+        }
+    }
+
+    /// 機械処理向けの種類名を返す。
+    ///
+    /// `serve` のエラーレスポンスなどで使う。
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Other(_) => "other",
+            Self::InvalidInput(_) => "invalid-input",
+            Self::NoMatches(_) => "no-matches",
+            Self::Cancelled => "cancelled",
+            Self::Corrupt { .. } => "corrupt",
+            Self::Io(_) => "io",
+            Self::Refused(_) => "refused",
+            Self::ChecksFailed(_) => "checks-failed",
+        }
+    }
+
+    /// 解析できないファイルのエラーを作る。
+    ///
+    /// 解析器のメッセージは複数行になることがあるため、1行にまとめる。
+    pub fn corrupt(path: impl Into<PathBuf>, message: impl fmt::Display) -> Self {
+        Self::Corrupt {
+            path: path.into(),
+            message: message.to_string().trim().replace('\n', ", "),
+        }
+    }
+
+    /// 読み書きの失敗を、何をしようとしていたかと合わせて作る。
+    pub fn io(context: &str, err: impl fmt::Display) -> Self {
+        Self::Io(format!("{context}: {err}"))
+    }
+}
+
+impl fmt::Display for GclipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cancelled => write!(f, "cancelled"),
+            Self::NoMatches(message)
+            | Self::InvalidInput(message)
+            | Self::Io(message)
+            | Self::Refused(message)
+            | Self::Other(message) => write!(f, "{message}"),
+            Self::Corrupt { path, message } => {
+                write!(f, "failed to parse {}: {message}", path.display())
+            }
+            Self::ChecksFailed(failures) => write!(f, "{failures} check(s) failed"),
+        }
+    }
+}
+
+impl std::error::Error for GclipError {}
+
+/// 種類を特定しない文字列のエラーは `Other` として扱う。
+impl From<String> for GclipError {
+    fn from(message: String) -> Self {
+        Self::Other(message)
+    }
+}
+
+impl From<&str> for GclipError {
+    fn from(message: &str) -> Self {
+        Self::Other(message.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,33 @@
+use super::super::GclipError;
+use std::collections::HashSet;
+
+#[test]
+fn exit_codes_are_distinct_per_kind() {
+    // 種類ごとに終了コードと種類名が重ならないことを確認する。
+    let errors = [
+        GclipError::Other("x".into()),
+        GclipError::InvalidInput("x".into()),
+        GclipError::NoMatches("x".into()),
+        GclipError::Cancelled,
+        GclipError::corrupt("registered.toml", "x"),
+        GclipError::Io("x".into()),
+        GclipError::Refused("x".into()),
+        GclipError::ChecksFailed(1),
+    ];
+    let codes: HashSet<i32> = errors.iter().map(GclipError::exit_code).collect();
+    let kinds: HashSet<&str> = errors.iter().map(GclipError::kind).collect();
+    assert_eq!(codes.len(), errors.len());
+    assert_eq!(kinds.len(), errors.len());
+    assert!(!codes.contains(&0));
+}
+
+#[test]
+fn messages_are_preserved() {
+    // 表示用のメッセージが元の文言を保つことを確認する。
+    assert_eq!(GclipError::from("HOME not set").to_string(), "HOME not set");
+    assert_eq!(
+        GclipError::corrupt("/tmp/registered.toml", "expected `]`").to_string(),
+        "failed to parse /tmp/registered.toml: expected `]`"
+    );
+    assert_eq!(GclipError::io("failed to read", "denied").to_string(), "failed to read: denied");
+}
@@ -0,0 +1 @@
+mod exit_code;
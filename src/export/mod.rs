@@ -0,0 +1,83 @@
+mod path_lookup;
+mod render;
+
+pub(crate) use path_lookup::find_executable;
+pub(crate) use render::{single_quote, Shell};
+
+use std::path::PathBuf;
+
+use crate::error::GclipError;
+use crate::registry::{self, Registry};
+use crate::shell::{self, ShellNames};
+
+/// 短縮キー付きのコマンドをシェルのエイリアスや関数の定義として出力する。
+///
+/// `gclip --export-aliases zsh|bash|fish` で実行する。
+/// `@キー` の参照は展開してから出力する。循環していて展開できないキーは出力しない。
+/// PATH上の実行ファイルや組み込みコマンド、予約語と名前が衝突するキーは出力せず、警告だけを表示する。
+/// `gclip init` の関数から受け取ったエイリアスや関数と同じ名前のキーは、上書きする旨を表示して出力する。
+pub fn run(shell: &str) -> Result<(), GclipError> {
+    let shell_kind = render::Shell::parse(shell)?;
+    let keyed = Registry::open()?.keyed_commands()?;
+    let (expanded, unexpanded) = expand_keyed(keyed);
+    let names = ShellNames::from_env();
+    let (exportable, conflicts) = split_conflicts(expanded, path_lookup::find_executable);
+
+    for (key, reason) in unexpanded.iter().chain(&conflicts) {
+        eprintln!("Skipped \"{key}\": {reason}");
+    }
+    for (key, _) in exportable.iter().filter(|(key, _)| names.is_defined(key)) {
+        eprintln!("Note: \"{key}\" is already defined in this shell; the exported definition replaces it");
+    }
+    print!("{}", render::render_aliases(shell_kind, &exportable));
+    Ok(())
+}
+
+/// `(キー, 値)` の組の一覧。
+type KeyedPairs = Vec<(String, String)>;
+
+/// 各コマンドの `@キー` の参照を展開する。
+///
+/// 戻り値は `(展開したキーとコマンド, 展開できなかったキーと理由)` の組。
+fn expand_keyed(keyed: KeyedPairs) -> (KeyedPairs, KeyedPairs) {
+    let table = keyed.iter().cloned().collect();
+    let mut expanded = Vec::new();
+    let mut failed = Vec::new();
+    for (key, command) in keyed {
+        match registry::expand_references(&command, &table) {
+            Ok(body) => expanded.push((key, body)),
+            Err(err) => failed.push((key, err.to_string())),
+        }
+    }
+    (expanded, failed)
+}
+
+/// 組み込みコマンドや予約語、PATH上の実行ファイルと衝突するキーを分離する。
+///
+/// `find_executable` はPATHから実行ファイルを探す関数。
+/// 戻り値は `(出力対象, 衝突したキーと理由)` の組。
+/// 警告は標準エラーへ出し、標準出力はそのままファイルへ保存できるよう定義だけにしておく。
+fn split_conflicts(
+    keyed: KeyedPairs,
+    find_executable: impl Fn(&str) -> Option<PathBuf>,
+) -> (KeyedPairs, KeyedPairs) {
+    let mut exportable = Vec::new();
+    let mut conflicts = Vec::new();
+    for (key, command) in keyed {
+        if shell::is_builtin(&key) {
+            conflicts.push((key, "conflicts with a shell builtin or keyword".to_string()));
+            continue;
+        }
+        match find_executable(&key) {
+            Some(found) => {
+                let reason = format!("conflicts with {}", found.display());
+                conflicts.push((key, reason));
+            }
+            None => exportable.push((key, command)),
+        }
+    }
+    (exportable, conflicts)
+}
+
+#[cfg(test)]
+mod tests;
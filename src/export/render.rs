@@ -0,0 +1,158 @@
+use crate::error::GclipError;
+
+/// エイリアスの出力先シェル。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Shell {
+    Zsh,
+    Bash,
+    Fish,
+}
+
+impl Shell {
+    /// シェル名を解析する。
+    ///
+    /// 対応していないシェル名はエラーにする。
+    pub(crate) fn parse(name: &str) -> Result<Self, GclipError> {
+        match name.trim() {
+            "zsh" => Ok(Self::Zsh),
+            "bash" => Ok(Self::Bash),
+            "fish" => Ok(Self::Fish),
+            other => Err(GclipError::InvalidInput(format!(
+                "unsupported shell: {other} (zsh, bash, fish)"
+            ))),
+        }
+    }
+}
+
+/// 短縮キーとコマンドの組からエイリアスと関数の定義を組み立てる。
+///
+/// 1文のコマンドはエイリアスにする。
+/// - zsh/bash: `alias key='command'`
+/// - fish: `abbr -a key 'command'`
+///
+/// 複数の文からなるコマンドや `$1` などの引数を参照するコマンドは、
+/// 引数を渡せるよう関数にする。fishでは引数の参照を `$argv` に書き換える。
+pub(crate) fn render_aliases(shell: Shell, keyed: &[(String, String)]) -> String {
+    let mut script = String::from("# generated by gclip --export-aliases\n");
+    for (key, command) in keyed {
+        let definition = match (shell, needs_function(command)) {
+            (Shell::Zsh | Shell::Bash, false) => format!("alias {key}={}\n", single_quote(command)),
+            (Shell::Fish, false) => format!("abbr -a {key} {}\n", fish_single_quote(command)),
+            (Shell::Zsh | Shell::Bash, true) => format!("function {key} {{\n{command}\n}}\n"),
+            (Shell::Fish, true) => format!("function {key}\n{}\nend\n", fish_arguments(command)),
+        };
+        script.push_str(&definition);
+    }
+    script
+}
+
+/// エイリアスでは引数を正しく渡せないコマンドかを返す。
+///
+/// 引用符の外にある文の区切り（`;`、`&&`、`||`、改行）か、
+/// シングルクォートの外にある引数の参照（`$1`、`${1}`、`$@`、`$*`、`$#`）があれば真を返す。
+/// パイプや `2>&1` などのリダイレクトだけのコマンドは1文として扱う。
+pub(crate) fn needs_function(command: &str) -> bool {
+    let chars: Vec<char> = command.chars().collect();
+    let mut quote: Option<char> = None;
+    let mut index = 0;
+    while index < chars.len() {
+        let ch = chars[index];
+        match (quote, ch) {
+            (Some('\''), '\'') => quote = None,
+            (Some('\''), _) => {}
+            (_, '\\') => index += 1,
+            (_, '$') if is_positional(chars.get(index + 1..).unwrap_or_default()) => return true,
+            (Some('"'), '"') => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(ch),
+            (None, ';' | '\n') => return true,
+            (None, '&' | '|') if chars.get(index + 1) == Some(&ch) => return true,
+            _ => {}
+        }
+        index += 1;
+    }
+    false
+}
+
+/// `$` の直後が引数の参照かを返す。
+fn is_positional(rest: &[char]) -> bool {
+    match rest {
+        [first, ..] if first.is_ascii_digit() || matches!(first, '@' | '*' | '#') => true,
+        ['{', first, ..] => first.is_ascii_digit() || matches!(first, '@' | '*' | '#'),
+        _ => false,
+    }
+}
+
+/// 引数の参照をfishの `$argv` に書き換える。
+///
+/// `"$@"`、`$@`、`$*` は `$argv`、`$N` と `${N}` は `$argv[N]`、`$#` は `(count $argv)` にする。
+/// シングルクォートの中はそのまま残す。
+pub(crate) fn fish_arguments(command: &str) -> String {
+    let chars: Vec<char> = command.chars().collect();
+    let mut converted = String::with_capacity(command.len());
+    let mut in_single = false;
+    let mut in_double = false;
+    let mut index = 0;
+    while index < chars.len() {
+        let ch = chars[index];
+        match ch {
+            '\'' if !in_double => in_single = !in_single,
+            '"' if !in_single => in_double = !in_double,
+            _ => {}
+        }
+        if in_single || ch != '$' {
+            converted.push(ch);
+            index += 1;
+            continue;
+        }
+        let rest = &chars[index + 1..];
+        let braced = rest.first() == Some(&'{');
+        let name_start = usize::from(braced);
+        let name_len = match rest.get(name_start) {
+            Some('@' | '*' | '#') => 1,
+            Some(digit) if digit.is_ascii_digit() => rest[name_start..]
+                .iter()
+                .take_while(|next| next.is_ascii_digit())
+                .count(),
+            _ => 0,
+        };
+        let closed = !braced || rest.get(name_start + name_len) == Some(&'}');
+        if name_len == 0 || !closed {
+            converted.push(ch);
+            index += 1;
+            continue;
+        }
+        let name: String = rest[name_start..name_start + name_len].iter().collect();
+        let replacement = match name.as_str() {
+            "@" | "*" => "$argv".to_string(),
+            "#" => "(count $argv)".to_string(),
+            number => format!("$argv[{number}]"),
+        };
+        // `"$@"` は引用符ごと置き換え、要素を分けたまま渡す。
+        let quoted = converted.ends_with('"') && rest.get(name_start + name_len + usize::from(braced)) == Some(&'"');
+        if name == "@" && quoted {
+            converted.pop();
+            in_double = false;
+            index += 1;
+        }
+        converted.push_str(&replacement);
+        index += 1 + name_start + name_len + usize::from(braced);
+    }
+    converted
+}
+
+/// シングルクォートで囲み、内部の `'` をエスケープする。
+///
+/// zsh/bash向けの形で、内部の `'` はいったんクォートを閉じて `\'` で表す。
+/// fishではシングルクォート内の `\` の扱いが違うため `fish_single_quote` を使う。
+pub(crate) fn single_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// fish向けにシングルクォートで囲む。
+///
+/// fishはシングルクォートの中でも `\\` と `\'` をエスケープとして扱うため、
+/// `\` と `'` の前にバックスラッシュを付ける。
+pub(crate) fn fish_single_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'"))
+}
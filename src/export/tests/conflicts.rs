@@ -0,0 +1,19 @@
+use std::path::PathBuf;
+
+use super::super::split_conflicts;
+
+#[test]
+fn builtins_keywords_and_executables_are_skipped() {
+    // 組み込みコマンドや予約語、PATH上の実行ファイルと同じ名前のキーは出力しない。
+    let keyed: Vec<(String, String)> = [("cd", "cd ~/src"), ("if", "ls"), ("ls", "ls -la"), ("gs", "git status")]
+        .iter()
+        .map(|(key, command)| (key.to_string(), command.to_string()))
+        .collect();
+    let find = |name: &str| (name == "ls").then(|| PathBuf::from("/bin/ls"));
+    let (exportable, conflicts) = split_conflicts(keyed, find);
+
+    assert_eq!(exportable, vec![("gs".to_string(), "git status".to_string())]);
+    let skipped: Vec<&str> = conflicts.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(skipped, vec!["cd", "if", "ls"]);
+    assert_eq!(conflicts[2].1, "conflicts with /bin/ls");
+}
@@ -0,0 +1,3 @@
+mod conflicts;
+mod references;
+mod render;
@@ -0,0 +1,26 @@
+use super::super::expand_keyed;
+
+fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+    items
+        .iter()
+        .map(|(key, command)| (key.to_string(), command.to_string()))
+        .collect()
+}
+
+#[test]
+fn references_are_expanded_before_export() {
+    // `@キー` は参照先のコマンドに置き換え、循環しているキーは理由付きで除く。
+    let keyed = pairs(&[
+        ("build", "cargo build"),
+        ("loop", "@loop -v"),
+        ("ship", "@build && ./deploy.sh"),
+    ]);
+    let (expanded, failed) = expand_keyed(keyed);
+    assert_eq!(
+        expanded,
+        pairs(&[("build", "cargo build"), ("ship", "cargo build && ./deploy.sh")])
+    );
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].0, "loop");
+    assert!(failed[0].1.contains("reference cycle"));
+}
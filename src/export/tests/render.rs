@@ -0,0 +1,77 @@
+use super::super::render::{
+    fish_arguments, fish_single_quote, needs_function, render_aliases, single_quote, Shell,
+};
+
+#[test]
+fn parses_supported_shells() {
+    // 対応しているシェル名だけが解析できることを確認する。
+    assert_eq!(Shell::parse("zsh"), Ok(Shell::Zsh));
+    assert_eq!(Shell::parse("fish"), Ok(Shell::Fish));
+    assert!(Shell::parse("pwsh").is_err());
+}
+
+#[test]
+fn single_quote_escapes_quotes() {
+    // 内部のシングルクォートが閉じてから再度開く形になることを確認する。
+    assert_eq!(single_quote("echo 'hi'"), r"'echo '\''hi'\'''");
+}
+
+#[test]
+fn fish_single_quote_escapes_backslashes_and_quotes() {
+    // fishではシングルクォート内でも `\\` と `\'` がエスケープになるため、どちらも前に `\` を付ける。
+    assert_eq!(fish_single_quote("echo 'hi'"), r"'echo \'hi\''");
+    assert_eq!(fish_single_quote(r"printf 'a\n'"), r"'printf \'a\\n\''");
+    let keyed = vec![("gp".to_string(), r"grep -E 'a\|b'".to_string())];
+    let fish = render_aliases(Shell::Fish, &keyed);
+    assert!(fish.contains(r"abbr -a gp 'grep -E \'a\\|b\''"));
+}
+
+#[test]
+fn renders_alias_for_zsh() {
+    // zshではalias定義になることを確認する。
+    let keyed = vec![("gs".to_string(), "git status".to_string())];
+    let script = render_aliases(Shell::Zsh, &keyed);
+    assert!(script.contains("alias gs='git status'\n"));
+}
+
+#[test]
+fn renders_abbr_for_fish() {
+    // fishではabbr定義になることを確認する。
+    let keyed = vec![("gs".to_string(), "git status".to_string())];
+    let script = render_aliases(Shell::Fish, &keyed);
+    assert!(script.contains("abbr -a gs 'git status'\n"));
+}
+
+#[test]
+fn multi_statement_and_argument_commands_need_functions() {
+    // 文の区切りや引数の参照があれば関数にし、引用符の中やパイプだけなら1文として扱う。
+    assert!(needs_function("make && make test"));
+    assert!(needs_function("cd /tmp; ls"));
+    assert!(needs_function("git log -n \"$1\""));
+    assert!(needs_function("kubectl logs ${1} -f"));
+    assert!(!needs_function("git log --oneline | head"));
+    assert!(!needs_function("echo 'a; b && $1'"));
+    assert!(!needs_function("echo \"a;b\" \\&"));
+    assert!(!needs_function("echo $HOME"));
+    assert!(!needs_function("make 2>&1 | less"));
+    assert!(!needs_function("cargo build &> build.log"));
+}
+
+#[test]
+fn renders_functions_for_zsh_and_fish() {
+    // 関数にするコマンドは本文をそのまま使い、fishでは引数の参照を `$argv` にする。
+    let keyed = vec![("mt".to_string(), "make && make test \"$@\"".to_string())];
+    let zsh = render_aliases(Shell::Zsh, &keyed);
+    assert!(zsh.contains("function mt {\nmake && make test \"$@\"\n}\n"));
+    let fish = render_aliases(Shell::Fish, &keyed);
+    assert!(fish.contains("function mt\nmake && make test $argv\nend\n"));
+}
+
+#[test]
+fn fish_arguments_rewrites_positional_parameters() {
+    // シングルクォートの中は書き換えない。
+    assert_eq!(fish_arguments("git log -n $1"), "git log -n $argv[1]");
+    assert_eq!(fish_arguments("echo ${2} $# $*"), "echo $argv[2] (count $argv) $argv");
+    assert_eq!(fish_arguments("echo \"x $1\" '$1'"), "echo \"x $argv[1]\" '$1'");
+    assert_eq!(fish_arguments("echo $HOME"), "echo $HOME");
+}
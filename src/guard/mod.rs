@@ -0,0 +1,77 @@
+mod pattern;
+mod rules;
+
+pub(crate) use pattern::glob_match;
+
+use crate::config::{Config, GuardConfig};
+use crate::error::GclipError;
+
+/// 危険なコマンドを判定するためのルール集合。
+///
+/// 挿入前に `classify` で判定し、該当すれば追加の確認を求める。
+#[derive(Debug, Default)]
+pub struct Guard {
+    rules: Vec<Rule>,
+}
+
+/// 1件の判定ルール。
+///
+/// `pattern` は空白区切りの語の並びで、各語は `*` / `?` / `[a-z]` のワイルドカードを使える。
+/// コマンド内の同じ区間（`;` `|` `&` で区切られた範囲）に、
+/// 各語に一致するトークンが順番どおりに現れれば一致とみなす。
+/// 先頭の語に `^` を付けると、区間のコマンド名そのものにだけ一致する。
+/// パターンの先頭に `(?i)` を付けると大文字と小文字を区別しない。
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub pattern: String,
+    pub reason: String,
+}
+
+/// 判定結果。どのルールに一致したかを保持する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Risk {
+    pub pattern: String,
+    pub reason: String,
+}
+
+impl Guard {
+    /// 設定ファイルからルールを組み立てる。
+    ///
+    /// 設定ファイルがなければ組み込みルールだけを使う。
+    pub fn load() -> Result<Self, GclipError> {
+        let config = Config::load()?;
+        Ok(Self::from_config(&config.guard))
+    }
+
+    /// 設定内容からルールを組み立てる。
+    ///
+    /// 組み込みルールの後ろに利用者のパターンを追加する。
+    pub fn from_config(config: &GuardConfig) -> Self {
+        let mut rules = if config.builtin {
+            rules::builtin_rules()
+        } else {
+            Vec::new()
+        };
+        rules.extend(config.patterns.iter().map(|pattern| Rule {
+            pattern: pattern.clone(),
+            reason: format!("matches \"{pattern}\""),
+        }));
+        Self { rules }
+    }
+
+    /// コマンドが危険かどうかを判定する。
+    ///
+    /// 最初に一致したルールを返し、どれにも一致しなければ `None` を返す。
+    pub fn classify(&self, command: &str) -> Option<Risk> {
+        self.rules
+            .iter()
+            .find(|rule| pattern::matches(&rule.pattern, command))
+            .map(|rule| Risk {
+                pattern: rule.pattern.clone(),
+                reason: rule.reason.clone(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,168 @@
+/// 区間の先頭でコマンド名の前に置かれても、実行されるコマンドを変えない語。
+const COMMAND_WRAPPERS: &[&str] = &["sudo", "env", "command", "exec", "nohup", "time", "xargs"];
+
+/// コマンドがパターンに一致するかを判定する。
+///
+/// コマンドを `;` `|` `&` で区間に分け、いずれかの区間で
+/// パターンの各語に一致するトークンが順番どおりに現れれば一致とみなす。
+/// 先頭の語が `^` で始まる場合は、その語が区間のコマンド名に一致しなければならない。
+/// パターンが `(?i)` で始まる場合は大文字と小文字を区別しない。
+pub(crate) fn matches(pattern: &str, command: &str) -> bool {
+    if let Some(rest) = pattern.strip_prefix("(?i)") {
+        return matches(&rest.to_lowercase(), &command.to_lowercase());
+    }
+    let words: Vec<&str> = pattern.split_whitespace().collect();
+    if words.is_empty() {
+        return false;
+    }
+
+    command
+        .split([';', '|', '&', '\n'])
+        .any(|segment| matches_segment(&words, segment))
+}
+
+/// 1区間のトークン列にパターンの語が順番どおり現れるかを判定する。
+fn matches_segment(words: &[&str], segment: &str) -> bool {
+    let mut tokens = segment.split_whitespace().map(trim_token);
+    let mut remaining = words.iter().peekable();
+    if let Some(name) = words[0].strip_prefix('^') {
+        if !command_name(&mut tokens).is_some_and(|token| glob_match(name, token)) {
+            return false;
+        }
+        remaining.next();
+    }
+    for token in tokens {
+        let Some(word) = remaining.peek() else {
+            break;
+        };
+        if glob_match(word, token) {
+            remaining.next();
+        }
+    }
+    remaining.peek().is_none()
+}
+
+/// 区間のコマンド名を取り出す。
+///
+/// 先頭の `NAME=value` の代入と `sudo` などの前置きのコマンド、その後ろのオプションは読み飛ばす。
+fn command_name<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let mut after_wrapper = false;
+    for token in tokens.by_ref() {
+        let is_assignment = token
+            .split_once('=')
+            .is_some_and(|(name, _)| !name.is_empty() && name.chars().all(|ch| ch == '_' || ch.is_ascii_alphanumeric()));
+        if is_assignment || (after_wrapper && token.starts_with('-')) {
+            continue;
+        }
+        if COMMAND_WRAPPERS.contains(&token) {
+            after_wrapper = true;
+            continue;
+        }
+        return Some(token);
+    }
+    None
+}
+
+/// トークンの前後から引用符や括弧を取り除く。
+///
+/// `psql -c "DROP TABLE x"` のような引用内のSQLも判定できるようにする。
+fn trim_token(token: &str) -> &str {
+    token.trim_matches(|ch: char| matches!(ch, '"' | '\'' | '(' | ')' | '`'))
+}
+
+/// ワイルドカードの1要素。
+enum Glob {
+    /// `*`：任意の文字列。
+    Any,
+    /// `?`：任意の1文字。
+    One,
+    /// `[a-z]` のような文字クラス。`[!...]` は否定になる。
+    Class { ranges: Vec<(char, char)>, negated: bool },
+    Literal(char),
+}
+
+impl Glob {
+    /// 1文字に一致するかを判定する。`*` は別に扱うため常に偽を返す。
+    fn accepts(&self, ch: char) -> bool {
+        match self {
+            Self::Any => false,
+            Self::One => true,
+            Self::Class { ranges, negated } => {
+                ranges.iter().any(|(low, high)| (*low..=*high).contains(&ch)) != *negated
+            }
+            Self::Literal(expected) => *expected == ch,
+        }
+    }
+}
+
+/// パターンをワイルドカードの要素に分解する。
+///
+/// 閉じていない `[` はそのままの文字として扱う。
+fn parse_glob(pattern: &str) -> Vec<Glob> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut parsed = Vec::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let glob = match chars[index] {
+            '*' => Glob::Any,
+            '?' => Glob::One,
+            '[' => match parse_class(&chars[index + 1..]) {
+                Some((class, consumed)) => {
+                    index += consumed;
+                    class
+                }
+                None => Glob::Literal('['),
+            },
+            ch => Glob::Literal(ch),
+        };
+        parsed.push(glob);
+        index += 1;
+    }
+    parsed
+}
+
+/// `[` の直後から文字クラスを読み取り、要素と `]` までの文字数を返す。
+fn parse_class(chars: &[char]) -> Option<(Glob, usize)> {
+    let negated = chars.first() == Some(&'!');
+    let start = usize::from(negated);
+    let close = start + 1 + chars.get(start + 1..)?.iter().position(|ch| *ch == ']')?;
+    let body = &chars[start..close];
+    let mut ranges = Vec::new();
+    let mut index = 0;
+    while index < body.len() {
+        if index + 2 < body.len() && body[index + 1] == '-' {
+            ranges.push((body[index], body[index + 2]));
+            index += 3;
+        } else {
+            ranges.push((body[index], body[index]));
+            index += 1;
+        }
+    }
+    Some((Glob::Class { ranges, negated }, close + 1))
+}
+
+/// `*`（任意の文字列）、`?`（任意の1文字）、`[a-z]`（文字クラス）に対応したワイルドカード一致。
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let globs = parse_glob(pattern);
+    let text_chars: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text_chars.len() {
+        if p < globs.len() && globs[p].accepts(text_chars[t]) {
+            p += 1;
+            t += 1;
+        } else if p < globs.len() && matches!(globs[p], Glob::Any) {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    globs[p..].iter().all(|glob| matches!(glob, Glob::Any))
+}
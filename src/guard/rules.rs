@@ -0,0 +1,45 @@
+use super::Rule;
+
+/// 組み込みの危険なコマンドのパターンと理由。
+///
+/// 取り消しが難しい削除・上書き・強制操作を中心に並べる。
+/// `rm` は `git rm` などを除くためコマンド名に限り、`--force` のような長いオプションは
+/// 再帰指定と見なさないよう、短いオプションのまとまりだけを対象にする。
+/// SQLのキーワードは大文字と小文字を区別しない。`truncate` 単独はファイルを切り詰める
+/// コマンドと区別できないため、大文字の `TRUNCATE` だけを対象にする。
+const BUILTIN_RULES: &[(&str, &str)] = &[
+    ("^rm -[rR]*", "recursive delete"),
+    ("^rm -[a-zA-Z]*[rR]*", "recursive delete"),
+    ("^rm --recursive", "recursive delete"),
+    ("git push --force*", "force push"),
+    ("git push -f", "force push"),
+    ("git push *+*", "force push"),
+    ("git reset --hard", "discards local changes"),
+    ("git clean -*f*", "deletes untracked files"),
+    ("git branch -D", "force-deletes a branch"),
+    ("git checkout -- .", "discards local changes"),
+    ("kubectl delete", "deletes cluster resources"),
+    ("helm uninstall", "deletes a release"),
+    ("terraform destroy", "destroys infrastructure"),
+    ("docker system prune", "deletes docker data"),
+    ("(?i)drop table", "drops a table"),
+    ("(?i)drop database", "drops a database"),
+    ("TRUNCATE", "truncates a table"),
+    ("(?i)truncate table", "truncates a table"),
+    ("dd of=*", "overwrites a device or file"),
+    ("mkfs*", "formats a filesystem"),
+    ("chmod -R 777", "opens permissions recursively"),
+    ("shutdown", "shuts down the machine"),
+    ("reboot", "reboots the machine"),
+];
+
+/// 組み込みルールの一覧を返す。
+pub(super) fn builtin_rules() -> Vec<Rule> {
+    BUILTIN_RULES
+        .iter()
+        .map(|(pattern, reason)| Rule {
+            pattern: pattern.to_string(),
+            reason: reason.to_string(),
+        })
+        .collect()
+}
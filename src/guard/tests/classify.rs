@@ -0,0 +1,52 @@
+use super::super::Guard;
+use crate::config::GuardConfig;
+
+#[test]
+fn builtin_rules_flag_destructive_commands() {
+    // 組み込みルールで代表的な危険コマンドが判定されることを確認する。
+    let guard = Guard::from_config(&GuardConfig::default());
+    assert!(guard.classify("rm -rf ~/tmp").is_some());
+    assert!(guard.classify("git push --force-with-lease").is_some());
+    assert!(guard.classify("kubectl delete pod web-0").is_some());
+    assert!(guard.classify("git status").is_none());
+    assert!(guard.classify("rm file.txt").is_none());
+}
+
+#[test]
+fn recursive_rm_needs_a_short_flag_on_rm_itself() {
+    // 再帰削除は `rm` 自身の短いオプションのまとまりか `--recursive` だけで判定する。
+    let guard = Guard::from_config(&GuardConfig::default());
+    assert!(guard.classify("rm -r build").is_some());
+    assert!(guard.classify("rm -fR build").is_some());
+    assert!(guard.classify("rm -v --recursive build").is_some());
+    assert!(guard.classify("sudo rm -rf /var/tmp/x").is_some());
+    assert!(guard.classify("find . -name '*.o' | xargs rm -fr").is_some());
+    assert!(guard.classify("rm --force x").is_none());
+    assert!(guard.classify("rm --interactive=never f").is_none());
+    assert!(guard.classify("git rm -r --cached target").is_none());
+    assert!(guard.classify("echo rm -rf").is_none());
+}
+
+#[test]
+fn sql_keywords_ignore_case() {
+    // SQLのキーワードは大文字小文字を問わず判定し、`truncate` コマンドは対象にしない。
+    let guard = Guard::from_config(&GuardConfig::default());
+    assert!(guard.classify("psql -c 'Drop Table users'").is_some());
+    assert!(guard.classify("mysql -e \"drop DATABASE app\"").is_some());
+    assert!(guard.classify("psql -c \"TRUNCATE table sessions\"").is_some());
+    assert!(guard.classify("psql -c 'TRUNCATE sessions'").is_some());
+    assert!(guard.classify("truncate -s 0 app.log").is_none());
+}
+
+#[test]
+fn configured_patterns_are_added() {
+    // 設定したパターンが追加のルールとして使われることを確認する。
+    let config = GuardConfig {
+        builtin: false,
+        patterns: vec!["make deploy*".to_string()],
+    };
+    let guard = Guard::from_config(&config);
+    let risk = guard.classify("make deploy-prod").expect("should be flagged");
+    assert_eq!(risk.pattern, "make deploy*");
+    assert!(guard.classify("rm -rf /").is_none());
+}
@@ -0,0 +1,49 @@
+use super::super::pattern::{glob_match, matches};
+
+#[test]
+fn glob_match_supports_wildcards() {
+    // `*` と `?` のワイルドカードが機能することを確認する。
+    assert!(glob_match("-*r*", "-rf"));
+    assert!(glob_match("-*r*", "-fr"));
+    assert!(glob_match("of=*", "of=/dev/disk2"));
+    assert!(glob_match("?s", "ls"));
+    assert!(!glob_match("-*r*", "-f"));
+}
+
+#[test]
+fn glob_match_supports_character_classes() {
+    // `[a-z]` の範囲と `[!...]` の否定を確認する。閉じていない `[` はそのままの文字になる。
+    assert!(glob_match("-[a-zA-Z]*[rR]*", "-fR"));
+    assert!(!glob_match("-[a-zA-Z]*[rR]*", "--force"));
+    assert!(glob_match("[!.]*", "src"));
+    assert!(!glob_match("[!.]*", ".git"));
+    assert!(glob_match("a[b", "a[b"));
+}
+
+#[test]
+fn anchored_word_must_be_the_command_name() {
+    // `^` 付きの語は代入や `sudo` を除いた区間の先頭のコマンドにだけ一致する。
+    assert!(matches("^rm -r", "FOO=1 sudo -E rm -r x"));
+    assert!(matches("^rm -r", "ls && rm -r x"));
+    assert!(!matches("^rm -r", "git rm -r x"));
+}
+
+#[test]
+fn words_must_appear_in_order() {
+    // 語は間に別のトークンがあっても順番どおりなら一致する。
+    assert!(matches("git push --force", "git push origin main --force"));
+    assert!(!matches("git push --force", "git --force push"));
+}
+
+#[test]
+fn words_must_be_in_same_segment() {
+    // `&&` などで区切られた別のコマンドにまたがる場合は一致しない。
+    assert!(!matches("^rm -[rR]*", "rm a.txt && ls -ra"));
+    assert!(matches("^rm -[rR]*", "cd /tmp && rm -rf build"));
+}
+
+#[test]
+fn quoted_tokens_are_matched() {
+    // 引用符で囲まれたSQLも判定できることを確認する。
+    assert!(matches("DROP TABLE", r#"psql -c "DROP TABLE users""#));
+}
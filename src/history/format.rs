@@ -0,0 +1,167 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::parse;
+
+/// 履歴の書式。
+///
+/// - `Zsh`: `.zsh_history`（拡張形式 `: 開始時刻:経過秒;コマンド` と通常形式）
+/// - `Bash`: `.bash_history`（`HISTTIMEFORMAT` による `#UNIX秒` の行を含む場合がある）
+/// - `Fish`: `fish_history`（`- cmd: コマンド` の形式）
+/// - `Numbered`: `history` コマンドの出力（先頭に番号が付く）
+/// - `Plain`: 1行1コマンド（`fc -ln 1` の出力など）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryFormat {
+    Zsh,
+    Bash,
+    Fish,
+    Numbered,
+    Plain,
+}
+
+impl HistoryFormat {
+    /// `--history-format` で指定できる名前の一覧。
+    pub const NAMES: [&'static str; 5] = ["zsh", "bash", "fish", "numbered", "plain"];
+
+    /// 指定用の名前を返す。
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Zsh => "zsh",
+            Self::Bash => "bash",
+            Self::Fish => "fish",
+            Self::Numbered => "numbered",
+            Self::Plain => "plain",
+        }
+    }
+}
+
+impl fmt::Display for HistoryFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for HistoryFormat {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "zsh" => Ok(Self::Zsh),
+            "bash" => Ok(Self::Bash),
+            "fish" => Ok(Self::Fish),
+            "numbered" | "history" => Ok(Self::Numbered),
+            "plain" | "fc" => Ok(Self::Plain),
+            other => Err(format!(
+                "unknown history format: {other} ({})",
+                Self::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// 内容から履歴の書式を推測する。
+///
+/// 空でない行の多くが特徴的な形式に当てはまるものを選び、
+/// どれにも当てはまらなければ `Plain` とする。
+pub(super) fn detect_format(contents: &str) -> HistoryFormat {
+    let lines: Vec<&str> = contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if lines.is_empty() {
+        return HistoryFormat::Plain;
+    }
+    let count = |predicate: fn(&str) -> bool| lines.iter().filter(|line| predicate(line)).count();
+    let majority = |matches: usize| matches * 2 > lines.len();
+
+    // bashのタイムスタンプ行はコマンド行と交互に現れるため、半数近くあれば十分とする。
+    let timestamps = count(is_bash_timestamp);
+    if majority(count(is_fish_line)) {
+        HistoryFormat::Fish
+    } else if parse::uses_extended_format(contents) {
+        HistoryFormat::Zsh
+    } else if timestamps > 0 && timestamps * 3 >= lines.len() {
+        HistoryFormat::Bash
+    } else if majority(count(|line| strip_number(line).is_some())) {
+        HistoryFormat::Numbered
+    } else {
+        HistoryFormat::Plain
+    }
+}
+
+/// 指定した書式で履歴全体を解析し、古い順のコマンド一覧を返す。
+pub(super) fn parse_commands(contents: &str, format: HistoryFormat) -> Vec<String> {
+    let lines = contents.lines();
+    match format {
+        HistoryFormat::Zsh => lines.filter_map(parse::parse_history_line).collect(),
+        HistoryFormat::Bash => lines
+            .filter(|line| !is_bash_timestamp(line))
+            .filter_map(non_empty)
+            .collect(),
+        HistoryFormat::Fish => lines
+            .filter_map(|line| line.strip_prefix("- cmd: "))
+            .map(unescape_fish)
+            .filter_map(|command| non_empty(&command))
+            .collect(),
+        HistoryFormat::Numbered => lines
+            .map(|line| strip_number(line).unwrap_or(line))
+            .filter_map(non_empty)
+            .collect(),
+        HistoryFormat::Plain => lines.filter_map(non_empty).collect(),
+    }
+}
+
+/// 前後の空白を除き、空でなければコマンドとして返す。
+fn non_empty(line: &str) -> Option<String> {
+    let trimmed = line.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+/// fishの履歴の行（`- cmd:` とそれに続く `when:` など）かを返す。
+fn is_fish_line(line: &str) -> bool {
+    line.starts_with("- cmd: ") || line.starts_with("  when: ") || line.starts_with("  paths:")
+}
+
+/// bashの `HISTTIMEFORMAT` による `#1700000000` 行かを返す。
+fn is_bash_timestamp(line: &str) -> bool {
+    line.strip_prefix('#')
+        .is_some_and(|rest| !rest.is_empty() && rest.chars().all(|ch| ch.is_ascii_digit()))
+}
+
+/// `history` の出力の先頭の番号（`  42  ` や `  42* `）を取り除く。
+///
+/// 番号で始まらない行は `None` を返す。
+fn strip_number(line: &str) -> Option<&str> {
+    let rest = line.trim_start();
+    let digits = rest.len() - rest.trim_start_matches(|ch: char| ch.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+    let after = rest[digits..].strip_prefix('*').unwrap_or(&rest[digits..]);
+    if !after.starts_with([' ', '\t']) {
+        return None;
+    }
+    Some(after.trim_start())
+}
+
+/// fishの履歴のエスケープ（`\n` と `\\`）を元に戻す。
+fn unescape_fish(escaped: &str) -> String {
+    let mut command = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            command.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => command.push('\n'),
+            Some('\\') => command.push('\\'),
+            Some(other) => {
+                command.push('\\');
+                command.push(other);
+            }
+            None => command.push('\\'),
+        }
+    }
+    command
+}
@@ -1,9 +1,13 @@
+mod format;
 mod parse;
 mod path;
 mod recent;
 
 use std::path::PathBuf;
 
+pub use format::HistoryFormat;
+pub use path::{HistoryFile, HistoryFileOrigin};
+
 /// シェル履歴の読み取り・解析に関する処理をまとめる。
 ///
 /// 呼び出し側は `History` の公開メソッドだけを使うことで、
@@ -11,13 +15,32 @@ use std::path::PathBuf;
 pub struct History;
 
 impl History {
-    /// 履歴ファイルのパスを返す（macOS前提）。
+    /// 履歴ファイルのパスを返す。存在しない場合は `None` を返す。
     ///
-    /// macOSの標準シェルはzshなので `~/.zsh_history` のみを対象とする。
+    /// `HISTFILE`、`$ZDOTDIR/.zsh_history`、`~/.zsh_history` の順に決める。
     pub fn find_history_file() -> Option<PathBuf> {
         path::find_history_file()
     }
 
+    /// 履歴ファイルの場所と、それをどこから決めたかを返す。
+    ///
+    /// ファイルが存在しなくても返すため、エラー表示や `doctor` に使う。
+    pub fn locate_history_file() -> Option<HistoryFile> {
+        path::locate_history_file()
+    }
+
+    /// 履歴がzshの拡張形式（開始時刻と経過秒付き）で書かれているかを判定する。
+    pub fn uses_extended_format(contents: &str) -> bool {
+        parse::uses_extended_format(contents)
+    }
+
+    /// 履歴の内容から書式を推測する。
+    ///
+    /// どの形式にも当てはまらなければ1行1コマンドとみなす。
+    pub fn detect_format(contents: &str) -> HistoryFormat {
+        format::detect_format(contents)
+    }
+
     /// 履歴ファイル全体の文字列から、直近 `limit` 件のコマンドを抽出する。
     ///
     /// - 行単位で解析し、空行は除外する。
@@ -25,6 +48,13 @@ impl History {
     pub fn recent_commands(contents: &str, limit: usize) -> Vec<String> {
         recent::recent_commands(contents, limit)
     }
+
+    /// 書式を指定して、直近 `limit` 件のコマンドを抽出する。
+    ///
+    /// zsh以外のシェルの履歴や `fc -ln 1` の出力を読むときに使う。
+    pub fn recent_commands_in(contents: &str, format: HistoryFormat, limit: usize) -> Vec<String> {
+        recent::recent_commands_in(contents, format, limit)
+    }
 }
 
 #[cfg(test)]
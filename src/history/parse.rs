@@ -20,6 +20,36 @@ pub(super) fn parse_history_line(line: &str) -> Option<String> {
     parse_plain_line(trimmed)
 }
 
+/// 履歴がzshの拡張形式（`setopt EXTENDED_HISTORY`）で書かれているかを判定する。
+///
+/// 空でない行の過半数が `: 開始時刻:経過秒;` で始まる場合に拡張形式とみなす。
+pub(super) fn uses_extended_format(contents: &str) -> bool {
+    let mut total = 0;
+    let mut extended = 0;
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        total += 1;
+        if is_extended_line(line) {
+            extended += 1;
+        }
+    }
+    total > 0 && extended * 2 > total
+}
+
+/// `: 1700000000:0;` の形式で始まる行かどうかを返す。
+fn is_extended_line(line: &str) -> bool {
+    let Some(rest) = line.strip_prefix(": ") else {
+        return false;
+    };
+    let Some((header, _)) = rest.split_once(';') else {
+        return false;
+    };
+    let Some((started, elapsed)) = header.split_once(':') else {
+        return false;
+    };
+    let is_number = |text: &str| !text.is_empty() && text.chars().all(|ch| ch.is_ascii_digit());
+    is_number(started) && is_number(elapsed)
+}
+
 /// zshの拡張履歴形式 `: 1700000000:0;cmd` を解析する。
 ///
 /// `;` 以降をコマンドとして返し、空なら `None` を返す。
@@ -1,13 +1,84 @@
 use std::env;
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 
+/// 履歴ファイルの場所をどこから決めたか。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryFileOrigin {
+    /// 環境変数 `HISTFILE`（`gclip init` のスクリプトが渡す）。
+    Histfile,
+    /// `$ZDOTDIR/.zsh_history`。
+    Zdotdir,
+    /// `~/.zsh_history`。
+    Home,
+}
+
+impl HistoryFileOrigin {
+    /// 表示用の名前を返す。
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Histfile => "HISTFILE",
+            Self::Zdotdir => "ZDOTDIR",
+            Self::Home => "default",
+        }
+    }
+}
+
+/// 解決した履歴ファイルの場所。存在するとは限らない。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryFile {
+    pub path: PathBuf,
+    pub origin: HistoryFileOrigin,
+}
+
 /// 履歴ファイルのパスを解決する。
 ///
-/// macOSの標準構成として `~/.zsh_history` のみを探索対象とする。
+/// 解決した場所にファイルがある場合のみ返す。
 pub(crate) fn find_history_file() -> Option<PathBuf> {
-    let home = home_dir()?;
-    let path = history_path_from_home(&home);
-    path_if_exists(path)
+    let file = locate_history_file()?;
+    path_if_exists(file.path)
+}
+
+/// 環境変数から履歴ファイルの場所を解決する。
+pub(super) fn locate_history_file() -> Option<HistoryFile> {
+    history_file_from(
+        non_empty_var("HISTFILE"),
+        non_empty_var("ZDOTDIR"),
+        home_dir(),
+    )
+}
+
+/// `HISTFILE`、`ZDOTDIR`、ホームディレクトリの順に履歴ファイルの場所を決める。
+///
+/// zshは `HISTFILE` を環境変数として公開しないため、
+/// 通常は `gclip init` のスクリプトが呼び出し時に渡した値になる。
+/// `HISTFILE` の先頭の `~/` はホームディレクトリに展開する。
+pub(super) fn history_file_from(
+    histfile: Option<OsString>,
+    zdotdir: Option<OsString>,
+    home: Option<PathBuf>,
+) -> Option<HistoryFile> {
+    if let Some(value) = histfile {
+        return Some(HistoryFile {
+            path: expand_home(PathBuf::from(value), home.as_deref()),
+            origin: HistoryFileOrigin::Histfile,
+        });
+    }
+    if let Some(dir) = zdotdir {
+        return Some(HistoryFile {
+            path: history_path_in(Path::new(&dir)),
+            origin: HistoryFileOrigin::Zdotdir,
+        });
+    }
+    home.map(|dir| HistoryFile {
+        path: history_path_in(&dir),
+        origin: HistoryFileOrigin::Home,
+    })
+}
+
+/// 空でない環境変数の値を返す。
+fn non_empty_var(name: &str) -> Option<OsString> {
+    env::var_os(name).filter(|value| !value.is_empty())
 }
 
 /// `HOME` 環境変数からホームディレクトリを取得する。
@@ -17,11 +88,17 @@ fn home_dir() -> Option<PathBuf> {
     env::var("HOME").ok().map(PathBuf::from)
 }
 
-/// ホームディレクトリから zsh 履歴のパスを組み立てる。
-///
-/// macOSの標準構成を前提に、`~/.zsh_history` を返す。
-fn history_path_from_home(home: &Path) -> PathBuf {
-    home.join(".zsh_history")
+/// 先頭の `~` をホームディレクトリに置き換える。
+fn expand_home(path: PathBuf, home: Option<&Path>) -> PathBuf {
+    match (path.strip_prefix("~"), home) {
+        (Ok(rest), Some(dir)) => dir.join(rest),
+        _ => path,
+    }
+}
+
+/// ディレクトリ直下の zsh 履歴のパスを組み立てる。
+fn history_path_in(dir: &Path) -> PathBuf {
+    dir.join(".zsh_history")
 }
 
 /// パスが存在する場合のみ `Some` を返す。
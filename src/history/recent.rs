@@ -1,3 +1,4 @@
+use super::format::{self, HistoryFormat};
 use super::parse;
 
 /// 履歴ファイル全体の文字列から、直近 `limit` 件のコマンドを抽出する。
@@ -9,6 +10,12 @@ pub(super) fn recent_commands(contents: &str, limit: usize) -> Vec<String> {
     limit_recent(commands, limit)
 }
 
+/// 指定した書式の履歴全体から、直近 `limit` 件のコマンドを抽出する。
+pub(super) fn recent_commands_in(contents: &str, history_format: HistoryFormat, limit: usize) -> Vec<String> {
+    let commands = format::parse_commands(contents, history_format);
+    limit_recent(commands, limit)
+}
+
 /// 履歴ファイル全体からコマンドを抽出する。
 ///
 /// 行単位で `parse_history_line` を呼び出して集約する。
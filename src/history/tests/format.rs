@@ -0,0 +1,56 @@
+use super::super::format::{detect_format, parse_commands, HistoryFormat};
+
+#[test]
+fn detects_each_format() {
+    // 各シェルの履歴や `history` の出力を書式ごとに見分けられることを確認する。
+    let zsh = ": 1700000000:0;git status\n: 1700000001:0;ls";
+    let bash = "#1700000000\ngit status\n#1700000001\nls";
+    let fish = "- cmd: git status\n  when: 1700000000\n- cmd: ls\n  when: 1700000001";
+    let numbered = "  1  git status\n  2* ls\n 10  make";
+    let plain = "git status\nls";
+    assert_eq!(detect_format(zsh), HistoryFormat::Zsh);
+    assert_eq!(detect_format(bash), HistoryFormat::Bash);
+    assert_eq!(detect_format(fish), HistoryFormat::Fish);
+    assert_eq!(detect_format(numbered), HistoryFormat::Numbered);
+    assert_eq!(detect_format(plain), HistoryFormat::Plain);
+    assert_eq!(detect_format(""), HistoryFormat::Plain);
+}
+
+#[test]
+fn parses_commands_per_format() {
+    // 書式ごとの付加情報を取り除き、コマンドだけを古い順に返す。
+    let bash = "#1700000000\ngit status\n\n#1700000001\nls";
+    assert_eq!(parse_commands(bash, HistoryFormat::Bash), ["git status", "ls"]);
+
+    let fish = "- cmd: echo a\\\\b\n  when: 1\n- cmd: printf x\\ny\n  when: 2";
+    assert_eq!(
+        parse_commands(fish, HistoryFormat::Fish),
+        ["echo a\\b", "printf x\ny"]
+    );
+
+    let numbered = "  1  git status\n  2* ls -la\n  3  42";
+    assert_eq!(
+        parse_commands(numbered, HistoryFormat::Numbered),
+        ["git status", "ls -la", "42"]
+    );
+
+    let plain = "  git status  \n\nls";
+    assert_eq!(parse_commands(plain, HistoryFormat::Plain), ["git status", "ls"]);
+}
+
+#[test]
+fn numbered_lines_need_separator() {
+    // 数字で始まるだけのコマンドは番号として取り除かない。
+    assert_eq!(parse_commands("7z x a.7z", HistoryFormat::Numbered), ["7z x a.7z"]);
+}
+
+#[test]
+fn format_names_round_trip() {
+    // 名前から書式へ変換でき、別名も受け付ける。
+    for name in HistoryFormat::NAMES {
+        let format: HistoryFormat = name.parse().expect("known format");
+        assert_eq!(format.name(), name);
+    }
+    assert_eq!("history".parse(), Ok(HistoryFormat::Numbered));
+    assert!("csh".parse::<HistoryFormat>().is_err());
+}
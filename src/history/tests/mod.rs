@@ -1,2 +1,4 @@
+mod format;
 mod parse;
+mod path;
 mod recent;
@@ -1,4 +1,4 @@
-use super::super::parse::parse_history_line;
+use super::super::parse::{parse_history_line, uses_extended_format};
 
 #[test]
 fn ignores_empty_lines() {
@@ -28,3 +28,12 @@ fn accepts_plain_format() {
     let line = "git status";
     assert_eq!(parse_history_line(line), Some("git status".to_string()));
 }
+
+#[test]
+fn detects_extended_format() {
+    // 過半数の行が拡張形式なら拡張形式と判定する。
+    assert!(uses_extended_format(": 1700000000:0;ls\n: 1700000001:2;make\n"));
+    assert!(!uses_extended_format("ls\nmake\n: 1700000000:0;pwd\n"));
+    assert!(!uses_extended_format(": not:time;ls\n"));
+    assert!(!uses_extended_format(""));
+}
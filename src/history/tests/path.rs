@@ -0,0 +1,36 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use super::super::path::{history_file_from, HistoryFileOrigin};
+
+fn home() -> Option<PathBuf> {
+    Some(PathBuf::from("/home/user"))
+}
+
+#[test]
+fn histfile_takes_precedence() {
+    // `HISTFILE` があれば `ZDOTDIR` より優先し、先頭の `~` を展開する。
+    let file = history_file_from(
+        Some(OsString::from("~/.local/state/zsh/history")),
+        Some(OsString::from("/home/user/.config/zsh")),
+        home(),
+    )
+    .expect("history file");
+    assert_eq!(file.path, PathBuf::from("/home/user/.local/state/zsh/history"));
+    assert_eq!(file.origin, HistoryFileOrigin::Histfile);
+}
+
+#[test]
+fn falls_back_to_zdotdir_then_home() {
+    // `HISTFILE` がなければ `$ZDOTDIR/.zsh_history`、それもなければ `~/.zsh_history` を使う。
+    let zdotdir = history_file_from(None, Some(OsString::from("/home/user/.config/zsh")), home())
+        .expect("history file");
+    assert_eq!(zdotdir.path, PathBuf::from("/home/user/.config/zsh/.zsh_history"));
+    assert_eq!(zdotdir.origin, HistoryFileOrigin::Zdotdir);
+
+    let default = history_file_from(None, None, home()).expect("history file");
+    assert_eq!(default.path, PathBuf::from("/home/user/.zsh_history"));
+    assert_eq!(default.origin, HistoryFileOrigin::Home);
+
+    assert_eq!(history_file_from(None, None, None), None);
+}
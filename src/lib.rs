@@ -7,4 +7,17 @@ pub mod list;
 pub mod remove;
 pub mod doctor;
 pub(crate) mod selection;
+pub(crate) mod shell;
 pub mod suggest;
+pub mod export;
+pub mod output;
+pub mod serve;
+pub mod completion;
+pub mod config;
+pub mod guard;
+pub mod secret;
+pub mod clock;
+pub mod stats;
+pub mod prune;
+pub mod error;
+pub mod record;
@@ -1,24 +1,157 @@
-use crate::registry::Registry;
+use crate::error::GclipError;
+use crate::guard::Guard;
+use crate::output::{self, display_safe, OutputFormat};
+use crate::registry::{self, CommandDetails, Condition, Registry, WhenContext};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// 機械処理向けに出力する一覧の1件分。
+///
+/// 短縮キーや説明、条件、作業ディレクトリがない場合、`key` / `description` / `when` / `cwd` は `null` になる。
+/// `steps` はワークフローの手順で、通常のコマンドでは空になる。
+/// `active` は現在の環境で条件を満たすか（条件がなければ常に真）。
+/// 危険なコマンドと判定された場合は `risk` に理由が入る。
+#[derive(Debug, Serialize)]
+struct ListItem<'a> {
+    index: usize,
+    command: &'a str,
+    risk: Option<String>,
+    key: Option<&'a str>,
+    description: Option<&'a str>,
+    tags: &'a [String],
+    when: Option<&'a Condition>,
+    active: bool,
+    cwd: Option<&'a str>,
+    steps: &'a [String],
+}
 
 /// 登録済みコマンドを一覧表示する。
 ///
 /// `gclip --list` で一覧を出力する。
-pub fn run() -> Result<(), String> {
-    let commands = Registry::list_commands()?;
-    print_commands(&commands);
-    Ok(())
+/// `when` の条件を満たさないコマンドは後ろに回す。
+pub fn run(format: &OutputFormat) -> Result<(), GclipError> {
+    let registry = Registry::open()?;
+    let context = WhenContext::current();
+    let commands = registry.rank_by_conditions(registry.list_commands()?, &context)?;
+    let details = registry.command_details()?;
+    let guard = Guard::load()?;
+    let references = registry.reference_table()?;
+    let risk = |command: &str| {
+        let expanded = registry::expand_references(command, &references)
+            .unwrap_or_else(|_| command.to_string());
+        guard.classify(&expanded).map(|found| found.reason)
+    };
+    if format.is_text() {
+        print_commands(&commands, &details, &context, &risk);
+        return Ok(());
+    }
+
+    let keyed = registry.keyed_commands()?;
+    let keys: HashMap<&str, &str> = keyed
+        .iter()
+        .map(|(key, command)| (command.as_str(), key.as_str()))
+        .collect();
+    let items: Vec<ListItem> = commands
+        .iter()
+        .enumerate()
+        .map(|(index, command)| {
+            let detail = details.get(command);
+            let when = detail.and_then(|found| found.when.as_ref());
+            ListItem {
+                index: index + 1,
+                command,
+                risk: risk(command),
+                key: keys.get(command.as_str()).copied(),
+                description: detail.and_then(|found| found.description.as_deref()),
+                tags: detail.map(|found| found.tags.as_slice()).unwrap_or_default(),
+                when,
+                active: when.is_none_or(|condition| condition.holds(&context)),
+                cwd: detail.and_then(|found| found.cwd.as_deref()),
+                steps: detail.map(|found| found.steps.as_slice()).unwrap_or_default(),
+            }
+        })
+        .collect();
+    output::print_items(format, &items)
 }
 
 /// 登録済みコマンドを標準出力へ表示する。
 ///
 /// 件数が0の場合も明示的に表示する。
-fn print_commands(commands: &[String]) {
+/// 制御文字は見える形に置き換えて表示する。
+fn print_commands(
+    commands: &[String],
+    details: &BTreeMap<String, CommandDetails>,
+    context: &WhenContext,
+    risk: &dyn Fn(&str) -> Option<String>,
+) {
     if commands.is_empty() {
         println!("No registered commands.");
         return;
     }
 
+    let home = env::var_os("HOME").map(PathBuf::from);
     for (index, command) in commands.iter().enumerate() {
-        println!("{:>2}. {}", index + 1, command);
+        let line = format_entry(
+            index + 1,
+            command,
+            details.get(command),
+            risk(command).as_deref(),
+            context,
+            home.as_deref(),
+        );
+        println!("{line}");
     }
 }
+
+/// 一覧の1行を組み立てる。
+///
+/// 危険なコマンドには検索結果と同じく `(!) 理由` を添える。
+/// 説明とタグがあれば `# 説明 [タグ]` の形で後ろに添える。
+/// 条件があれば `(when ...)` を添え、満たさない場合は `inactive` と示す。
+/// 作業ディレクトリがあれば `(in ~/dir)` を、ワークフローなら手順の数を添える。
+fn format_entry(
+    number: usize,
+    command: &str,
+    details: Option<&CommandDetails>,
+    risk: Option<&str>,
+    context: &WhenContext,
+    home: Option<&Path>,
+) -> String {
+    let marker = risk.map(|reason| format!("  (!) {reason}")).unwrap_or_default();
+    let suffix = details
+        .map(|detail| format_details(detail, context, home))
+        .unwrap_or_default();
+    format!("{number:>2}. {}{marker}{suffix}", display_safe(command))
+}
+
+/// 説明、タグ、条件、作業ディレクトリ、手順の数を一覧の後ろに添える文字列にする。
+fn format_details(details: &CommandDetails, context: &WhenContext, home: Option<&Path>) -> String {
+    let mut parts = Vec::new();
+    if let Some(description) = &details.description {
+        parts.push(display_safe(description).into_owned());
+    }
+    if !details.tags.is_empty() {
+        parts.push(format!("[{}]", display_safe(&details.tags.join(", "))));
+    }
+    if let Some(condition) = &details.when {
+        let state = if condition.holds(context) { "" } else { "inactive, " };
+        parts.push(format!("({state}when {})", display_safe(&condition.to_string())));
+    }
+    if let Some(dir) = &details.cwd {
+        let shown = registry::display_dir(Path::new(dir), home);
+        parts.push(format!("(in {})", display_safe(&shown)));
+    }
+    if !details.steps.is_empty() {
+        parts.push(format!("(workflow, {} steps)", details.steps.len()));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("  # {}", parts.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,17 @@
+use super::super::format_entry;
+use crate::registry::{CommandDetails, WhenContext};
+
+#[test]
+fn risky_entries_are_marked() {
+    // 危険なコマンドには `(!) 理由` を添え、説明などはその後ろに続ける。
+    let context = WhenContext::default();
+    let details = CommandDetails {
+        description: Some("clean build".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        format_entry(3, "rm -rf target", Some(&details), Some("recursive delete"), &context, None),
+        " 3. rm -rf target  (!) recursive delete  # clean build"
+    );
+    assert_eq!(format_entry(12, "git status", None, None, &context, None), "12. git status");
+}
@@ -1,56 +1,61 @@
 use clap::Parser;
 use gclip_cli::add;
-use gclip_cli::cli;
+use gclip_cli::cli::{self, Action};
+use gclip_cli::completion;
 use gclip_cli::doctor;
+use gclip_cli::error::GclipError;
+use gclip_cli::export;
 use gclip_cli::list;
+use gclip_cli::prune;
+use gclip_cli::record;
 use gclip_cli::remove;
 use gclip_cli::search;
+use gclip_cli::serve;
+use gclip_cli::stats;
 use gclip_cli::suggest;
 
 fn main() {
     // mainはプロセス終了コードの責務だけを持ち、実処理はrunに委譲する。
+    // 終了コードはエラーの種類ごとに分け、ウィジェットやスクリプトが区別できるようにする。
     if let Err(err) = run() {
-        eprintln!("gclip: {err}");
-        std::process::exit(1);
+        match &err {
+            GclipError::Cancelled => eprintln!("Cancelled."),
+            other => eprintln!("gclip: {other}"),
+        }
+        std::process::exit(err.exit_code());
     }
 }
 
-fn run() -> Result<(), String> {
+fn run() -> Result<(), GclipError> {
     // CLI引数の解析はここで行い、各機能の実装はモジュールへ分離する。
-    let cli = cli::Cli::parse();
-    if cli.init {
-        search::print_init_script();
-        return Ok(());
+    let action = cli::Cli::parse().into_action();
+    match action {
+        Action::Search { query, options } => search::run(&query, &options),
+        Action::Recent { options } => search::run_recent(&options),
+        Action::Add { command, options } => add::run(&command, &options),
+        Action::AddLast { nth, options } => add::run_last(nth, &options),
+        Action::AddBulk { nul, format, options } => add::run_bulk(nul, format, &options),
+        Action::AddWorkflow { steps, options } => add::run_workflow(&steps, &options),
+        Action::List { options } => list::run(&options.format),
+        Action::Remove { query, options } => remove::run(&query, options.select.as_deref()),
+        Action::Suggest { options, source } => suggest::run(&options, &source),
+        Action::Doctor { options, fix } => doctor::run(&options.format, fix),
+        Action::Stats { options } => stats::run(&options.format),
+        Action::Prune { unused_for, options } => prune::run(unused_for, &options),
+        Action::Restore { query, options } => prune::run_restore(query.as_deref(), &options),
+        Action::Workflow { cancel } => search::run_workflow(cancel),
+        Action::Init { record } => {
+            search::print_init_script(record);
+            Ok(())
+        }
+        Action::ZshWidget => {
+            search::print_zsh_widget();
+            Ok(())
+        }
+        Action::ExportAliases { shell } => export::run(&shell),
+        Action::Completions { shell } => completion::print_completions(&shell),
+        Action::Complete { word } => completion::run_complete(&word),
+        Action::Serve => serve::run(),
+        Action::Record { command, options } => record::run(&command, &options),
     }
-
-    if cli.zsh_widget {
-        search::print_zsh_widget();
-        return Ok(());
-    }
-
-    if cli.suggest {
-        return suggest::run();
-    }
-
-    if cli.doctor {
-        return doctor::run();
-    }
-
-    if cli.list {
-        return list::run();
-    }
-
-    if let Some(command) = cli.remove {
-        return remove::run(&command);
-    }
-
-    if let Some(command) = cli.add {
-        return add::run(&command);
-    }
-
-    if let Some(query) = cli.query {
-        return search::run(&query);
-    }
-
-    search::run_recent()
 }
@@ -0,0 +1,143 @@
+mod sanitize;
+mod template;
+
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{self, Write};
+
+use crate::error::GclipError;
+
+pub use sanitize::{display_safe, has_control_chars};
+
+/// 機械処理向けの出力形式。
+///
+/// `--json` / `--print0` / `--format` から決まり、
+/// 何も指定されない場合は従来どおり人向けのテキストで出力する。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    Print0,
+    Template(String),
+}
+
+impl OutputFormat {
+    /// CLIのフラグから出力形式を決める。
+    ///
+    /// フラグ同士の排他はclap側で保証されている前提とする。
+    pub fn from_flags(json: bool, print0: bool, template: Option<String>) -> Self {
+        if json {
+            Self::Json
+        } else if print0 {
+            Self::Print0
+        } else if let Some(format) = template {
+            Self::Template(format)
+        } else {
+            Self::Text
+        }
+    }
+
+    /// 人向けのテキスト出力かどうかを返す。
+    pub fn is_text(&self) -> bool {
+        matches!(self, Self::Text)
+    }
+}
+
+/// 非対話で実行するための出力/選択の指定。
+///
+/// - `format`: 一覧や結果の出力形式
+/// - `select`: プロンプトの代わりに使う選択入力（`--select` / `--first`）
+/// - `assume_yes`: 確認を求めずに同意したものとして扱う（`--yes`）
+#[derive(Debug, Clone, Default)]
+pub struct OutputOptions {
+    pub format: OutputFormat,
+    pub select: Option<String>,
+    pub assume_yes: bool,
+}
+
+impl OutputOptions {
+    /// プロンプトを出さずに一覧だけを出力すべきかを返す。
+    ///
+    /// 機械処理向けの形式で、選択が指定されていない場合に該当する。
+    pub fn list_only(&self) -> bool {
+        !self.format.is_text() && self.select.is_none()
+    }
+}
+
+/// 複数件の結果を指定形式で標準出力へ出力する。
+///
+/// - JSON: 配列として1行で出力する
+/// - print0: `command` フィールドをNUL区切りで出力する
+/// - テンプレート: 1件ごとに展開して改行で区切る
+pub(crate) fn print_items<T: Serialize>(format: &OutputFormat, items: &[T]) -> Result<(), GclipError> {
+    let values = items.iter().map(to_value).collect::<Result<Vec<_>, _>>()?;
+    let rendered = match format {
+        OutputFormat::Text => return Err("text output is handled by each command".into()),
+        OutputFormat::Json => format!("{}\n", Value::Array(values)),
+        OutputFormat::Print0 => values.iter().map(render_print0).collect(),
+        OutputFormat::Template(pattern) => render_template_lines(pattern, &values)?,
+    };
+    write_stdout(&rendered)
+}
+
+/// 1件の結果を指定形式で標準出力へ出力する。
+///
+/// JSONではオブジェクトとして出力し、それ以外は `print_items` と同じ規則で出力する。
+pub(crate) fn print_item<T: Serialize>(format: &OutputFormat, item: &T) -> Result<(), GclipError> {
+    let value = to_value(item)?;
+    let rendered = match format {
+        OutputFormat::Text => return Err("text output is handled by each command".into()),
+        OutputFormat::Json => format!("{value}\n"),
+        OutputFormat::Print0 => render_print0(&value),
+        OutputFormat::Template(pattern) => render_template_lines(pattern, &[value])?,
+    };
+    write_stdout(&rendered)
+}
+
+/// 構造体をJSONの値へ変換する。
+fn to_value<T: Serialize>(item: &T) -> Result<Value, GclipError> {
+    serde_json::to_value(item).map_err(|err| GclipError::Other(format!("failed to serialize output: {err}")))
+}
+
+/// NUL区切りの出力を組み立てる。
+///
+/// `command` フィールドがあればその値だけを出力し、
+/// なければ `field=value` を1件ずつNUL区切りで出力する。
+fn render_print0(value: &Value) -> String {
+    if let Some(command) = value.get("command") {
+        return format!("{}\0", template::value_to_text(command));
+    }
+
+    match value {
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(name, field)| format!("{name}={}\0", template::value_to_text(field)))
+            .collect(),
+        other => format!("{}\0", template::value_to_text(other)),
+    }
+}
+
+/// テンプレートを各件に適用し、改行区切りで連結する。
+fn render_template_lines(pattern: &str, values: &[Value]) -> Result<String, GclipError> {
+    let mut rendered = String::new();
+    for value in values {
+        rendered.push_str(&template::render_template(pattern, value)?);
+        rendered.push('\n');
+    }
+    Ok(rendered)
+}
+
+/// 標準出力へ書き込み、フラッシュする。
+///
+/// パイプ先が閉じられた場合もエラーとして上位に返す。
+fn write_stdout(rendered: &str) -> Result<(), GclipError> {
+    let mut stdout = io::stdout().lock();
+    stdout
+        .write_all(rendered.as_bytes())
+        .and_then(|_| stdout.flush())
+        .map_err(|err| GclipError::io("failed to write stdout", err))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,44 @@
+use std::borrow::Cow;
+
+/// 端末へ表示するために制御文字を見える形へ置き換える。
+///
+/// - C0制御文字とDELはキャレット記法（`ESC` → `^[`、`BEL` → `^G`）にする
+/// - C1制御文字と双方向テキストの制御文字は `<U+XXXX>` にする
+///
+/// ANSI/OSCのエスケープシーケンスで表示を偽装したり、
+/// 端末の状態を変えたりできないようにする。制御文字がなければ複製しない。
+pub fn display_safe(text: &str) -> Cow<'_, str> {
+    if !has_control_chars(text) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut safe = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\u{0}'..='\u{1f}' => {
+                safe.push('^');
+                safe.push(char::from(ch as u8 + b'@'));
+            }
+            '\u{7f}' => safe.push_str("^?"),
+            special if is_special_control(special) => {
+                safe.push_str(&format!("<U+{:04X}>", special as u32));
+            }
+            other => safe.push(other),
+        }
+    }
+    Cow::Owned(safe)
+}
+
+/// 表示を変えてしまう制御文字を含むかどうかを返す。
+pub fn has_control_chars(text: &str) -> bool {
+    text.chars()
+        .any(|ch| ch.is_ascii_control() || is_special_control(ch))
+}
+
+/// C1制御文字と双方向テキストの制御文字かどうかを返す。
+fn is_special_control(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{80}'..='\u{9f}' | '\u{200e}' | '\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}'
+    )
+}
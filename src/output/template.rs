@@ -0,0 +1,55 @@
+use serde_json::Value;
+
+use crate::error::GclipError;
+
+/// `{field}` 形式のプレースホルダを値で置き換える。
+///
+/// - `{{` / `}}` はそれぞれ `{` / `}` として出力する
+/// - `\t` / `\n` / `\0` はエスケープとして展開する
+/// - 存在しないフィールド名はエラーにする
+pub(crate) fn render_template(pattern: &str, value: &Value) -> Result<String, GclipError> {
+    let mut rendered = String::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                rendered.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                rendered.push('}');
+            }
+            '{' => {
+                let name: String = chars.by_ref().take_while(|next| *next != '}').collect();
+                let field = value
+                    .get(name.trim())
+                    .ok_or_else(|| format!("unknown field in format: {{{name}}}"))?;
+                rendered.push_str(&value_to_text(field));
+            }
+            '\\' => match chars.next() {
+                Some('t') => rendered.push('\t'),
+                Some('n') => rendered.push('\n'),
+                Some('0') => rendered.push('\0'),
+                Some(other) => {
+                    rendered.push('\\');
+                    rendered.push(other);
+                }
+                None => rendered.push('\\'),
+            },
+            other => rendered.push(other),
+        }
+    }
+    Ok(rendered)
+}
+
+/// JSONの値をテンプレート展開用の文字列に変換する。
+///
+/// 文字列は引用符なし、`null` は空文字列として扱う。
+pub(crate) fn value_to_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,2 @@
+mod sanitize;
+mod template;
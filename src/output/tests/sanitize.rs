@@ -0,0 +1,27 @@
+use super::super::sanitize::{display_safe, has_control_chars};
+
+#[test]
+fn plain_text_is_borrowed() {
+    // 制御文字がなければそのまま返す。
+    assert!(!has_control_chars("git log --oneline | head"));
+    assert_eq!(display_safe("git status"), "git status");
+}
+
+#[test]
+fn escape_sequences_become_visible() {
+    // ANSI/OSCのエスケープシーケンスがキャレット記法になることを確認する。
+    assert_eq!(display_safe("echo \x1b[2Jhi"), "echo ^[[2Jhi");
+    assert_eq!(
+        display_safe("ls\x1b]0;title\x07"),
+        "ls^[]0;title^G"
+    );
+    assert_eq!(display_safe("a\tb\nc\x7f"), "a^Ib^Jc^?");
+}
+
+#[test]
+fn c1_and_bidi_controls_become_visible() {
+    // C1制御文字と双方向テキストの制御文字をコードポイントで表示する。
+    assert!(has_control_chars("ls \u{202e}txt.exe"));
+    assert_eq!(display_safe("ls \u{202e}x"), "ls <U+202E>x");
+    assert_eq!(display_safe("\u{9b}31m"), "<U+009B>31m");
+}
@@ -0,0 +1,33 @@
+use super::super::template::render_template;
+use serde_json::json;
+
+#[test]
+fn replaces_fields() {
+    // フィールド名のプレースホルダが値に置き換わることを確認する。
+    let value = json!({"index": 1, "command": "git status"});
+    let rendered = render_template("{index}:{command}", &value).expect("should render");
+    assert_eq!(rendered, "1:git status");
+}
+
+#[test]
+fn expands_escapes_and_braces() {
+    // タブのエスケープと二重波括弧が展開されることを確認する。
+    let value = json!({"command": "ls"});
+    let rendered = render_template(r"{{x}}\t{command}", &value).expect("should render");
+    assert_eq!(rendered, "{x}\tls");
+}
+
+#[test]
+fn null_is_rendered_as_empty() {
+    // 値がnullのフィールドは空文字列になる。
+    let value = json!({"key": null});
+    let rendered = render_template("[{key}]", &value).expect("should render");
+    assert_eq!(rendered, "[]");
+}
+
+#[test]
+fn unknown_field_is_error() {
+    // 存在しないフィールドはエラーにする。
+    let value = json!({"command": "ls"});
+    assert!(render_template("{nope}", &value).is_err());
+}
@@ -0,0 +1,60 @@
+use crate::clock;
+use crate::output::display_safe;
+use crate::registry::UsageSnapshot;
+
+/// 削除候補の1件分。
+///
+/// `missing` は実行ファイルが見つからないことが理由の場合に真になる。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PruneCandidate {
+    pub(crate) command: String,
+    pub(crate) reason: String,
+    pub(crate) missing: bool,
+}
+
+/// 使用状況から削除候補を選ぶ。
+///
+/// - 実行ファイルが見つからないもの（`missing` が名前を返すもの）
+/// - `unused_for_days` が指定された場合、その期間に挿入されていないもの
+///   （未使用なら登録日から数える。日付が分からないものは対象にしない）
+///
+/// 候補は登録順に返す。
+pub(crate) fn find_candidates(
+    snapshot: &UsageSnapshot,
+    now: u64,
+    unused_for_days: Option<u64>,
+    missing: impl Fn(&str) -> Option<String>,
+) -> Vec<PruneCandidate> {
+    let cutoff = unused_for_days.map(|days| now.saturating_sub(days * clock::SECONDS_PER_DAY));
+    snapshot
+        .commands
+        .iter()
+        .filter_map(|command| {
+            let not_found = missing(command);
+            let reason = match &not_found {
+                Some(name) => Some(format!("{} not found", display_safe(name))),
+                None => cutoff.and_then(|limit| stale_reason(snapshot, command, limit)),
+            }?;
+            Some(PruneCandidate {
+                command: command.clone(),
+                reason,
+                missing: not_found.is_some(),
+            })
+        })
+        .collect()
+}
+
+/// 期限より前から使われていなければ、その理由を返す。
+fn stale_reason(snapshot: &UsageSnapshot, command: &str, cutoff: u64) -> Option<String> {
+    match snapshot.usage.get(command).filter(|record| record.count > 0) {
+        Some(record) if record.last_used < cutoff => Some(format!(
+            "last used {}",
+            clock::format_date(record.last_used)
+        )),
+        Some(_) => None,
+        None => {
+            let added = *snapshot.added.get(command)?;
+            (added < cutoff).then(|| format!("never used since {}", clock::format_date(added)))
+        }
+    }
+}
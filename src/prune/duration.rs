@@ -0,0 +1,28 @@
+use crate::error::GclipError;
+
+/// `90d` のような期間指定を日数に変換する。
+///
+/// - `d`: 日、`w`: 週、`m`: 30日、`y`: 365日
+/// - 単位を省略した場合は日数として扱う
+/// - 0日は指定ミスとみなしてエラーにする
+pub fn parse_days(text: &str) -> Result<u64, GclipError> {
+    let trimmed = text.trim();
+    let (number, unit_days) = match trimmed.char_indices().last() {
+        Some((index, 'd')) => (&trimmed[..index], 1),
+        Some((index, 'w')) => (&trimmed[..index], 7),
+        Some((index, 'm')) => (&trimmed[..index], 30),
+        Some((index, 'y')) => (&trimmed[..index], 365),
+        _ => (trimmed, 1),
+    };
+    let count: u64 = number
+        .parse()
+        .map_err(|_| {
+            GclipError::InvalidInput(format!("invalid period: {text} (e.g. 90d, 12w, 6m, 1y)"))
+        })?;
+    if count == 0 {
+        return Err(GclipError::InvalidInput(format!(
+            "period must be longer than zero: {text}"
+        )));
+    }
+    Ok(count * unit_days)
+}
@@ -0,0 +1,84 @@
+use std::env;
+use std::path::Path;
+
+use crate::export::find_executable;
+use crate::shell::{self, ShellNames};
+
+/// 後ろに続くコマンドを実行するためのラッパー。
+///
+/// `sudo make install` の場合は `make` を判定の対象にする。
+const WRAPPERS: &[&str] = &["sudo", "env", "nohup", "nice", "time", "noglob", "command", "exec"];
+
+/// コマンドの実行ファイルが見つからない場合、その名前を返す。
+///
+/// 判定できないもの（組み込みコマンドや変数展開など）と、
+/// シェルのエイリアスや関数として `names` にあるものは見つかったものとみなす。
+pub(crate) fn missing_executable(command: &str, names: &ShellNames) -> Option<String> {
+    let name = executable_name(command)?;
+    let found = if names.contains(&name) {
+        true
+    } else if name.contains('/') {
+        expand_home(&name).is_file()
+    } else {
+        find_executable(&name).is_some()
+    };
+    (!found).then_some(name)
+}
+
+/// コマンド文字列から実行ファイル名を取り出す。
+///
+/// - 先頭の `VAR=value` は読み飛ばす
+/// - `sudo` などのラッパーは、オプションでない次の語を対象にする
+/// - 組み込みコマンド、変数展開、サブシェル、相対パスなどは `None` を返す
+pub(crate) fn executable_name(command: &str) -> Option<String> {
+    let mut words = command.split_whitespace().peekable();
+    while let Some(word) = words.peek() {
+        if is_assignment(word) {
+            words.next();
+        } else {
+            break;
+        }
+    }
+
+    let mut name = strip_quotes(words.next()?);
+    while WRAPPERS.contains(&name.as_str()) {
+        match words.next() {
+            Some(next) if !next.starts_with('-') && !is_assignment(next) => {
+                name = strip_quotes(next);
+            }
+            _ => break,
+        }
+    }
+
+    let undeterminable = name.is_empty()
+        || shell::is_builtin(&name)
+        || name.starts_with(['(', '{', '@'])
+        || name.contains(['$', '`', '*', '?'])
+        || (name.contains('/') && !name.starts_with(['/', '~']));
+    (!undeterminable).then_some(name)
+}
+
+/// `VAR=value` 形式の語かどうかを返す。
+fn is_assignment(word: &str) -> bool {
+    match word.split_once('=') {
+        Some((name, _)) => {
+            !name.is_empty() && name.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        }
+        None => false,
+    }
+}
+
+/// 前後の引用符と末尾の `;` を除く。
+fn strip_quotes(word: &str) -> String {
+    word.trim_end_matches(';')
+        .trim_matches(|ch| ch == '"' || ch == '\'')
+        .to_string()
+}
+
+/// 先頭の `~/` をホームディレクトリに展開する。
+fn expand_home(name: &str) -> std::path::PathBuf {
+    match (name.strip_prefix("~/"), env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest),
+        _ => Path::new(name).to_path_buf(),
+    }
+}
@@ -0,0 +1,125 @@
+mod candidate;
+mod duration;
+mod executable;
+mod restore;
+
+use crate::clock;
+use crate::error::GclipError;
+use crate::output::{display_safe, OutputOptions};
+use crate::registry::Registry;
+use crate::selection;
+use crate::shell::ShellNames;
+use crate::suggest::parse_multi_selection;
+use candidate::PruneCandidate;
+
+pub use duration::parse_days;
+pub use restore::run as run_restore;
+
+/// 使われなくなった登録済みコマンドをゴミ箱へ移す。
+///
+/// `gclip prune --unused-for 90d` で実行する。
+/// 実行ファイルが見つからないコマンドは期間に関係なく候補にする。
+/// 移したコマンドは `gclip restore` で元に戻せる。
+/// `--yes` なら全候補を、`--select` なら指定した候補を確認なしで移す。
+/// ただし `gclip init` の関数からエイリアスや関数の一覧を受け取っていない場合、
+/// 実行ファイルが見つからないだけの候補は `--yes` でも移さない。
+pub fn run(unused_for_days: Option<u64>, options: &OutputOptions) -> Result<(), GclipError> {
+    let registry = Registry::open()?;
+    let snapshot = registry.usage_snapshot()?;
+    let names = ShellNames::from_env();
+    let candidates = candidate::find_candidates(
+        &snapshot,
+        clock::now_secs(),
+        unused_for_days,
+        |command| executable::missing_executable(command, &names),
+    );
+    if candidates.is_empty() {
+        println!("Nothing to prune.");
+        return Ok(());
+    }
+    print_candidates(&candidates);
+
+    let selected = select_candidates(&candidates, options, names.is_provided())?;
+    if selected.is_empty() {
+        // `--yes` で全候補を残した場合はキャンセルではない。
+        if options.assume_yes {
+            println!("Nothing was pruned.");
+            return Ok(());
+        }
+        return Err(GclipError::Cancelled);
+    }
+
+    let (trash_path, moved) = registry.move_to_trash(&selected)?;
+    println!("Moved {moved} command(s) to {}", trash_path.display());
+    println!("Run `gclip restore` to bring them back.");
+
+    // 登録日のない古いコマンドは、実際に整理したときだけ今日を登録日として記録する。
+    let dated = registry.backfill_added_dates()?;
+    if dated > 0 {
+        println!("Recorded today as the registration date of {dated} older command(s).");
+    }
+    Ok(())
+}
+
+/// 削除候補を理由付きで表示する。
+fn print_candidates(candidates: &[PruneCandidate]) {
+    println!("Prune candidates:");
+    for (index, found) in candidates.iter().enumerate() {
+        println!(
+            "{:>2}. {}  ({})",
+            index + 1,
+            display_safe(&found.command),
+            found.reason
+        );
+    }
+}
+
+/// 移す候補を決め、`(コマンド, 理由)` の組で返す。
+///
+/// 空入力は空配列を返し、呼び出し側でキャンセルとして扱う。
+/// `--yes` では、`trust_missing` が偽なら実行ファイルが見つからないだけの候補を除く。
+fn select_candidates(
+    candidates: &[PruneCandidate],
+    options: &OutputOptions,
+    trust_missing: bool,
+) -> Result<Vec<(String, String)>, GclipError> {
+    let indices = if options.assume_yes {
+        let (kept, auto): (Vec<usize>, Vec<usize>) = (1..=candidates.len())
+            .partition(|index| candidates[index - 1].missing && !trust_missing);
+        if !kept.is_empty() {
+            eprintln!(
+                "Kept {} command(s) not found on PATH: they may be aliases or functions. Run prune from a shell with `gclip init` loaded, or pick them with --select.",
+                kept.len()
+            );
+        }
+        auto
+    } else if let Some(input) = options.select.as_deref() {
+        parse_multi_selection(input, candidates.len())?
+    } else {
+        prompt_indices("prune", candidates.len())?
+    };
+
+    Ok(indices
+        .iter()
+        .filter_map(|index| candidates.get(index - 1))
+        .map(|found| (found.command.clone(), found.reason.clone()))
+        .collect())
+}
+
+/// 対話的に対象のコマンドを選んでもらう。
+///
+/// `verb` はプロンプトに表示する操作名。不正な入力があった場合は再入力を促す。
+fn prompt_indices(verb: &str, max: usize) -> Result<Vec<usize>, GclipError> {
+    loop {
+        let input = selection::prompt_line(&format!(
+            "Select commands to {verb} (1-{max}, e.g. 1,3 or 1-3, 'all', empty to cancel)"
+        ))?;
+        match parse_multi_selection(&input, max) {
+            Ok(indices) => return Ok(indices),
+            Err(err) => eprintln!("Invalid selection: {err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
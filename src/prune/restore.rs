@@ -0,0 +1,75 @@
+use super::prompt_indices;
+use crate::clock;
+use crate::error::GclipError;
+use crate::output::{display_safe, OutputOptions};
+use crate::registry::{Registry, TrashEntry};
+use crate::suggest::parse_multi_selection;
+
+/// `gclip prune` でゴミ箱に移したコマンドを登録し直す。
+///
+/// `query` を含むエントリだけを候補にする。
+/// 短縮キー、説明、タグ、条件、作業ディレクトリ、ワークフローの手順もあわせて戻す。
+/// `--yes` なら全候補を、`--select` なら指定した候補を確認なしで戻す。
+pub fn run(query: Option<&str>, options: &OutputOptions) -> Result<(), GclipError> {
+    let registry = Registry::open()?;
+    let entries = registry.trash_entries()?;
+    let candidates: Vec<(usize, &TrashEntry)> = entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| query.is_none_or(|text| entry.command.contains(text)))
+        .collect();
+    if candidates.is_empty() {
+        println!("Nothing to restore.");
+        return Ok(());
+    }
+    print_entries(&candidates);
+
+    let indices = if options.assume_yes {
+        (1..=candidates.len()).collect()
+    } else if let Some(input) = options.select.as_deref() {
+        parse_multi_selection(input, candidates.len())?
+    } else {
+        prompt_indices("restore", candidates.len())?
+    };
+    let positions: Vec<usize> = indices
+        .iter()
+        .filter_map(|index| candidates.get(index - 1))
+        .map(|(position, _)| *position)
+        .collect();
+    if positions.is_empty() {
+        return Err(GclipError::Cancelled);
+    }
+
+    let (registry_path, restored) = registry.restore_from_trash(&positions)?;
+    println!(
+        "Restored {} command(s) to {}",
+        restored.count,
+        registry_path.display()
+    );
+    for key in &restored.taken_keys {
+        eprintln!(
+            "Key '{}' is now used by another command and was not restored.",
+            display_safe(key)
+        );
+    }
+    Ok(())
+}
+
+/// ゴミ箱のエントリを移した日と理由付きで表示する。
+fn print_entries(candidates: &[(usize, &TrashEntry)]) {
+    println!("Trashed commands:");
+    for (index, (_, entry)) in candidates.iter().enumerate() {
+        let key = entry
+            .key
+            .as_deref()
+            .map(|key| format!("[{}] ", display_safe(key)))
+            .unwrap_or_default();
+        println!(
+            "{:>2}. {key}{}  ({}, {})",
+            index + 1,
+            display_safe(&entry.command),
+            display_safe(&entry.reason),
+            clock::format_date(entry.removed_at)
+        );
+    }
+}
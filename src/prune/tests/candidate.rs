@@ -0,0 +1,55 @@
+use super::super::candidate::find_candidates;
+use crate::clock::SECONDS_PER_DAY;
+use crate::registry::{UsageRecord, UsageSnapshot};
+
+const NOW: u64 = 1_000 * SECONDS_PER_DAY;
+
+fn snapshot() -> UsageSnapshot {
+    let mut snapshot = UsageSnapshot {
+        commands: ["recent", "stale", "never", "fresh", "undated", "gone"]
+            .map(String::from)
+            .to_vec(),
+        ..Default::default()
+    };
+    for (command, last_day) in [("recent", 990), ("stale", 800)] {
+        snapshot.usage.insert(
+            command.into(),
+            UsageRecord {
+                count: 1,
+                last_used: last_day * SECONDS_PER_DAY,
+                ..Default::default()
+            },
+        );
+    }
+    snapshot.added.insert("never".into(), 700 * SECONDS_PER_DAY);
+    snapshot.added.insert("fresh".into(), 980 * SECONDS_PER_DAY);
+    snapshot.added.insert("gone".into(), 999 * SECONDS_PER_DAY);
+    snapshot
+}
+
+fn missing(command: &str) -> Option<String> {
+    (command == "gone").then(|| command.to_string())
+}
+
+#[test]
+fn finds_stale_and_missing_commands() {
+    // 期間内に使われていないものと、実行ファイルがないものが候補になる。
+    let candidates = find_candidates(&snapshot(), NOW, Some(90), missing);
+    let commands: Vec<&str> = candidates.iter().map(|found| found.command.as_str()).collect();
+    assert_eq!(commands, vec!["stale", "never", "gone"]);
+    assert!(candidates[0].reason.starts_with("last used"));
+    assert!(candidates[1].reason.starts_with("never used since"));
+    assert_eq!(candidates[2].reason, "gone not found");
+    assert_eq!(
+        candidates.iter().map(|found| found.missing).collect::<Vec<_>>(),
+        vec![false, false, true]
+    );
+}
+
+#[test]
+fn without_period_only_missing_commands_are_candidates() {
+    // 期間を指定しない場合は実行ファイルがないものだけが候補になる。
+    let candidates = find_candidates(&snapshot(), NOW, None, missing);
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].command, "gone");
+}
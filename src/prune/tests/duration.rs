@@ -0,0 +1,20 @@
+use super::super::parse_days;
+
+#[test]
+fn parses_units() {
+    // 単位ごとに日数へ変換されることを確認する。
+    assert_eq!(parse_days("90d"), Ok(90));
+    assert_eq!(parse_days("2w"), Ok(14));
+    assert_eq!(parse_days("6m"), Ok(180));
+    assert_eq!(parse_days("1y"), Ok(365));
+    assert_eq!(parse_days("30"), Ok(30));
+}
+
+#[test]
+fn rejects_invalid_periods() {
+    // 数値でないものや0日はエラーになる。
+    assert!(parse_days("ninety").is_err());
+    assert!(parse_days("90h").is_err());
+    assert!(parse_days("0d").is_err());
+    assert!(parse_days("").is_err());
+}
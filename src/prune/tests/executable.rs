@@ -0,0 +1,45 @@
+use super::super::executable::{executable_name, missing_executable};
+use crate::shell::ShellNames;
+
+#[test]
+fn skips_assignments_and_wrappers() {
+    // 環境変数の代入とラッパーを読み飛ばして実行ファイル名を取り出す。
+    assert_eq!(executable_name("git status").as_deref(), Some("git"));
+    assert_eq!(executable_name("RUST_LOG=debug cargo run").as_deref(), Some("cargo"));
+    assert_eq!(executable_name("sudo make install").as_deref(), Some("make"));
+    assert_eq!(executable_name("sudo -u app ls").as_deref(), Some("sudo"));
+    assert_eq!(executable_name("\"/opt/tool/bin/run\" --x").as_deref(), Some("/opt/tool/bin/run"));
+}
+
+#[test]
+fn undeterminable_commands_are_none() {
+    // 組み込みコマンドや展開を含むもの、相対パスは判定しない。
+    assert_eq!(executable_name("cd ~/src && make"), None);
+    assert_eq!(executable_name("$EDITOR notes.md"), None);
+    assert_eq!(executable_name("(cd app && make)"), None);
+    assert_eq!(executable_name("./scripts/deploy.sh"), None);
+    assert_eq!(executable_name("FOO=1"), None);
+}
+
+#[test]
+fn zsh_builtins_are_not_executables() {
+    // zshの組み込みコマンドはPATHになくても使えるため判定しない。
+    assert_eq!(executable_name("print -P '%F{red}hi'"), None);
+    assert_eq!(executable_name("autoload -Uz compinit"), None);
+    assert_eq!(executable_name("kill -9 1234"), None);
+    assert_eq!(executable_name("disown %1"), None);
+}
+
+#[test]
+fn shell_aliases_and_functions_are_found() {
+    // シェルから受け取ったエイリアスや関数は、PATHになくても見つかったものとみなす。
+    let names = ShellNames::from_list("ll\nmy_deploy\n");
+    assert!(names.is_provided());
+    assert_eq!(missing_executable("ll -a", &names), None);
+    assert_eq!(missing_executable("my_deploy staging", &names), None);
+    assert_eq!(
+        missing_executable("gclip-surely-missing-tool --x", &names).as_deref(),
+        Some("gclip-surely-missing-tool")
+    );
+    assert!(!ShellNames::default().is_provided());
+}
@@ -0,0 +1,3 @@
+mod candidate;
+mod duration;
+mod executable;
@@ -0,0 +1,29 @@
+use crate::clock;
+use crate::error::GclipError;
+use crate::registry::{Execution, Registry};
+
+/// `gclip record` の記録オプション。
+///
+/// - `cwd`: 実行したディレクトリ（`--cwd`）
+/// - `exit`: 終了コード（`--exit`）
+/// - `duration_ms`: 所要時間（`--duration`、ミリ秒）
+#[derive(Debug, Clone, Default)]
+pub struct RecordOptions {
+    pub cwd: Option<String>,
+    pub exit: i32,
+    pub duration_ms: u64,
+}
+
+/// シェルのフックから実行されたコマンドを記録する機能。
+///
+/// `gclip init --record` の `precmd` フックが毎回呼び出すため、
+/// 実行履歴への追記だけを行い、何も出力しない。
+pub fn run(command: &str, options: &RecordOptions) -> Result<(), GclipError> {
+    Registry::open()?.record_execution(&Execution {
+        command: command.trim().to_string(),
+        cwd: options.cwd.clone().filter(|cwd| !cwd.is_empty()),
+        exit: options.exit,
+        duration_ms: options.duration_ms,
+        at: clock::now_secs(),
+    })
+}
@@ -1,24 +1,130 @@
 use std::path::PathBuf;
 
-use super::Registry;
+use super::storage::{Storage, StoreFile};
+use super::details::{self, CommandDetails};
+use super::{conditions, io, key, merge, RegisteredCommands};
+use crate::error::GclipError;
+
+/// 推薦されたコマンドを登録ファイルへ追記する。
+///
+/// - すでに登録済みのコマンドは重複登録しない。
+/// - 追加件数と登録ファイルのパスを返す。
+pub(super) fn register_commands(
+    storage: &dyn Storage,
+    commands: &[String],
+) -> Result<(PathBuf, usize), GclipError> {
+    register_with_details(storage, commands, &CommandDetails::default())
+}
+
+/// 複数のコマンドを登録し、新たに登録したものに説明とタグ、条件、作業ディレクトリを付ける。
+///
+/// 既に登録済みのコマンドの付加情報は変更しない。
+/// 変更があった場合だけ、1回で書き込む。
+pub(super) fn register_with_details(
+    storage: &dyn Storage,
+    commands: &[String],
+    extra: &CommandDetails,
+) -> Result<(PathBuf, usize), GclipError> {
+    let mut registered = io::load_registry(storage)?;
+    let before = registered.commands.len();
+    let added = merge::merge_commands(&mut registered, commands);
+    let new_commands = registered.commands[before..].to_vec();
+    for command in &new_commands {
+        apply_extra(&mut registered, command, extra, false);
+    }
+
+    if added > 0 {
+        io::write_registry(storage, &registered)?;
+    }
+
+    Ok((storage.location(StoreFile::Registry), added))
+}
+
+/// 説明とタグ、条件、作業ディレクトリを反映し、変更があったかを返す。
+///
+/// `replace_when` が真なら既存の条件を消してから設定する。手順は扱わない。
+pub(super) fn apply_extra(
+    registered: &mut RegisteredCommands,
+    command: &str,
+    extra: &CommandDetails,
+    replace_when: bool,
+) -> bool {
+    let mut changed = details::apply_details(registered, command, extra.description.as_deref(), &extra.tags);
+    if replace_when {
+        changed |= registered.when.remove(command).is_some();
+    }
+    if let Some(condition) = &extra.when {
+        changed |= conditions::apply_condition(registered, command, condition);
+    }
+    if let Some(dir) = &extra.cwd
+        && registered.cwd.get(command) != Some(dir)
+    {
+        registered.cwd.insert(command.to_string(), dir.clone());
+        changed = true;
+    }
+    changed
+}
 
 /// 1件のコマンド文字列を登録する。
 ///
 /// 入力を正規化してから既存登録処理に委譲する。
-pub(super) fn add_command(command: &str) -> Result<(PathBuf, usize), String> {
+pub(super) fn add_command(
+    storage: &dyn Storage,
+    command: &str,
+) -> Result<(PathBuf, usize), GclipError> {
+    let normalized = normalize_command(command)?;
+    let commands = build_single_command(normalized);
+    register_commands(storage, &commands)
+}
+
+/// 1件のコマンド文字列を短縮キー付きで登録する。
+///
+/// コマンドの追加とキーの割り当てを1回の書き込みで行い、
+/// キーが不正な場合はコマンドも登録しない。
+pub(super) fn add_command_with_key(
+    storage: &dyn Storage,
+    command: &str,
+    key: &str,
+) -> Result<(PathBuf, usize), GclipError> {
+    add_with_details(storage, command, Some(key), &CommandDetails::default(), false)
+}
+
+/// 1件のコマンド文字列を登録し、短縮キーと説明・タグ・条件・作業ディレクトリを付ける。
+///
+/// 登録済みのコマンドでも付加情報は反映する。すべて1回の書き込みで行い、
+/// キーが不正または使用中ならコマンドも登録しない。
+pub(super) fn add_with_details(
+    storage: &dyn Storage,
+    command: &str,
+    key: Option<&str>,
+    extra: &CommandDetails,
+    replace_when: bool,
+) -> Result<(PathBuf, usize), GclipError> {
     let normalized = normalize_command(command)?;
+    let valid_key = key.map(key::validate_key).transpose()?;
+
+    let mut registered = io::load_registry(storage)?;
     let commands = build_single_command(normalized);
-    Registry::register_commands(&commands)
+    let added = merge::merge_commands(&mut registered, &commands);
+    if let Some(short_key) = valid_key.as_deref() {
+        key::assign_key(&mut registered, &commands[0], short_key)?;
+    }
+    let changed = apply_extra(&mut registered, &commands[0], extra, replace_when);
+    if added > 0 || valid_key.is_some() || changed {
+        io::write_registry(storage, &registered)?;
+    }
+
+    Ok((storage.location(StoreFile::Registry), added))
 }
 
 /// コマンド文字列の正規化を行う。
 ///
 /// - 前後の空白を除去する
 /// - 空文字列はエラーにする
-pub(super) fn normalize_command(command: &str) -> Result<String, String> {
+pub(crate) fn normalize_command(command: &str) -> Result<String, GclipError> {
     let trimmed = command.trim();
     if trimmed.is_empty() {
-        Err("command is empty".to_string())
+        Err(GclipError::InvalidInput("command is empty".to_string()))
     } else {
         Ok(trimmed.to_string())
     }
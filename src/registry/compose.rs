@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+
+use crate::error::GclipError;
+
+/// コマンド中の `@キー` を、そのキーが割り当てられたコマンドに置き換える。
+///
+/// - 単語の先頭にある `@キー` だけを参照とみなし、引用符の中やエスケープされた `@` は置き換えない
+/// - 置き換えたコマンドに含まれる参照も再帰的に展開する
+/// - 参照が循環している場合はエラーにする
+/// - 割り当てのないキーはそのまま残す（`curl -d @body.json` など）
+///
+/// `keys` はキーからコマンドへの対応。
+pub fn expand_references(
+    command: &str,
+    keys: &BTreeMap<String, String>,
+) -> Result<String, GclipError> {
+    expand(command, keys, &mut Vec::new())
+}
+
+/// 展開中のキーの並び `chain` をたどりながら置き換える。
+fn expand(
+    command: &str,
+    keys: &BTreeMap<String, String>,
+    chain: &mut Vec<String>,
+) -> Result<String, GclipError> {
+    let mut expanded = String::with_capacity(command.len());
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut previous: Option<char> = None;
+    let mut skip_until = 0;
+
+    for (offset, ch) in command.char_indices() {
+        if offset < skip_until {
+            continue;
+        }
+        if ch == '@' && quote.is_none() && !escaped && previous.is_none_or(is_word_break) {
+            let rest = &command[offset + 1..];
+            let name_len = rest.len() - rest.trim_start_matches(is_key_char).len();
+            let name = &rest[..name_len];
+            let ends_word = rest[name_len..].chars().next().is_none_or(is_word_break);
+            if ends_word && let Some(target) = keys.get(name) {
+                if chain.iter().any(|item| item == name) {
+                    return Err(cycle_error(chain, name));
+                }
+                chain.push(name.to_string());
+                expanded.push_str(&expand(target, keys, chain)?);
+                chain.pop();
+                skip_until = offset + 1 + name_len;
+                previous = name.chars().last();
+                continue;
+            }
+        }
+
+        expanded.push(ch);
+        previous = Some(ch);
+        if escaped {
+            escaped = false;
+        } else if ch == '\\' && quote != Some('\'') {
+            escaped = true;
+        } else if quote == Some(ch) {
+            quote = None;
+        } else if quote.is_none() && matches!(ch, '\'' | '"') {
+            quote = Some(ch);
+        }
+    }
+    Ok(expanded)
+}
+
+/// 短縮キーに使える文字かを返す。
+fn is_key_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.')
+}
+
+/// 参照の前後に置ける区切り（空白やシェルの演算子）かを返す。
+fn is_word_break(ch: char) -> bool {
+    ch.is_whitespace() || matches!(ch, ';' | '&' | '|' | '(' | ')')
+}
+
+/// 循環した参照の経路を示すエラーを作る。
+fn cycle_error(chain: &[String], name: &str) -> GclipError {
+    let path: Vec<String> = chain
+        .iter()
+        .map(String::as_str)
+        .chain([name])
+        .map(|item| format!("@{item}"))
+        .collect();
+    GclipError::InvalidInput(format!("reference cycle: {}", path.join(" -> ")))
+}
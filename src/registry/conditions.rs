@@ -0,0 +1,248 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use super::storage::Storage;
+use super::{io, RegisteredCommands};
+use crate::error::GclipError;
+use crate::guard::glob_match;
+
+/// 登録済みコマンドを候補として有効にする条件。
+///
+/// 登録ファイルの `[when."コマンド"]` に保存し、指定したものをすべて満たすときに有効とする。
+///
+/// - `cwd`: カレントディレクトリ（またはその祖先）が一致するワイルドカード（単独の `~` と先頭の `~/` はホーム）
+/// - `file`: カレントディレクトリか祖先にあるファイル名（`Cargo.toml` など）
+/// - `env`: 空でない値が設定されている環境変数
+/// - `host`: ホスト名が一致するワイルドカード
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Condition {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+}
+
+/// 条件の判定に使う現在の環境。
+#[derive(Debug, Clone, Default)]
+pub struct WhenContext {
+    pub cwd: Option<PathBuf>,
+    pub home: Option<PathBuf>,
+    pub hostname: Option<String>,
+    /// 空でない値が設定されている環境変数の名前。
+    pub vars: HashSet<String>,
+}
+
+impl Condition {
+    /// 何も指定されていない場合に真を返す。
+    pub fn is_empty(&self) -> bool {
+        self.cwd.is_none() && self.file.is_none() && self.env.is_none() && self.host.is_none()
+    }
+
+    /// 指定されている項目で上書きする。
+    pub fn merge(&mut self, other: &Condition) {
+        let fields = [
+            (&mut self.cwd, &other.cwd),
+            (&mut self.file, &other.file),
+            (&mut self.env, &other.env),
+            (&mut self.host, &other.host),
+        ];
+        for (current, update) in fields {
+            if update.is_some() {
+                current.clone_from(update);
+            }
+        }
+    }
+
+    /// 現在の環境で条件を満たすかを返す。
+    pub fn holds(&self, context: &WhenContext) -> bool {
+        let cwd_matches = self.cwd.as_deref().is_none_or(|pattern| {
+            context.cwd.as_deref().is_some_and(|cwd| {
+                let expanded = expand_home(pattern, context.home.as_deref());
+                let dir = cwd.display().to_string();
+                glob_match(&expanded, &dir) || glob_match(&format!("{expanded}/*"), &dir)
+            })
+        });
+        let file_found = self.file.as_deref().is_none_or(|name| {
+            context
+                .cwd
+                .as_deref()
+                .is_some_and(|cwd| cwd.ancestors().any(|dir| dir.join(name).exists()))
+        });
+        let env_set = self
+            .env
+            .as_deref()
+            .is_none_or(|name| context.vars.contains(name));
+        let host_matches = self.host.as_deref().is_none_or(|pattern| {
+            context
+                .hostname
+                .as_deref()
+                .is_some_and(|hostname| glob_match(pattern, hostname))
+        });
+        cwd_matches && file_found && env_set && host_matches
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = [
+            ("cwd", &self.cwd),
+            ("file", &self.file),
+            ("env", &self.env),
+            ("host", &self.host),
+        ]
+        .into_iter()
+        .filter_map(|(kind, value)| value.as_ref().map(|text| format!("{kind}={text}")))
+        .collect();
+        f.write_str(&parts.join(", "))
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    /// `KIND=VALUE` の形式（`cwd=~/src/infra*`、`file=Cargo.toml` など）を解析する。
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let Some((kind, raw)) = spec.split_once('=') else {
+            return Err(format!("expected KIND=VALUE: {spec}"));
+        };
+        let value = raw.trim();
+        if value.is_empty() {
+            return Err(format!("empty value for {kind}"));
+        }
+        let mut condition = Self::default();
+        let slot = match kind.trim() {
+            "cwd" => &mut condition.cwd,
+            "file" => &mut condition.file,
+            "env" => &mut condition.env,
+            "host" => &mut condition.host,
+            other => {
+                return Err(format!(
+                    "unknown condition: {other} (cwd, file, env or host)"
+                ));
+            }
+        };
+        *slot = Some(value.to_string());
+        Ok(condition)
+    }
+}
+
+impl WhenContext {
+    /// 現在のプロセスの環境から作る。
+    pub fn current() -> Self {
+        Self {
+            cwd: env::current_dir().ok(),
+            home: env::var_os("HOME").map(PathBuf::from),
+            hostname: hostname(),
+            vars: env::vars_os()
+                .filter(|(_, value)| !value.is_empty())
+                .filter_map(|(name, _)| name.into_string().ok())
+                .collect(),
+        }
+    }
+}
+
+/// 複数のコマンドに条件を設定する。既存の条件には指定した項目だけを上書きする。
+///
+/// 未登録のコマンドはエラーにする。
+pub(super) fn set_conditions(
+    storage: &dyn Storage,
+    commands: &[String],
+    condition: &Condition,
+) -> Result<(), GclipError> {
+    let mut registered = io::load_registry(storage)?;
+    if let Some(missing) = commands
+        .iter()
+        .find(|command| !registered.commands.contains(command))
+    {
+        return Err(GclipError::InvalidInput(format!(
+            "command is not registered: {missing}"
+        )));
+    }
+
+    let mut changed = false;
+    for command in commands {
+        changed |= apply_condition(&mut registered, command, condition);
+    }
+    if changed {
+        io::write_registry(storage, &registered)?;
+    }
+    Ok(())
+}
+
+/// 条件を反映し、変更があったかを返す。
+pub(super) fn apply_condition(
+    registered: &mut RegisteredCommands,
+    command: &str,
+    condition: &Condition,
+) -> bool {
+    if condition.is_empty() {
+        return false;
+    }
+    let current = registered.when.entry(command.to_string()).or_default();
+    let before = current.clone();
+    current.merge(condition);
+    *current != before
+}
+
+/// 条件を満たさないコマンドを後ろに回す。
+///
+/// 条件を満たすもの同士、満たさないもの同士では元の順を保つ。
+pub(super) fn rank_by_conditions(
+    storage: &dyn Storage,
+    commands: Vec<String>,
+    context: &WhenContext,
+) -> Result<Vec<String>, GclipError> {
+    let registered = io::load_registry(storage)?;
+    Ok(partition_active(commands, &registered.when, context))
+}
+
+/// 条件を満たすコマンドと満たさないコマンドに分け、満たすものを先にして返す。
+pub(super) fn partition_active(
+    commands: Vec<String>,
+    conditions: &BTreeMap<String, Condition>,
+    context: &WhenContext,
+) -> Vec<String> {
+    let (mut active, inactive): (Vec<String>, Vec<String>) =
+        commands.into_iter().partition(|command| {
+            conditions
+                .get(command)
+                .is_none_or(|condition| condition.holds(context))
+        });
+    active.extend(inactive);
+    active
+}
+
+/// 単独の `~` と先頭の `~/` をホームディレクトリに置き換える。
+///
+/// `~bob/src` のような他のユーザーのホームは展開せず、そのまま比べる。
+pub(super) fn expand_home(pattern: &str, home: Option<&Path>) -> String {
+    let Some(dir) = home else {
+        return pattern.to_string();
+    };
+    if pattern == "~" {
+        return dir.display().to_string();
+    }
+    match pattern.strip_prefix("~/") {
+        Some(rest) => format!("{}/{rest}", dir.display()),
+        None => pattern.to_string(),
+    }
+}
+
+/// ホスト名を返す。取得できない場合は `None` を返す。
+fn hostname() -> Option<String> {
+    let mut buffer = [0u8; 256];
+    let result = unsafe { libc::gethostname(buffer.as_mut_ptr().cast(), buffer.len()) };
+    if result != 0 {
+        return None;
+    }
+    let len = buffer.iter().position(|byte| *byte == 0).unwrap_or(buffer.len());
+    Some(String::from_utf8_lossy(&buffer[..len]).into_owned()).filter(|name| !name.is_empty())
+}
@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::path::Path;
+
+use super::usage::UsageRecord;
+
+/// コマンドを使用した場所。
+///
+/// - `cwd`: カレントディレクトリ
+/// - `repo_root`: カレントディレクトリを含むgitリポジトリのルート（あれば）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UsageContext {
+    pub cwd: Option<String>,
+    pub repo_root: Option<String>,
+}
+
+impl UsageContext {
+    /// 現在のプロセスのカレントディレクトリから作る。
+    ///
+    /// 取得できない場合は場所なしとして扱う。
+    pub fn current() -> Self {
+        env::current_dir()
+            .map(|dir| Self::for_dir(&dir))
+            .unwrap_or_default()
+    }
+
+    /// 指定したディレクトリから作る。
+    ///
+    /// 祖先に `.git` を持つディレクトリがあれば、それをリポジトリのルートとする。
+    pub fn for_dir(dir: &Path) -> Self {
+        let repo_root = dir
+            .ancestors()
+            .find(|ancestor| ancestor.join(".git").exists())
+            .map(|root| root.display().to_string());
+        Self {
+            cwd: Some(dir.display().to_string()),
+            repo_root,
+        }
+    }
+}
+
+/// 候補の並び順での優先度。小さいほど先に表示する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Affinity {
+    /// カレントディレクトリで使ったことがある。
+    SameDir,
+    /// 同じリポジトリ内で使ったことがある。
+    SameRepo,
+    /// 場所の記録がない、またはリポジトリ外でも使っている。
+    Neutral,
+    /// 別のリポジトリ内でしか使っていない。
+    OtherRepo,
+}
+
+/// 使用した場所に応じて候補を並べ替える。
+///
+/// カレントディレクトリ、同じリポジトリで使ったものを先に、
+/// 別のリポジトリでしか使っていないものを後ろに回す。
+/// 同じ優先度の中では、その場所での使用回数が多い順、次に元の順を保つ。
+pub(super) fn rank_by_context(
+    commands: Vec<String>,
+    usage: &BTreeMap<String, UsageRecord>,
+    context: &UsageContext,
+) -> Vec<String> {
+    let mut ranked: Vec<(Affinity, usize, String)> = commands
+        .into_iter()
+        .map(|command| {
+            let (affinity, uses) = usage
+                .get(&command)
+                .map(|record| affinity(record, context))
+                .unwrap_or((Affinity::Neutral, 0));
+            (affinity, uses, command)
+        })
+        .collect();
+    ranked.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| b.1.cmp(&a.1)));
+    ranked.into_iter().map(|(_, _, command)| command).collect()
+}
+
+/// 1コマンドの優先度と、その場所での使用回数を決める。
+fn affinity(record: &UsageRecord, context: &UsageContext) -> (Affinity, usize) {
+    let count_in = |places: &BTreeMap<String, usize>, place: &Option<String>| {
+        place
+            .as_ref()
+            .and_then(|key| places.get(key))
+            .copied()
+            .unwrap_or_default()
+    };
+
+    let in_dir = count_in(&record.dirs, &context.cwd);
+    if in_dir > 0 {
+        return (Affinity::SameDir, in_dir);
+    }
+    let in_repo = count_in(&record.repos, &context.repo_root);
+    if in_repo > 0 {
+        return (Affinity::SameRepo, in_repo);
+    }
+    // 場所を記録した使用がすべてリポジトリ内なら、そのリポジトリ専用とみなす。
+    let located: usize = record.dirs.values().sum();
+    let in_repos: usize = record.repos.values().sum();
+    if located > 0 && in_repos >= located {
+        (Affinity::OtherRepo, 0)
+    } else {
+        (Affinity::Neutral, 0)
+    }
+}
@@ -0,0 +1,127 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use super::conditions::Condition;
+use super::storage::Storage;
+use super::{io, RegisteredCommands};
+use crate::error::GclipError;
+
+/// 登録済みコマンドに付けた説明、タグ、有効にする条件、作業ディレクトリ、ワークフローの手順。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CommandDetails {
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub when: Option<Condition>,
+    pub cwd: Option<String>,
+    pub steps: Vec<String>,
+}
+
+impl CommandDetails {
+    /// 説明もタグも条件も作業ディレクトリも手順もない場合に真を返す。
+    pub fn is_empty(&self) -> bool {
+        self.description.is_none()
+            && self.tags.is_empty()
+            && self.when.is_none()
+            && self.cwd.is_none()
+            && self.steps.is_empty()
+    }
+}
+
+/// 登録済みコマンドに説明とタグを付ける。
+///
+/// - 説明は前後の空白を除去し、空なら変更しない
+/// - タグは既存のものに追加する（重複は除く）
+///
+/// 未登録のコマンドはエラーにする。
+pub(super) fn set_details(
+    storage: &dyn Storage,
+    command: &str,
+    description: Option<&str>,
+    tags: &[String],
+) -> Result<(), GclipError> {
+    let mut registered = io::load_registry(storage)?;
+    if !registered.commands.iter().any(|item| item == command) {
+        return Err(GclipError::InvalidInput(format!(
+            "command is not registered: {command}"
+        )));
+    }
+
+    if apply_details(&mut registered, command, description, tags) {
+        io::write_registry(storage, &registered)?;
+    }
+    Ok(())
+}
+
+/// 説明とタグを反映し、変更があったかを返す。
+pub(super) fn apply_details(
+    registered: &mut RegisteredCommands,
+    command: &str,
+    description: Option<&str>,
+    tags: &[String],
+) -> bool {
+    let mut changed = false;
+    if let Some(text) = description.map(str::trim).filter(|text| !text.is_empty())
+        && registered.descriptions.get(command).map(String::as_str) != Some(text)
+    {
+        registered
+            .descriptions
+            .insert(command.to_string(), text.to_string());
+        changed = true;
+    }
+
+    let current = registered.tags.entry(command.to_string()).or_default();
+    for tag in normalize_tags(tags) {
+        if !current.contains(&tag) {
+            current.push(tag);
+            changed = true;
+        }
+    }
+    if current.is_empty() {
+        registered.tags.remove(command);
+    }
+    changed
+}
+
+/// コマンドごとの説明、タグ、条件、作業ディレクトリ、手順を返す。
+///
+/// いずれも持たないコマンドは含めない。
+pub(super) fn command_details(
+    storage: &dyn Storage,
+) -> Result<BTreeMap<String, CommandDetails>, GclipError> {
+    let registered = io::load_registry(storage)?;
+    let mut details: BTreeMap<String, CommandDetails> = BTreeMap::new();
+    for (command, description) in registered.descriptions {
+        details.entry(command).or_default().description = Some(description);
+    }
+    for (command, tags) in registered.tags {
+        details.entry(command).or_default().tags = tags;
+    }
+    for (command, condition) in registered.when {
+        details.entry(command).or_default().when = Some(condition);
+    }
+    for (command, dir) in registered.cwd {
+        details.entry(command).or_default().cwd = Some(dir);
+    }
+    for (command, steps) in registered.steps {
+        details.entry(command).or_default().steps = steps;
+    }
+    Ok(details)
+}
+
+/// タグの入力を正規化する。
+///
+/// カンマと空白で区切り、空のものと重複を除く。先頭の `#` は取り除く。
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags
+        .iter()
+        .flat_map(|item| item.split(|ch: char| ch == ',' || ch.is_whitespace()))
+        .map(|tag| tag.trim_start_matches('#'))
+        .filter(|tag| !tag.is_empty())
+    {
+        if !normalized.iter().any(|item| item == tag) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
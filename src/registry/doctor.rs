@@ -1,44 +1,60 @@
 use std::collections::HashSet;
 
 use crate::doctor::DoctorReport;
+use crate::error::GclipError;
+use crate::secret;
 
-use super::{path, RegisteredCommands};
+use super::storage::{Storage, StoreFile};
+use super::{recent, RegisteredCommands};
 
 /// 設定/保存場所の確認を行う。
 ///
 /// 現在のパスと整合性の診断結果を返す。
-pub(super) fn doctor_report() -> Result<DoctorReport, String> {
-    let data_dir = path::data_dir().ok_or("HOME not set")?;
-    let registry_path = path::registry_path(&data_dir);
+pub(super) fn doctor_report(storage: &dyn Storage) -> Result<DoctorReport, GclipError> {
+    let registry_path = storage.location(StoreFile::Registry);
+    let recent_path = storage.location(StoreFile::Recent);
+    let data_dir = registry_path
+        .parent()
+        .map(|dir| dir.to_path_buf())
+        .unwrap_or_default();
 
     let mut report = DoctorReport {
         data_dir: data_dir.clone(),
         registry_path: registry_path.clone(),
-        data_dir_exists: data_dir.exists(),
-        registry_exists: registry_path.exists(),
+        data_dir_exists: data_dir.is_dir(),
+        registry_exists: false,
         registry_readable: false,
         registry_valid: false,
         command_count: 0,
         empty_commands: 0,
         duplicate_commands: 0,
+        secret_commands: Vec::new(),
+        recent_path: recent_path.clone(),
+        recent_valid: true,
+        checks: Vec::new(),
         errors: Vec::new(),
+        actions: Vec::new(),
     };
 
-    if !report.registry_exists {
-        report.registry_readable = true;
-        report.registry_valid = true;
-        return Ok(report);
+    if let Err(err) = recent::load_recent(storage) {
+        report.recent_valid = false;
+        report.errors.push(err.to_string());
     }
 
-    let contents = match std::fs::read_to_string(&registry_path) {
-        Ok(contents) => {
+    let contents = match storage.read(StoreFile::Registry) {
+        Ok(Some(contents)) => {
+            report.registry_exists = true;
             report.registry_readable = true;
             contents
         }
+        Ok(None) => {
+            report.registry_readable = true;
+            report.registry_valid = true;
+            return Ok(report);
+        }
         Err(err) => {
-            report
-                .errors
-                .push(format!("failed to read registry file: {err}"));
+            report.registry_exists = true;
+            report.errors.push(err.to_string());
             return Ok(report);
         }
     };
@@ -74,6 +90,11 @@ fn apply_command_stats(report: &mut DoctorReport, registered: &RegisteredCommand
     report.command_count = registered.commands.len();
     report.empty_commands = count_empty_commands(&registered.commands);
     report.duplicate_commands = count_duplicate_commands(&registered.commands);
+    report.secret_commands = registered
+        .commands
+        .iter()
+        .filter_map(|command| secret::redact(command))
+        .collect();
 }
 
 /// 空コマンドの数を数える。
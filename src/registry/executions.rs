@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+use super::storage::{Storage, StoreFile};
+use crate::error::GclipError;
+
+/// 実行履歴がこの大きさ（バイト）を超えたら古い記録を捨てる。
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// 古い記録を捨てるときに残す大きさ（バイト）の目安。
+const KEEP_LOG_BYTES: u64 = MAX_LOG_BYTES / 2;
+
+/// シェルのフックが記録した、実行されたコマンド1件分。
+///
+/// 実行履歴は1行1件のJSONとして追記する。
+/// 日時はUNIX秒、所要時間はミリ秒で保存する。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Execution {
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    pub exit: i32,
+    #[serde(default)]
+    pub duration_ms: u64,
+    pub at: u64,
+}
+
+impl Execution {
+    /// 終了コードが0だったかを返す。
+    pub fn succeeded(&self) -> bool {
+        self.exit == 0
+    }
+}
+
+/// 実行履歴に1件追記する。
+///
+/// 空のコマンドは記録しない。
+/// 追記後に上限を超えていれば、新しい記録だけを残して古いものを捨てる。
+pub(super) fn record_execution(storage: &dyn Storage, execution: &Execution) -> Result<(), GclipError> {
+    if execution.command.trim().is_empty() {
+        return Ok(());
+    }
+    let mut line = serde_json::to_string(execution)
+        .map_err(|err| GclipError::Other(format!("failed to serialize execution: {err}")))?;
+    line.push('\n');
+    storage.append(StoreFile::Executions, &line)?;
+    rotate(storage, MAX_LOG_BYTES, KEEP_LOG_BYTES)
+}
+
+/// 実行履歴が `max` バイトを超えていれば、末尾の約 `keep` バイト分の行だけを残す。
+///
+/// 行の途中から残さないよう、切り位置の後の最初の改行までは捨てる。
+/// 別のシェルが同時に追記した行を失わないよう、置き換えは `Storage::rewrite` で行う。
+pub(super) fn rotate(storage: &dyn Storage, max: u64, keep: u64) -> Result<(), GclipError> {
+    if storage.size(StoreFile::Executions)? <= max {
+        return Ok(());
+    }
+    storage.rewrite(StoreFile::Executions, &|contents| {
+        if contents.len() as u64 <= max {
+            return None;
+        }
+        let cut = contents.len().saturating_sub(usize::try_from(keep).unwrap_or(usize::MAX));
+        let kept = contents.as_bytes()[cut..]
+            .iter()
+            .position(|byte| *byte == b'\n')
+            .map_or("", |offset| &contents[cut + offset + 1..]);
+        Some(kept.to_string())
+    })
+}
+
+/// 実行履歴を記録順に返す。
+///
+/// 書き込み途中などで解析できない行は読み飛ばす。
+pub(super) fn executions(storage: &dyn Storage) -> Result<Vec<Execution>, GclipError> {
+    let Some(contents) = storage.read(StoreFile::Executions)? else {
+        return Ok(Vec::new());
+    };
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
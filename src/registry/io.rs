@@ -1,32 +1,64 @@
-use std::fs;
-use std::path::Path;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
+use super::storage::{Storage, StoreFile};
 use super::RegisteredCommands;
+use crate::error::GclipError;
 
 /// 既存の登録ファイルを読み込み、構造体へ変換する。
 ///
 /// 未作成の場合は空の状態を返す。
-pub(crate) fn load_registry(path: &Path) -> Result<RegisteredCommands, String> {
-    if !path.exists() {
-        return Ok(RegisteredCommands::default());
-    }
+pub(crate) fn load_registry(storage: &dyn Storage) -> Result<RegisteredCommands, GclipError> {
+    load_file(storage, StoreFile::Registry)
+}
+
+/// 構造体をTOMLに整形して保存する。
+///
+/// 末尾に改行を付与して、手動編集時の差分が分かりやすい形にする。
+pub(crate) fn write_registry(
+    storage: &dyn Storage,
+    registered: &RegisteredCommands,
+) -> Result<(), GclipError> {
+    save_file(storage, StoreFile::Registry, registered)
+}
 
-    let contents =
-        fs::read_to_string(path).map_err(|err| format!("failed to read registry file: {err}"))?;
+/// 保存ファイルを読み込み、構造体へ変換する。
+///
+/// 未作成または空の場合は既定値を返す。
+pub(crate) fn load_file<T: DeserializeOwned + Default>(
+    storage: &dyn Storage,
+    file: StoreFile,
+) -> Result<T, GclipError> {
+    let Some(contents) = storage.read(file)? else {
+        return Ok(T::default());
+    };
+    parse_file(storage, file, &contents)
+}
+
+/// 読み込んだ文字列を構造体へ変換する。
+///
+/// 空の場合は既定値を返し、解析できない場合は `GclipError::Corrupt` にする。
+pub(crate) fn parse_file<T: DeserializeOwned + Default>(
+    storage: &dyn Storage,
+    file: StoreFile,
+    contents: &str,
+) -> Result<T, GclipError> {
     if contents.trim().is_empty() {
-        return Ok(RegisteredCommands::default());
+        return Ok(T::default());
     }
-    toml::from_str::<RegisteredCommands>(&contents)
-        .map_err(|err| format!("failed to parse registry file: {err}"))
+    toml::from_str::<T>(contents)
+        .map_err(|err| GclipError::corrupt(storage.location(file), err.message()))
 }
 
 /// 構造体をTOMLに整形して保存する。
-///
-/// 末尾に改行を付与して、手動編集時の差分が分かりやすい形にする。
-pub(crate) fn write_registry(path: &Path, registered: &RegisteredCommands) -> Result<(), String> {
-    let mut serialized = toml::to_string_pretty(registered).map_err(|err| format!("{err}"))?;
+pub(crate) fn save_file<T: Serialize>(
+    storage: &dyn Storage,
+    file: StoreFile,
+    value: &T,
+) -> Result<(), GclipError> {
+    let mut serialized = toml::to_string_pretty(value).map_err(|err| err.to_string())?;
     if !serialized.ends_with('\n') {
         serialized.push('\n');
     }
-    fs::write(path, serialized).map_err(|err| format!("failed to write registry file: {err}"))
+    storage.write(file, &serialized)
 }
@@ -0,0 +1,75 @@
+use super::storage::Storage;
+use super::{io, RegisteredCommands};
+use crate::error::GclipError;
+
+/// 短縮キーが割り当てられたコマンドを一覧で返す。
+///
+/// `(キー, コマンド)` の組をキーの昇順で返す。
+pub(super) fn keyed_commands(storage: &dyn Storage) -> Result<Vec<(String, String)>, GclipError> {
+    let registered = io::load_registry(storage)?;
+    Ok(registered.keys.into_iter().collect())
+}
+
+/// 短縮キーとして使える文字列か検証する。
+///
+/// - 前後の空白を除去する
+/// - 英数字と `_` `-` `.` のみ許可する
+/// - 先頭の `-` はオプションと紛らわしいため拒否する
+pub(super) fn validate_key(key: &str) -> Result<String, GclipError> {
+    let trimmed = key.trim();
+    if trimmed.is_empty() {
+        return Err(GclipError::InvalidInput("key is empty".to_string()));
+    }
+    if trimmed.starts_with('-') {
+        return Err(GclipError::InvalidInput(format!(
+            "key must not start with '-': {trimmed}"
+        )));
+    }
+    let valid = trimmed
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'));
+    if !valid {
+        return Err(GclipError::InvalidInput(format!(
+            "key contains invalid characters: {trimmed}"
+        )));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// 登録済みコマンドに短縮キーを割り当てる。
+///
+/// - 未登録のコマンドにはキーを割り当てない。
+/// - 別のコマンドが使用中のキーはエラーにする。
+/// - 同じコマンドに別のキーがあれば置き換える。
+pub(super) fn assign_key(
+    registered: &mut RegisteredCommands,
+    command: &str,
+    key: &str,
+) -> Result<(), GclipError> {
+    if !registered.commands.iter().any(|item| item == command) {
+        return Err(GclipError::InvalidInput(format!(
+            "command is not registered: {command}"
+        )));
+    }
+    if let Some(existing) = registered.keys.get(key)
+        && existing != command
+    {
+        return Err(GclipError::InvalidInput(format!(
+            "key \"{key}\" is already used by \"{existing}\""
+        )));
+    }
+
+    registered.keys.retain(|_, item| item != command);
+    registered.keys.insert(key.to_string(), command.to_string());
+    Ok(())
+}
+
+/// 登録済みに存在しないコマンドを指すキーを除去する。
+///
+/// 削除処理の後に呼び出し、キー表が孤立しないようにする。
+pub(super) fn drop_orphan_keys(registered: &mut RegisteredCommands) {
+    let commands = &registered.commands;
+    registered
+        .keys
+        .retain(|_, command| commands.iter().any(|item| item == command));
+}
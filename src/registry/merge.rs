@@ -1,16 +1,20 @@
 use super::RegisteredCommands;
+use crate::clock;
 
 /// 登録済みリストに新規コマンドを追加する。
 ///
 /// 追加件数を返すことで、上位の処理が書き込み判断に利用できる。
+/// 新規に追加したコマンドには登録日時を記録する。
 pub(crate) fn merge_commands(
     registered: &mut RegisteredCommands,
     commands: &[String],
 ) -> usize {
+    let now = clock::now_secs();
     let mut added = 0;
     for command in commands {
         if !registered.commands.contains(command) {
             registered.commands.push(command.clone());
+            registered.added.insert(command.clone(), now);
             added += 1;
         }
     }
@@ -2,95 +2,379 @@ mod io;
 mod merge;
 mod path;
 mod search;
-mod add;
+pub(crate) mod add;
 mod list;
 mod remove;
 mod doctor;
 mod recent;
+mod key;
+mod compose;
+mod conditions;
+mod context;
+mod details;
+mod executions;
+mod repair;
+mod storage;
+mod trash;
+mod usage;
+mod workdir;
+mod workflow;
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+use crate::error::GclipError;
+
+pub use compose::expand_references;
+pub use conditions::{Condition, WhenContext};
+pub use context::UsageContext;
+pub use details::{normalize_tags, CommandDetails};
+pub use executions::Execution;
+pub use storage::{FsStorage, MemoryStorage, Storage, StoreFile};
+pub use trash::{Restored, TrashEntry};
+pub use usage::{UsageRecord, UsageSnapshot, UsageSource};
+pub use workdir::{display_dir, is_within, with_cd_prefix};
+pub use workflow::{join_steps, WorkflowProgress, WorkflowStep};
+
 /// 推薦されたコマンドを「登録済み」として保存するための処理群。
 ///
 /// ここでの登録とは、TOMLファイルの配列に追記することを指す。
-pub struct Registry;
+/// 保存先は `Storage` で差し替えられ、既定は `~/.gclip` 配下のファイルになる。
+///
+/// ```
+/// use gclip_cli::registry::Registry;
+///
+/// let registry = Registry::in_memory();
+/// registry.add_command("git status").unwrap();
+/// assert_eq!(registry.list_commands().unwrap(), vec!["git status".to_string()]);
+/// ```
+pub struct Registry {
+    storage: Box<dyn Storage>,
+}
 
 /// 登録ファイルのスキーマ。
 ///
 /// TOMLの配列として保存し、編集しやすい形にする。
+/// 短縮キーは `[keys]` テーブルに `キー = "コマンド"` の形で保存する。
+/// 登録日時は `[added]` テーブルに `"コマンド" = UNIX秒` の形で保存する。
+/// 説明とタグは `[descriptions]` / `[tags]` テーブルにコマンドをキーとして保存する。
+/// 候補として有効にする条件は `[when."コマンド"]` テーブルに保存する。
+/// 作業ディレクトリは `[cwd]` テーブルに `"コマンド" = "絶対パス"` の形で保存する。
+/// ワークフローの手順は `[steps]` テーブルに `"手順1 && 手順2" = ["手順1", "手順2"]` の形で保存する。
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub(super) struct RegisteredCommands {
     pub(super) commands: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(super) keys: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(super) added: BTreeMap<String, u64>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(super) descriptions: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(super) tags: BTreeMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(super) when: BTreeMap<String, Condition>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(super) cwd: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(super) steps: BTreeMap<String, Vec<String>>,
 }
 
 impl Registry {
+    /// 任意の保存先を使う。
+    pub fn new(storage: impl Storage + 'static) -> Self {
+        Self {
+            storage: Box::new(storage),
+        }
+    }
+
+    /// 既定の保存先（`~/.gclip`）を使う。
+    ///
+    /// `HOME` が未設定の場合はエラーにする。
+    pub fn open() -> Result<Self, GclipError> {
+        Ok(Self::new(FsStorage::from_home()?))
+    }
+
+    /// 指定したディレクトリ配下のファイルを使う。
+    pub fn at(data_dir: impl Into<PathBuf>) -> Self {
+        Self::new(FsStorage::new(data_dir))
+    }
+
+    /// メモリ上にだけ保存する。
+    pub fn in_memory() -> Self {
+        Self::new(MemoryStorage::new())
+    }
+
+    /// 使用中の保存先を返す。
+    pub fn storage(&self) -> &dyn Storage {
+        self.storage.as_ref()
+    }
+
     /// 推薦されたコマンドを登録ファイルへ追記する。
     ///
     /// - すでに登録済みのコマンドは重複登録しない。
     /// - 追加件数と登録ファイルのパスを返す。
-    pub fn register_commands(commands: &[String]) -> Result<(PathBuf, usize), String> {
-        let data_dir = path::data_dir().ok_or("HOME not set")?;
-        path::ensure_dir(&data_dir)?;
-        let registry_path = path::registry_path(&data_dir);
-
-        let mut registered = io::load_registry(&registry_path)?;
-        let added = merge::merge_commands(&mut registered, commands);
-
-        if added > 0 {
-            io::write_registry(&registry_path, &registered)?;
-        }
+    pub fn register_commands(&self, commands: &[String]) -> Result<(PathBuf, usize), GclipError> {
+        add::register_commands(self.storage(), commands)
+    }
 
-        Ok((registry_path, added))
+    /// 複数のコマンドを登録し、新たに登録したものに説明とタグ、条件、作業ディレクトリを付ける。
+    ///
+    /// 登録と付加情報の設定は1回の書き込みで行う。`details` の手順は使わない。
+    /// 追加件数と登録ファイルのパスを返す。
+    pub fn register_commands_with_details(
+        &self,
+        commands: &[String],
+        details: &CommandDetails,
+    ) -> Result<(PathBuf, usize), GclipError> {
+        add::register_with_details(self.storage(), commands, details)
     }
 
     /// 1件のコマンド文字列を登録する。
     ///
     /// 空文字列は拒否し、余分な空白は除去する。
-    pub fn add_command(command: &str) -> Result<(PathBuf, usize), String> {
-        add::add_command(command)
+    pub fn add_command(&self, command: &str) -> Result<(PathBuf, usize), GclipError> {
+        add::add_command(self.storage(), command)
+    }
+
+    /// 1件のコマンド文字列を短縮キー付きで登録する。
+    ///
+    /// 登録済みのコマンドであればキーの割り当てだけを行う。
+    pub fn add_command_with_key(
+        &self,
+        command: &str,
+        key: &str,
+    ) -> Result<(PathBuf, usize), GclipError> {
+        add::add_command_with_key(self.storage(), command, key)
+    }
+
+    /// 1件のコマンド文字列を登録し、短縮キーと説明・タグ・条件・作業ディレクトリを付ける。
+    ///
+    /// 登録済みのコマンドでも付加情報は反映し、すべて1回の書き込みで行う。
+    /// `replace_when` が真なら既存の条件を消してから設定する。`details` の手順は使わない。
+    pub fn add_command_with_details(
+        &self,
+        command: &str,
+        key: Option<&str>,
+        details: &CommandDetails,
+        replace_when: bool,
+    ) -> Result<(PathBuf, usize), GclipError> {
+        add::add_with_details(self.storage(), command, key, details, replace_when)
+    }
+
+    /// 短縮キーが割り当てられたコマンドを一覧で返す。
+    ///
+    /// `(キー, コマンド)` の組をキーの昇順で返す。
+    pub fn keyed_commands(&self) -> Result<Vec<(String, String)>, GclipError> {
+        key::keyed_commands(self.storage())
+    }
+
+    /// 短縮キーからコマンドへの対応を返す。
+    ///
+    /// `@キー` の参照を展開する際に使う。
+    pub fn reference_table(&self) -> Result<BTreeMap<String, String>, GclipError> {
+        Ok(key::keyed_commands(self.storage())?.into_iter().collect())
+    }
+
+    /// 登録済みコマンドに説明とタグを付ける。
+    ///
+    /// 説明が空なら変更せず、タグは既存のものに追加する。
+    pub fn set_details(
+        &self,
+        command: &str,
+        description: Option<&str>,
+        tags: &[String],
+    ) -> Result<(), GclipError> {
+        details::set_details(self.storage(), command, description, tags)
+    }
+
+    /// 説明、タグ、条件のいずれかを持つコマンドの一覧を返す。
+    pub fn command_details(&self) -> Result<BTreeMap<String, CommandDetails>, GclipError> {
+        details::command_details(self.storage())
+    }
+
+    /// 登録済みコマンドに候補として有効にする条件を設定する。
+    ///
+    /// 既存の条件には指定した項目だけを上書きする。
+    pub fn set_conditions(&self, commands: &[String], condition: &Condition) -> Result<(), GclipError> {
+        conditions::set_conditions(self.storage(), commands, condition)
+    }
+
+    /// 登録済みコマンドに作業ディレクトリを設定する。
+    ///
+    /// `dir` は絶対パスで渡す。既存の設定は上書きする。
+    pub fn set_workdir(&self, commands: &[String], dir: &str) -> Result<(), GclipError> {
+        workdir::set_workdir(self.storage(), commands, dir)
+    }
+
+    /// 手順を `&&` でつないだコマンドとしてワークフローを登録する。
+    ///
+    /// 手順は2つ以上必要。`key` と `details` があれば同じ書き込みで短縮キーと付加情報も設定する。
+    /// `replace_when` が真なら既存の条件を消してから設定する。`details` の手順は使わない。
+    /// 追加件数と登録ファイルのパス、登録したコマンドを返す。
+    pub fn add_workflow(
+        &self,
+        steps: &[String],
+        key: Option<&str>,
+        details: &CommandDetails,
+        replace_when: bool,
+    ) -> Result<(PathBuf, usize, String), GclipError> {
+        workflow::add_workflow(self.storage(), steps, key, details, replace_when)
+    }
+
+    /// 登録済みコマンドに手順を設定し、ワークフローにする。
+    pub fn set_steps(&self, command: &str, steps: &[String]) -> Result<(), GclipError> {
+        workflow::set_steps(self.storage(), command, steps)
+    }
+
+    /// 1段階ずつ挿入している途中のワークフローの、次の手順を返す。
+    ///
+    /// 最後の挿入から1時間以上経ったものは途中とみなさない。
+    pub fn next_workflow_step(&self) -> Result<Option<WorkflowStep>, GclipError> {
+        workflow::next_step(self.storage(), crate::clock::now_secs())
+    }
+
+    /// ワークフローの1段階ずつの挿入を始める（最初の手順は挿入済みとする）。
+    pub fn start_workflow(&self, command: &str) -> Result<(), GclipError> {
+        workflow::start(self.storage(), command, crate::clock::now_secs())
+    }
+
+    /// 挿入した手順の次へ進める。最後の手順なら終える。
+    pub fn advance_workflow(&self, step: &WorkflowStep) -> Result<(), GclipError> {
+        workflow::advance(self.storage(), step, crate::clock::now_secs())
+    }
+
+    /// 途中のワークフローを破棄する。
+    pub fn clear_workflow(&self) -> Result<(), GclipError> {
+        workflow::clear(self.storage())
+    }
+
+    /// 条件を満たさないコマンドを後ろに回す。
+    pub fn rank_by_conditions(
+        &self,
+        commands: Vec<String>,
+        context: &WhenContext,
+    ) -> Result<Vec<String>, GclipError> {
+        conditions::rank_by_conditions(self.storage(), commands, context)
     }
 
     /// 登録済みコマンドから検索する。
     ///
     /// 部分一致で検索し、入力順を保ったまま結果を返す。
-    pub fn search_commands(query: &str) -> Result<Vec<String>, String> {
-        search::search_commands(query)
+    pub fn search_commands(&self, query: &str) -> Result<Vec<String>, GclipError> {
+        search::search_commands(self.storage(), query)
     }
 
     /// 登録済みコマンドを一覧で返す。
     ///
     /// 登録ファイルがない場合は空配列を返す。
-    pub fn list_commands() -> Result<Vec<String>, String> {
-        list::list_commands()
+    pub fn list_commands(&self) -> Result<Vec<String>, GclipError> {
+        list::list_commands(self.storage())
     }
 
     /// 登録済みコマンドを削除する。
     ///
     /// 前後の空白を除去した上で、完全一致で削除する。
-    pub fn remove_command(command: &str) -> Result<(PathBuf, usize), String> {
-        remove::remove_command(command)
+    pub fn remove_command(&self, command: &str) -> Result<(PathBuf, usize), GclipError> {
+        remove::remove_command(self.storage(), command)
     }
 
     /// 直近使用コマンドを記録する。
     ///
     /// 先頭に追加し、重複は除去する。
-    pub fn record_recent(command: &str) -> Result<(), String> {
-        recent::record_recent(command)
+    /// あわせて使用回数と日時を挿入の経路ごとに記録する。
+    pub fn record_recent(&self, command: &str, source: UsageSource) -> Result<(), GclipError> {
+        self.record_recent_in(command, source, &UsageContext::default())
+    }
+
+    /// 使用した場所（ディレクトリとgitリポジトリ）とともに直近使用コマンドを記録する。
+    pub fn record_recent_in(
+        &self,
+        command: &str,
+        source: UsageSource,
+        context: &UsageContext,
+    ) -> Result<(), GclipError> {
+        recent::record_recent(self.storage(), command, source, context, crate::clock::now_secs())
+    }
+
+    /// 候補を使用した場所に応じて並べ替える。
+    ///
+    /// 同じディレクトリ、同じリポジトリで使ったものを先にし、
+    /// 別のリポジトリでしか使っていないものは後ろに回す。
+    pub fn rank_by_context(
+        &self,
+        commands: Vec<String>,
+        context: &UsageContext,
+    ) -> Result<Vec<String>, GclipError> {
+        recent::rank_by_context(self.storage(), commands, context)
     }
 
     /// 直近使用コマンドの一覧を返す。
     ///
     /// 保存ファイルがない場合は空配列を返す。
-    pub fn recent_commands(limit: usize) -> Result<Vec<String>, String> {
-        recent::recent_commands(limit)
+    pub fn recent_commands(&self, limit: usize) -> Result<Vec<String>, GclipError> {
+        recent::recent_commands(self.storage(), limit)
+    }
+
+    /// シェルのフックから受け取った実行結果を実行履歴に追記する。
+    pub fn record_execution(&self, execution: &Execution) -> Result<(), GclipError> {
+        executions::record_execution(self.storage(), execution)
+    }
+
+    /// 実行履歴を記録順に返す。
+    ///
+    /// 記録がない場合は空配列を返す。
+    pub fn executions(&self) -> Result<Vec<Execution>, GclipError> {
+        executions::executions(self.storage())
+    }
+
+    /// 統計表示のために登録済みコマンドと使用状況を読み出す。
+    pub fn usage_snapshot(&self) -> Result<UsageSnapshot, GclipError> {
+        recent::usage_snapshot(self.storage())
+    }
+
+    /// 登録日時の記録がないコマンドに現在時刻を記録する。
+    ///
+    /// 記録した件数を返す。
+    pub fn backfill_added_dates(&self) -> Result<usize, GclipError> {
+        trash::backfill_added(self.storage(), crate::clock::now_secs())
+    }
+
+    /// 登録済みコマンドを `(コマンド, 理由)` の組でゴミ箱へ移す。
+    ///
+    /// ゴミ箱ファイルのパスと移した件数を返す。
+    pub fn move_to_trash(
+        &self,
+        commands: &[(String, String)],
+    ) -> Result<(PathBuf, usize), GclipError> {
+        trash::move_to_trash(self.storage(), commands, crate::clock::now_secs())
+    }
+
+    /// ゴミ箱のエントリを古い順に返す。
+    pub fn trash_entries(&self) -> Result<Vec<TrashEntry>, GclipError> {
+        Ok(trash::load_trash(self.storage())?.entries)
+    }
+
+    /// ゴミ箱の指定した位置（0始まり、`trash_entries` の順）のエントリを登録ファイルへ戻す。
+    ///
+    /// 説明やタグ、条件なども元に戻す。登録ファイルのパスと結果を返す。
+    pub fn restore_from_trash(&self, positions: &[usize]) -> Result<(PathBuf, Restored), GclipError> {
+        trash::restore_from_trash(self.storage(), positions)
+    }
+
+    /// 登録ファイルと直近使用コマンドのファイルを修復する。
+    ///
+    /// 行った処理を表示用の文字列で返す。何もしなければ空配列になる。
+    pub fn repair(&self) -> Result<Vec<String>, GclipError> {
+        repair::repair(self.storage())
     }
 
     /// 設定/保存場所の確認を行う。
     ///
     /// 現在のパスと整合性の診断結果を返す。
-    pub fn doctor_report() -> Result<crate::doctor::DoctorReport, String> {
-        doctor::doctor_report()
+    pub fn doctor_report(&self) -> Result<crate::doctor::DoctorReport, GclipError> {
+        doctor::doctor_report(self.storage())
     }
 }
 
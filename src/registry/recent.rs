@@ -1,15 +1,36 @@
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-use super::{path, RegisteredCommands};
+use super::context::{self, UsageContext};
+use super::io;
+use super::storage::{Storage, StoreFile};
+use super::usage::{self, UsageRecord, UsageSnapshot, UsageSource};
+use super::workflow::WorkflowProgress;
+use crate::error::GclipError;
 
 const RECENT_LIMIT: usize = 50;
 
+/// 直近使用コマンドのファイルのスキーマ。
+///
+/// 使用回数は `[usage."コマンド"]`、日別の回数は `[daily]` テーブルに保存する。
+/// 1段階ずつ挿入している途中のワークフローは `[workflow]` テーブルに保存する。
+/// いずれも記録前のファイルにはないため、省略時は空として扱う。
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(super) struct RecentCommands {
+    pub(super) commands: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(super) usage: BTreeMap<String, UsageRecord>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(super) daily: BTreeMap<String, usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) workflow: Option<WorkflowProgress>,
+}
+
 /// 直近使用コマンドの一覧を返す。
 ///
 /// 保存ファイルがない場合は空配列を返す。
-pub(super) fn recent_commands(limit: usize) -> Result<Vec<String>, String> {
-    let recent_path = resolve_recent_path()?;
-    let recent = load_recent(&recent_path)?;
+pub(super) fn recent_commands(storage: &dyn Storage, limit: usize) -> Result<Vec<String>, GclipError> {
+    let recent = load_recent(storage)?;
     Ok(recent.commands.into_iter().take(limit).collect())
 }
 
@@ -18,60 +39,115 @@ pub(super) fn recent_commands(limit: usize) -> Result<Vec<String>, String> {
 /// - 先頭に追加する
 /// - 既存の同一コマンドは除去する
 /// - 上限を超えた分は切り捨てる
-pub(super) fn record_recent(command: &str) -> Result<(), String> {
+/// - 使用回数と日時を経路ごとに加算する
+/// - 使用した場所が分かれば、場所ごとの回数も加算する
+pub(super) fn record_recent(
+    storage: &dyn Storage,
+    command: &str,
+    source: UsageSource,
+    context: &UsageContext,
+    now: u64,
+) -> Result<(), GclipError> {
     let normalized = normalize_command(command)?;
-    let recent_path = resolve_recent_path()?;
-    let mut recent = load_recent(&recent_path)?;
+    let mut recent = load_recent(storage)?;
 
     recent.commands.retain(|item| item != &normalized);
-    recent.commands.insert(0, normalized);
+    recent.commands.insert(0, normalized.clone());
     if recent.commands.len() > RECENT_LIMIT {
         recent.commands.truncate(RECENT_LIMIT);
     }
+    let record = recent.usage.entry(normalized).or_default();
+    record.record(source, now);
+    record.record_context(context);
+    usage::record_daily(&mut recent.daily, now);
 
-    save_recent(&recent_path, &recent)?;
+    save_recent(storage, &recent)?;
     Ok(())
 }
 
+/// 登録から外したコマンドの直近使用と使用回数の記録を取り除く。
+///
+/// 取り除いた使用回数の記録をコマンドごとに返す。変更がなければ書き込まない。
+pub(super) fn forget_usage(
+    storage: &dyn Storage,
+    commands: &[&str],
+) -> Result<BTreeMap<String, UsageRecord>, GclipError> {
+    let mut recent = load_recent(storage)?;
+    let before = recent.commands.len();
+    recent.commands.retain(|item| !commands.contains(&item.as_str()));
+    let forgotten: BTreeMap<String, UsageRecord> = commands
+        .iter()
+        .filter_map(|command| {
+            recent
+                .usage
+                .remove(*command)
+                .map(|record| (command.to_string(), record))
+        })
+        .collect();
+    if !forgotten.is_empty() || recent.commands.len() != before {
+        save_recent(storage, &recent)?;
+    }
+    Ok(forgotten)
+}
+
+/// ゴミ箱から戻したコマンドの使用回数の記録を戻す。
+///
+/// 戻すまでの間に記録があれば合算する。
+pub(super) fn restore_usage(
+    storage: &dyn Storage,
+    records: Vec<(String, UsageRecord)>,
+) -> Result<(), GclipError> {
+    if records.is_empty() {
+        return Ok(());
+    }
+    let mut recent = load_recent(storage)?;
+    for (command, record) in records {
+        recent.usage.entry(command).or_default().merge(record);
+    }
+    save_recent(storage, &recent)
+}
+
+/// 使用した場所に応じて候補を並べ替える。
+pub(super) fn rank_by_context(
+    storage: &dyn Storage,
+    commands: Vec<String>,
+    context: &UsageContext,
+) -> Result<Vec<String>, GclipError> {
+    let recent = load_recent(storage)?;
+    Ok(context::rank_by_context(commands, &recent.usage, context))
+}
+
+/// 登録済みコマンドと使用状況をまとめて読み出す。
+pub(super) fn usage_snapshot(storage: &dyn Storage) -> Result<UsageSnapshot, GclipError> {
+    let registered = io::load_registry(storage)?;
+    let recent = load_recent(storage)?;
+    Ok(UsageSnapshot {
+        commands: registered.commands,
+        added: registered.added,
+        usage: recent.usage,
+        daily: recent.daily,
+    })
+}
+
 /// コマンド文字列の正規化を行う。
 ///
 /// - 前後の空白を除去する
 /// - 空文字列はエラーにする
-fn normalize_command(command: &str) -> Result<String, String> {
+fn normalize_command(command: &str) -> Result<String, GclipError> {
     let trimmed = command.trim();
     if trimmed.is_empty() {
-        Err("command is empty".to_string())
+        Err(GclipError::InvalidInput("command is empty".to_string()))
     } else {
         Ok(trimmed.to_string())
     }
 }
 
 /// 直近使用コマンドのファイルを読み込む。
-fn load_recent(path: &PathBuf) -> Result<RegisteredCommands, String> {
-    if !path.exists() {
-        return Ok(RegisteredCommands::default());
-    }
-    let contents =
-        std::fs::read_to_string(path).map_err(|err| format!("failed to read recent file: {err}"))?;
-    if contents.trim().is_empty() {
-        return Ok(RegisteredCommands::default());
-    }
-    toml::from_str::<RegisteredCommands>(&contents)
-        .map_err(|err| format!("failed to parse recent file: {err}"))
+pub(super) fn load_recent(storage: &dyn Storage) -> Result<RecentCommands, GclipError> {
+    io::load_file(storage, StoreFile::Recent)
 }
 
 /// 直近使用コマンドのファイルを書き込む。
-fn save_recent(path: &PathBuf, recent: &RegisteredCommands) -> Result<(), String> {
-    let mut serialized = toml::to_string_pretty(recent).map_err(|err| format!("{err}"))?;
-    if !serialized.ends_with('\n') {
-        serialized.push('\n');
-    }
-    std::fs::write(path, serialized).map_err(|err| format!("failed to write recent file: {err}"))
-}
-
-/// 直近使用コマンドの保存先パスを解決する。
-fn resolve_recent_path() -> Result<PathBuf, String> {
-    let data_dir = path::data_dir().ok_or("HOME not set")?;
-    path::ensure_dir(&data_dir)?;
-    Ok(path::recent_path(&data_dir))
+pub(super) fn save_recent(storage: &dyn Storage, recent: &RecentCommands) -> Result<(), GclipError> {
+    io::save_file(storage, StoreFile::Recent, recent)
 }
@@ -1,31 +1,37 @@
 use std::path::PathBuf;
 
-use super::{io, path, RegisteredCommands};
+use super::storage::{Storage, StoreFile};
+use super::{io, key, recent, RegisteredCommands};
+use crate::error::GclipError;
 
 /// 登録済みコマンドを削除する。
 ///
 /// 前後の空白を除去した上で、完全一致で削除する。
-pub(super) fn remove_command(command: &str) -> Result<(PathBuf, usize), String> {
+/// 削除したコマンドの使用回数の記録もあわせて取り除く。
+pub(super) fn remove_command(
+    storage: &dyn Storage,
+    command: &str,
+) -> Result<(PathBuf, usize), GclipError> {
     let normalized = normalize_command(command)?;
-    let registry_path = resolve_registry_path()?;
-    let mut registered = io::load_registry(&registry_path)?;
+    let mut registered = io::load_registry(storage)?;
     let removed = remove_matching(&mut registered, &normalized);
 
     if removed > 0 {
-        io::write_registry(&registry_path, &registered)?;
+        io::write_registry(storage, &registered)?;
+        recent::forget_usage(storage, &[normalized.as_str()])?;
     }
 
-    Ok((registry_path, removed))
+    Ok((storage.location(StoreFile::Registry), removed))
 }
 
 /// コマンド文字列の正規化を行う。
 ///
 /// - 前後の空白を除去する
 /// - 空文字列はエラーにする
-pub(super) fn normalize_command(command: &str) -> Result<String, String> {
+pub(super) fn normalize_command(command: &str) -> Result<String, GclipError> {
     let trimmed = command.trim();
     if trimmed.is_empty() {
-        Err("command is empty".to_string())
+        Err(GclipError::InvalidInput("command is empty".to_string()))
     } else {
         Ok(trimmed.to_string())
     }
@@ -37,13 +43,12 @@ pub(super) fn normalize_command(command: &str) -> Result<String, String> {
 pub(super) fn remove_matching(registered: &mut RegisteredCommands, command: &str) -> usize {
     let before = registered.commands.len();
     registered.commands.retain(|item| item != command);
+    registered.added.remove(command);
+    registered.descriptions.remove(command);
+    registered.tags.remove(command);
+    registered.when.remove(command);
+    registered.cwd.remove(command);
+    registered.steps.remove(command);
+    key::drop_orphan_keys(registered);
     before - registered.commands.len()
 }
-
-/// 登録ファイルのパスを解決する。
-///
-/// `~/.gclip/registered.toml` を前提にする。
-fn resolve_registry_path() -> Result<PathBuf, String> {
-    let data_dir = path::data_dir().ok_or("HOME not set")?;
-    Ok(path::registry_path(&data_dir))
-}
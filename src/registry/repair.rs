@@ -0,0 +1,246 @@
+use std::collections::{BTreeMap, HashSet};
+
+use super::recent::{self, RecentCommands};
+use super::storage::{Storage, StoreFile};
+use super::{io, RegisteredCommands};
+use crate::error::GclipError;
+use crate::output::display_safe;
+
+/// 登録ファイルと直近使用コマンドのファイルを修復する。
+///
+/// - 登録ファイル: 空白の正規化、空コマンドの除去、重複の除去（先勝ち）
+/// - 解析できない登録ファイルは `.broken` に退避する
+/// - 解析できない直近使用コマンドのファイルは、読める部分を残して作り直す
+/// - 直近使用コマンドと使用回数も登録ファイルと同じく空白を正規化し、同じコマンドの記録は合算する
+///
+/// 行った処理を表示用の文字列で返す。
+pub(super) fn repair(storage: &dyn Storage) -> Result<Vec<String>, GclipError> {
+    let mut actions = repair_registry_file(storage)?;
+    actions.extend(repair_recent_file(storage)?);
+    Ok(actions)
+}
+
+/// 登録ファイルを修復する。
+fn repair_registry_file(storage: &dyn Storage) -> Result<Vec<String>, GclipError> {
+    let Some(contents) = storage.read(StoreFile::Registry)? else {
+        return Ok(Vec::new());
+    };
+    let registered = match io::parse_file::<RegisteredCommands>(storage, StoreFile::Registry, &contents) {
+        Ok(registered) => registered,
+        Err(GclipError::Corrupt { message, .. }) => {
+            let broken = storage.quarantine(StoreFile::Registry)?;
+            return Ok(vec![format!(
+                "Moved the unreadable registry to {} ({message}); gclip starts from an empty registry. \
+                 Fix the file by hand and move it back to {} to recover",
+                broken.display(),
+                storage.location(StoreFile::Registry).display()
+            )]);
+        }
+        Err(err) => return Err(err),
+    };
+
+    let mut repaired = registered;
+    let actions = repair_commands(&mut repaired);
+    if !actions.is_empty() {
+        io::write_registry(storage, &repaired)?;
+    }
+    Ok(actions)
+}
+
+/// 登録済みコマンドの内容を修復し、行った処理を返す。
+///
+/// 短縮キーと登録日時は修復後のコマンドに付け替え、
+/// 除去したコマンドを指すものは削除する。
+pub(super) fn repair_commands(registered: &mut RegisteredCommands) -> Vec<String> {
+    let mut actions = Vec::new();
+    let mut seen = HashSet::new();
+    let mut empty = 0;
+    let mut commands = Vec::new();
+
+    for original in std::mem::take(&mut registered.commands) {
+        let normalized = normalize_whitespace(&original);
+        if normalized.is_empty() {
+            empty += 1;
+            continue;
+        }
+        if normalized != original {
+            actions.push(format!(
+                "Normalized whitespace: \"{}\" -> \"{}\"",
+                display_safe(&original),
+                display_safe(&normalized)
+            ));
+            rename_metadata(registered, &original, &normalized, seen.contains(&normalized));
+        }
+        if !seen.insert(normalized.clone()) {
+            actions.push(format!("Removed duplicate: \"{}\"", display_safe(&normalized)));
+            continue;
+        }
+        commands.push(normalized);
+    }
+    if empty > 0 {
+        actions.push(format!("Removed {empty} empty command(s)"));
+    }
+
+    registered.commands = commands;
+    let kept: HashSet<&String> = registered.commands.iter().collect();
+    registered.keys.retain(|_, command| kept.contains(command));
+    registered.added.retain(|command, _| kept.contains(command));
+    registered.descriptions.retain(|command, _| kept.contains(command));
+    registered.tags.retain(|command, _| kept.contains(command));
+    registered.when.retain(|command, _| kept.contains(command));
+    registered.cwd.retain(|command, _| kept.contains(command));
+    registered.steps.retain(|command, _| kept.contains(command));
+    actions
+}
+
+/// 引用符の外にある空白の連続を1つにまとめ、前後の空白を除去する。
+///
+/// 引用符の中やエスケープされた空白、改行はそのまま残す。
+pub(super) fn normalize_whitespace(command: &str) -> String {
+    let mut normalized = String::with_capacity(command.len());
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut pending_space = false;
+
+    for ch in command.trim().chars() {
+        if quote.is_none() && !escaped && matches!(ch, ' ' | '\t') {
+            pending_space = true;
+            continue;
+        }
+        if pending_space {
+            normalized.push(' ');
+            pending_space = false;
+        }
+        normalized.push(ch);
+
+        if escaped {
+            escaped = false;
+        } else if ch == '\\' && quote != Some('\'') {
+            escaped = true;
+        } else if quote == Some(ch) {
+            quote = None;
+        } else if quote.is_none() && matches!(ch, '\'' | '"') {
+            quote = Some(ch);
+        }
+    }
+    normalized
+}
+
+/// 短縮キー・登録日時・説明・タグなどを新しいコマンド文字列へ付け替える。
+///
+/// `to` が先に出現済みならその情報を残し、まだなら（後ろに同じコマンドがあっても）
+/// `from` の情報で上書きする。どちらの場合も先に出現したものが優先される。
+fn rename_metadata(registered: &mut RegisteredCommands, from: &str, to: &str, to_seen: bool) {
+    for command in registered.keys.values_mut() {
+        if command == from {
+            *command = to.to_string();
+        }
+    }
+    move_entry(&mut registered.added, from, to, to_seen);
+    move_entry(&mut registered.descriptions, from, to, to_seen);
+    move_entry(&mut registered.tags, from, to, to_seen);
+    move_entry(&mut registered.when, from, to, to_seen);
+    move_entry(&mut registered.cwd, from, to, to_seen);
+    move_entry(&mut registered.steps, from, to, to_seen);
+}
+
+/// `from` の値を `to` へ移す。`keep_existing` なら `to` の既存の値を残す。
+fn move_entry<V>(map: &mut BTreeMap<String, V>, from: &str, to: &str, keep_existing: bool) {
+    let Some(value) = map.remove(from) else {
+        return;
+    };
+    if keep_existing {
+        map.entry(to.to_string()).or_insert(value);
+    } else {
+        map.insert(to.to_string(), value);
+    }
+}
+
+/// 直近使用コマンドのファイルを修復する。
+///
+/// 解析できない場合は `.broken` に退避し、
+/// TOMLとして読める範囲の `commands` だけを残して作り直す。
+fn repair_recent_file(storage: &dyn Storage) -> Result<Vec<String>, GclipError> {
+    let Some(contents) = storage.read(StoreFile::Recent)? else {
+        return Ok(Vec::new());
+    };
+    if let Ok(mut parsed) = io::parse_file::<RecentCommands>(storage, StoreFile::Recent, &contents) {
+        let renamed = normalize_recent(&mut parsed);
+        if renamed == 0 {
+            return Ok(Vec::new());
+        }
+        recent::save_recent(storage, &parsed)?;
+        return Ok(vec![format!(
+            "Normalized whitespace in the usage of {renamed} recent command(s)"
+        )]);
+    }
+
+    let salvaged = salvage_recent_commands(&contents);
+    let broken = storage.quarantine(StoreFile::Recent)?;
+    let action = if salvaged.is_empty() {
+        format!(
+            "Reset the unreadable recent file (backup: {})",
+            broken.display()
+        )
+    } else {
+        format!(
+            "Rebuilt the recent file with {} command(s) (backup: {})",
+            salvaged.len(),
+            broken.display()
+        )
+    };
+    let rebuilt = RecentCommands {
+        commands: salvaged,
+        ..Default::default()
+    };
+    recent::save_recent(storage, &rebuilt)?;
+    Ok(vec![action])
+}
+
+/// 直近使用コマンドと使用回数の空白を正規化し、変わった件数を返す。
+///
+/// 登録ファイルの修復で付け替えたコマンドと同じ文字列になるようにし、
+/// 使用回数の記録が元のコマンドに取り残されないようにする。
+pub(super) fn normalize_recent(recent: &mut RecentCommands) -> usize {
+    let mut renamed = 0;
+    let mut seen = HashSet::new();
+    let mut commands = Vec::new();
+    for original in std::mem::take(&mut recent.commands) {
+        let normalized = normalize_whitespace(&original);
+        if normalized != original {
+            renamed += 1;
+        }
+        if !normalized.is_empty() && seen.insert(normalized.clone()) {
+            commands.push(normalized);
+        }
+    }
+    recent.commands = commands;
+
+    for (original, record) in std::mem::take(&mut recent.usage) {
+        let normalized = normalize_whitespace(&original);
+        if normalized != original {
+            renamed += 1;
+        }
+        if !normalized.is_empty() {
+            recent.usage.entry(normalized).or_default().merge(record);
+        }
+    }
+    renamed
+}
+
+/// TOMLとして読める場合、`commands` 配列の文字列だけを取り出す。
+pub(super) fn salvage_recent_commands(contents: &str) -> Vec<String> {
+    let Ok(value) = contents.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let Some(items) = value.get("commands").and_then(|commands| commands.as_array()) else {
+        return Vec::new();
+    };
+    let mut seen = HashSet::new();
+    items
+        .iter()
+        .filter_map(|item| item.as_str())
+        .map(normalize_whitespace)
+        .filter(|command| !command.is_empty() && seen.insert(command.clone()))
+        .collect()
+}
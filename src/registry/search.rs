@@ -1,24 +1,23 @@
-use std::path::PathBuf;
-
-use super::{io, path, RegisteredCommands};
+use super::storage::Storage;
+use super::{io, RegisteredCommands};
+use crate::error::GclipError;
 
 /// 登録済みコマンドから検索する。
 ///
 /// 部分一致で検索し、入力順を保ったまま結果を返す。
-pub(super) fn search_commands(query: &str) -> Result<Vec<String>, String> {
+pub(super) fn search_commands(storage: &dyn Storage, query: &str) -> Result<Vec<String>, GclipError> {
     let normalized = normalize_query(query)?;
-    let registry_path = resolve_registry_path()?;
-    let registered = io::load_registry(&registry_path)?;
+    let registered = io::load_registry(storage)?;
     Ok(filter_commands(&registered, &normalized))
 }
 
 /// クエリ文字列を正規化する。
 ///
 /// 前後の空白を除去し、空の場合はエラーにする。
-pub(super) fn normalize_query(query: &str) -> Result<String, String> {
+pub(super) fn normalize_query(query: &str) -> Result<String, GclipError> {
     let trimmed = query.trim();
     if trimmed.is_empty() {
-        Err("query is empty".to_string())
+        Err(GclipError::InvalidInput("query is empty".to_string()))
     } else {
         Ok(trimmed.to_string())
     }
@@ -39,11 +38,3 @@ pub(super) fn filter_commands(
         .cloned()
         .collect()
 }
-
-/// 登録ファイルのパスを解決する。
-///
-/// `~/.gclip/registered.toml` を前提にする。
-fn resolve_registry_path() -> Result<PathBuf, String> {
-    let data_dir = path::data_dir().ok_or("HOME not set")?;
-    Ok(path::registry_path(&data_dir))
-}
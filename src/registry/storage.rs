@@ -0,0 +1,329 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use super::path;
+use crate::error::GclipError;
+
+/// `Registry` が読み書きするファイルの種類。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StoreFile {
+    /// 登録済みコマンド（`registered.toml`）。
+    Registry,
+    /// 直近使用コマンドと使用回数（`recent.toml`）。
+    Recent,
+    /// ゴミ箱（`trash.toml`）。
+    Trash,
+    /// シェルのフックが記録した実行履歴（`executions.jsonl`）。
+    Executions,
+}
+
+impl StoreFile {
+    /// 保存時のファイル名を返す。
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Registry => "registered.toml",
+            Self::Recent => "recent.toml",
+            Self::Trash => "trash.toml",
+            Self::Executions => "executions.jsonl",
+        }
+    }
+
+    /// 表示用の名前を返す（`registry file` など）。
+    fn label(self) -> &'static str {
+        match self {
+            Self::Registry => "registry file",
+            Self::Recent => "recent file",
+            Self::Trash => "trash file",
+            Self::Executions => "execution log",
+        }
+    }
+}
+
+/// 保存ファイルの読み書きを担う差し替え可能な保存先。
+///
+/// 内容の解析は `Registry` 側で行い、ここでは文字列のまま扱う。
+pub trait Storage: Send + Sync {
+    /// 表示用の保存場所を返す。
+    fn location(&self, file: StoreFile) -> PathBuf;
+
+    /// 内容を読み込む。未作成の場合は `None` を返す。
+    fn read(&self, file: StoreFile) -> Result<Option<String>, GclipError>;
+
+    /// 内容を書き込む。
+    fn write(&self, file: StoreFile, contents: &str) -> Result<(), GclipError>;
+
+    /// 末尾に追記する。
+    ///
+    /// 既定では読み込んでから書き戻す。追記できる保存先では上書きする。
+    fn append(&self, file: StoreFile, contents: &str) -> Result<(), GclipError> {
+        let mut current = self.read(file)?.unwrap_or_default();
+        current.push_str(contents);
+        self.write(file, &current)
+    }
+
+    /// 並行する追記を失わないように内容を置き換える。
+    ///
+    /// `edit` は現在の内容から新しい内容を返し、`None` なら何もしない。未作成の場合は呼ばない。
+    /// 既定では読み込んでから書き戻す。別のプロセスから追記される保存先では上書きする。
+    fn rewrite(&self, file: StoreFile, edit: &dyn Fn(&str) -> Option<String>) -> Result<(), GclipError> {
+        let Some(current) = self.read(file)? else {
+            return Ok(());
+        };
+        match edit(&current) {
+            Some(replaced) => self.write(file, &replaced),
+            None => Ok(()),
+        }
+    }
+
+    /// 内容の大きさ（バイト数）を返す。未作成の場合は0を返す。
+    ///
+    /// 既定では読み込んで数える。大きさだけを調べられる保存先では上書きする。
+    fn size(&self, file: StoreFile) -> Result<u64, GclipError> {
+        Ok(self.read(file)?.map_or(0, |contents| contents.len() as u64))
+    }
+
+    /// 解析できない内容を退避し、退避先の表示用パスを返す。
+    ///
+    /// 退避後は未作成と同じ扱いになる。
+    fn quarantine(&self, file: StoreFile) -> Result<PathBuf, GclipError>;
+}
+
+/// ディレクトリ配下のファイルに保存する既定の保存先。
+#[derive(Debug, Clone)]
+pub struct FsStorage {
+    data_dir: PathBuf,
+}
+
+impl FsStorage {
+    /// 指定したディレクトリに保存する。
+    ///
+    /// ディレクトリは最初の書き込み時に作成する。
+    pub fn new(data_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            data_dir: data_dir.into(),
+        }
+    }
+
+    /// `~/.gclip` に保存する。
+    pub fn from_home() -> Result<Self, GclipError> {
+        let data_dir = path::data_dir().ok_or("HOME not set")?;
+        Ok(Self::new(data_dir))
+    }
+
+    /// 保存先のディレクトリを返す。
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+}
+
+impl Storage for FsStorage {
+    fn location(&self, file: StoreFile) -> PathBuf {
+        self.data_dir.join(file.file_name())
+    }
+
+    fn read(&self, file: StoreFile) -> Result<Option<String>, GclipError> {
+        let file_path = self.location(file);
+        if !file_path.exists() {
+            return Ok(None);
+        }
+        fs::read_to_string(&file_path)
+            .map(Some)
+            .map_err(|err| GclipError::io(&format!("failed to read {}", file.label()), err))
+    }
+
+    fn write(&self, file: StoreFile, contents: &str) -> Result<(), GclipError> {
+        path::ensure_dir(&self.data_dir)?;
+        fs::write(self.location(file), contents)
+            .map_err(|err| GclipError::io(&format!("failed to write {}", file.label()), err))
+    }
+
+    fn append(&self, file: StoreFile, contents: &str) -> Result<(), GclipError> {
+        path::ensure_dir(&self.data_dir)?;
+        append_locked(&self.location(file), contents)
+            .map_err(|err| GclipError::io(&format!("failed to append to {}", file.label()), err))
+    }
+
+    fn rewrite(&self, file: StoreFile, edit: &dyn Fn(&str) -> Option<String>) -> Result<(), GclipError> {
+        rewrite_locked(&self.location(file), edit)
+            .map_err(|err| GclipError::io(&format!("failed to write {}", file.label()), err))
+    }
+
+    fn size(&self, file: StoreFile) -> Result<u64, GclipError> {
+        match fs::metadata(self.location(file)) {
+            Ok(metadata) => Ok(metadata.len()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(0),
+            Err(err) => Err(GclipError::io(&format!("failed to read {}", file.label()), err)),
+        }
+    }
+
+    fn quarantine(&self, file: StoreFile) -> Result<PathBuf, GclipError> {
+        let file_path = self.location(file);
+        let broken = broken_path(&file_path);
+        fs::rename(&file_path, &broken).map_err(|err| {
+            GclipError::io(
+                &format!(
+                    "failed to move {} to {}",
+                    file_path.display(),
+                    broken.display()
+                ),
+                err,
+            )
+        })?;
+        Ok(broken)
+    }
+}
+
+/// ロックを取ってから追記する。
+///
+/// 1回の書き込みで追記し、並行して記録するシェル同士で行が混ざらないようにする。
+/// ロックを待つ間に `rewrite_locked` で置き換えられた場合は、開き直して新しいファイルに書く。
+fn append_locked(file_path: &Path, contents: &str) -> io::Result<()> {
+    loop {
+        let mut log = OpenOptions::new().create(true).append(true).open(file_path)?;
+        lock_file(&log)?;
+        if is_current(&log, file_path)? {
+            return log.write_all(contents.as_bytes());
+        }
+    }
+}
+
+/// 追記と同じロックを取ったまま、一時ファイルに書いてから置き換える。
+///
+/// 置き換えが終わるまで追記を待たせ、その間に記録された行を失わないようにする。
+fn rewrite_locked(file_path: &Path, edit: &dyn Fn(&str) -> Option<String>) -> io::Result<()> {
+    let temp_path = PathBuf::from(format!("{}.tmp", file_path.display()));
+    loop {
+        let mut current = match File::open(file_path) {
+            Ok(current) => current,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        lock_file(&current)?;
+        if !is_current(&current, file_path)? {
+            continue;
+        }
+        let mut contents = String::new();
+        current.read_to_string(&mut contents)?;
+        let Some(replaced) = edit(&contents) else {
+            return Ok(());
+        };
+        fs::write(&temp_path, replaced)?;
+        return fs::rename(&temp_path, file_path);
+    }
+}
+
+/// ファイルに排他ロックをかける。ロックはファイルを閉じると外れる。
+fn lock_file(file: &File) -> io::Result<()> {
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// 開いたファイルがまだ `file_path` にあるものかを返す。
+///
+/// ロックを待つ間に `rewrite` で置き換えられていれば偽になる。
+fn is_current(file: &File, file_path: &Path) -> io::Result<bool> {
+    let opened = file.metadata()?;
+    match fs::metadata(file_path) {
+        Ok(current) => Ok(opened.dev() == current.dev() && opened.ino() == current.ino()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// まだ存在しない退避先のパスを決める。
+///
+/// `.broken`（既にあれば `.broken.1` など）を付ける。
+fn broken_path(file_path: &Path) -> PathBuf {
+    let base = format!("{}.broken", file_path.display());
+    let mut candidate = PathBuf::from(&base);
+    let mut suffix = 1;
+    while candidate.exists() {
+        candidate = PathBuf::from(format!("{base}.{suffix}"));
+        suffix += 1;
+    }
+    candidate
+}
+
+/// メモリ上に保存する保存先。
+///
+/// テストや、ファイルを残したくない組み込み用途で使う。
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    files: Mutex<BTreeMap<StoreFile, String>>,
+    broken: Mutex<Vec<(StoreFile, String)>>,
+}
+
+impl MemoryStorage {
+    /// 空の保存先を作る。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 初期内容を持たせる。
+    pub fn with_file(self, file: StoreFile, contents: impl Into<String>) -> Self {
+        lock(&self.files).insert(file, contents.into());
+        self
+    }
+
+    /// 現在の内容を返す。未作成の場合は `None` を返す。
+    pub fn contents(&self, file: StoreFile) -> Option<String> {
+        lock(&self.files).get(&file).cloned()
+    }
+
+    /// 退避された内容を退避した順に返す。
+    pub fn quarantined(&self, file: StoreFile) -> Vec<String> {
+        lock(&self.broken)
+            .iter()
+            .filter(|(kind, _)| *kind == file)
+            .map(|(_, contents)| contents.clone())
+            .collect()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn location(&self, file: StoreFile) -> PathBuf {
+        Path::new("memory").join(file.file_name())
+    }
+
+    fn read(&self, file: StoreFile) -> Result<Option<String>, GclipError> {
+        Ok(self.contents(file))
+    }
+
+    fn write(&self, file: StoreFile, contents: &str) -> Result<(), GclipError> {
+        lock(&self.files).insert(file, contents.to_string());
+        Ok(())
+    }
+
+    fn quarantine(&self, file: StoreFile) -> Result<PathBuf, GclipError> {
+        let Some(contents) = lock(&self.files).remove(&file) else {
+            return Err(GclipError::Io(format!("{} does not exist", file.file_name())));
+        };
+        let mut broken = lock(&self.broken);
+        broken.push((file, contents));
+        let count = broken.iter().filter(|(kind, _)| *kind == file).count();
+        let suffix = if count == 1 {
+            String::new()
+        } else {
+            format!(".{}", count - 1)
+        };
+        Ok(PathBuf::from(format!(
+            "{}.broken{suffix}",
+            self.location(file).display()
+        )))
+    }
+}
+
+/// ロックを取得する。
+///
+/// 別スレッドのパニックでポイズンしていても内容は使えるため、そのまま続ける。
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
@@ -1,4 +1,34 @@
 use super::super::add::normalize_command;
+use super::super::{CommandDetails, Condition, MemoryStorage, Registry, Storage, StoreFile};
+use crate::error::GclipError;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// 書き込みの回数を数える保存先。
+struct CountingStorage {
+    inner: MemoryStorage,
+    writes: Arc<AtomicUsize>,
+}
+
+impl Storage for CountingStorage {
+    fn location(&self, file: StoreFile) -> PathBuf {
+        self.inner.location(file)
+    }
+
+    fn read(&self, file: StoreFile) -> Result<Option<String>, GclipError> {
+        self.inner.read(file)
+    }
+
+    fn write(&self, file: StoreFile, contents: &str) -> Result<(), GclipError> {
+        self.writes.fetch_add(1, Ordering::SeqCst);
+        self.inner.write(file, contents)
+    }
+
+    fn quarantine(&self, file: StoreFile) -> Result<PathBuf, GclipError> {
+        self.inner.quarantine(file)
+    }
+}
 
 #[test]
 fn normalize_command_trims_whitespace() {
@@ -20,3 +50,40 @@ fn accepts_long_command() {
     let normalized = normalize_command(command).expect("long command should be valid");
     assert_eq!(normalized, command);
 }
+
+#[test]
+fn add_with_details_writes_once() {
+    // キーと説明、タグ、条件、作業ディレクトリを付けても書き込みは1回にまとまる。
+    let writes = Arc::new(AtomicUsize::new(0));
+    let registry = Registry::new(CountingStorage {
+        inner: MemoryStorage::new(),
+        writes: Arc::clone(&writes),
+    });
+    let extra = CommandDetails {
+        description: Some("deploy".to_string()),
+        tags: vec!["infra".to_string()],
+        when: Some("env=AWS_PROFILE".parse::<Condition>().expect("condition")),
+        cwd: Some("/srv/app".to_string()),
+        ..Default::default()
+    };
+    let (_, added) = registry
+        .add_command_with_details(" make deploy ", Some("md"), &extra, false)
+        .expect("add");
+    assert_eq!(added, 1);
+    assert_eq!(writes.load(Ordering::SeqCst), 1);
+
+    let details = &registry.command_details().expect("details")["make deploy"];
+    assert_eq!(details.description.as_deref(), Some("deploy"));
+    assert_eq!(details.tags, vec!["infra".to_string()]);
+    assert_eq!(details.cwd.as_deref(), Some("/srv/app"));
+    assert!(details.when.is_some());
+    assert_eq!(
+        registry.keyed_commands().expect("keys"),
+        vec![("md".to_string(), "make deploy".to_string())]
+    );
+
+    registry
+        .add_command_with_details("make deploy", None, &extra, false)
+        .expect("add again");
+    assert_eq!(writes.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,75 @@
+use super::super::compose::expand_references;
+use super::super::{MemoryStorage, Registry};
+use crate::error::GclipError;
+use std::collections::BTreeMap;
+
+fn keys(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(key, command)| (key.to_string(), command.to_string()))
+        .collect()
+}
+
+#[test]
+fn references_are_expanded_recursively() {
+    // 参照先に含まれる参照も展開し、続く引数はそのまま残す。
+    let table = keys(&[
+        ("build", "cargo build --release"),
+        ("deploy", "@build && ./deploy.sh"),
+    ]);
+    assert_eq!(
+        expand_references("@build && @deploy staging", &table).expect("expand"),
+        "cargo build --release && cargo build --release && ./deploy.sh staging"
+    );
+    assert_eq!(
+        expand_references("(@build;@build)|tee log", &table).expect("expand"),
+        "(cargo build --release;cargo build --release)|tee log"
+    );
+}
+
+#[test]
+fn only_whole_words_outside_quotes_are_references() {
+    // 単語の途中、引用符の中、エスケープされた `@`、割り当てのないキーは置き換えない。
+    let table = keys(&[("build", "make")]);
+    for literal in [
+        "git log HEAD@{1}",
+        "ssh me@build",
+        "echo '@build' \"@build\"",
+        r"echo \@build",
+        "curl -d @body.json",
+        "echo @builds @build.",
+    ] {
+        assert_eq!(expand_references(literal, &table).expect("expand"), literal);
+    }
+}
+
+#[test]
+fn cycles_are_reported_with_their_path() {
+    // 循環する参照は、たどった経路つきのエラーにする。
+    let table = keys(&[("a", "echo @b"), ("b", "echo @a"), ("self", "make @self")]);
+    match expand_references("@a", &table) {
+        Err(GclipError::InvalidInput(message)) => {
+            assert_eq!(message, "reference cycle: @a -> @b -> @a");
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+    assert!(expand_references("@self", &table).is_err());
+    // 同じ参照を並べるだけなら循環ではない。
+    assert_eq!(expand_references("@b; @b", &keys(&[("b", "ls")])).expect("expand"), "ls; ls");
+}
+
+#[test]
+fn registry_expands_with_assigned_keys() {
+    // 短縮キーの割り当てがそのまま参照の名前になる。
+    let registry = Registry::new(MemoryStorage::new());
+    registry.add_command_with_key("cargo build", "build").expect("add");
+    registry.add_command_with_key("@build && cargo test", "check").expect("add");
+    assert_eq!(
+        expand_references(
+            "@check -- --nocapture",
+            &registry.reference_table().expect("table")
+        )
+        .expect("expand"),
+        "cargo build && cargo test -- --nocapture"
+    );
+}
@@ -0,0 +1,151 @@
+use super::super::conditions::{expand_home, partition_active, Condition, WhenContext};
+use super::super::{CommandDetails, MemoryStorage, Registry};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn context(cwd: &str) -> WhenContext {
+    WhenContext {
+        cwd: Some(PathBuf::from(cwd)),
+        home: Some(PathBuf::from("/home/user")),
+        hostname: Some("work-laptop".to_string()),
+        vars: HashSet::from(["KUBECONFIG".to_string()]),
+    }
+}
+
+fn condition(spec: &str) -> Condition {
+    spec.parse().expect("condition should parse")
+}
+
+#[test]
+fn parses_kind_and_value() {
+    // `KIND=VALUE` の形式だけを受け付ける。
+    assert_eq!(condition("env=KUBECONFIG").env.as_deref(), Some("KUBECONFIG"));
+    assert_eq!(condition("cwd = ~/src/*").cwd.as_deref(), Some("~/src/*"));
+    assert!("os=linux".parse::<Condition>().is_err());
+    assert!("file=".parse::<Condition>().is_err());
+    assert!("Cargo.toml".parse::<Condition>().is_err());
+}
+
+#[test]
+fn evaluates_each_predicate() {
+    // ディレクトリ、環境変数、ホスト名の条件を現在の環境で判定する。
+    let here = context("/home/user/src/infra/modules");
+    assert!(condition("cwd=~/src/infra").holds(&here));
+    assert!(condition("cwd=*/src/*").holds(&here));
+    assert!(!condition("cwd=~/src/app").holds(&here));
+    assert!(condition("env=KUBECONFIG").holds(&here));
+    assert!(!condition("env=AWS_PROFILE").holds(&here));
+    assert!(condition("host=work-*").holds(&here));
+    assert!(!condition("host=home-*").holds(&here));
+    assert!(Condition::default().holds(&WhenContext::default()));
+}
+
+#[test]
+fn only_the_own_home_is_expanded() {
+    // 単独の `~` と `~/` だけを展開し、`~bob` のような他のユーザーのホームはそのまま残す。
+    let home = Some(Path::new("/home/user"));
+    assert_eq!(expand_home("~", home), "/home/user");
+    assert_eq!(expand_home("~/src/*", home), "/home/user/src/*");
+    assert_eq!(expand_home("~bob/src", home), "~bob/src");
+    assert_eq!(expand_home("~/src", None), "~/src");
+    assert!(!condition("cwd=~bob/src").holds(&context("/home/userbob/src")));
+}
+
+#[test]
+fn merged_conditions_must_all_hold() {
+    // 複数の条件はすべてを満たすときだけ有効になる。
+    let mut merged = condition("env=KUBECONFIG");
+    merged.merge(&condition("host=home-*"));
+    assert_eq!(merged.to_string(), "env=KUBECONFIG, host=home-*");
+    assert!(!merged.holds(&context("/")));
+}
+
+#[test]
+fn file_is_searched_in_ancestors() {
+    // カレントディレクトリか祖先にファイルがあれば満たす。
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("gclip-test-{}-{nanos}", std::process::id()));
+    let nested = root.join("src").join("bin");
+    fs::create_dir_all(&nested).expect("create dirs");
+    fs::write(root.join("Cargo.toml"), "").expect("write file");
+
+    let here = context(&nested.display().to_string());
+    assert!(condition("file=Cargo.toml").holds(&here));
+    assert!(!condition("file=package.json").holds(&here));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn inactive_commands_are_demoted() {
+    // 条件を満たさないコマンドは、元の順を保ったまま後ろに回る。
+    let mut conditions = BTreeMap::new();
+    conditions.insert("kubectl get pods".to_string(), condition("env=KUBECONFIG"));
+    conditions.insert("make deploy".to_string(), condition("host=build-*"));
+    let commands = ["make deploy", "ls", "kubectl get pods"].map(String::from).to_vec();
+
+    assert_eq!(
+        partition_active(commands, &conditions, &context("/")),
+        ["ls", "kubectl get pods", "make deploy"]
+    );
+}
+
+#[test]
+fn conditions_are_stored_and_removed_with_the_command() {
+    // 条件は登録済みコマンドにだけ設定でき、削除すると一緒に消える。
+    let registry = Registry::new(MemoryStorage::new());
+    registry.add_command("make deploy").expect("add");
+    let commands = vec!["make deploy".to_string()];
+    registry
+        .set_conditions(&commands, &condition("cwd=~/src/infra"))
+        .expect("set conditions");
+    registry
+        .set_conditions(&commands, &condition("env=AWS_PROFILE"))
+        .expect("set conditions");
+    assert!(registry
+        .set_conditions(&["ls".to_string()], &condition("env=HOME"))
+        .is_err());
+
+    let details = registry.command_details().expect("details");
+    assert_eq!(
+        details["make deploy"].when.as_ref().map(ToString::to_string).as_deref(),
+        Some("cwd=~/src/infra, env=AWS_PROFILE")
+    );
+
+    registry.remove_command("make deploy").expect("remove");
+    assert!(registry.command_details().expect("details").is_empty());
+}
+
+#[test]
+fn conditions_can_be_cleared_or_replaced() {
+    // 置き換えでは既存の条件を消し、空の条件なら消すだけにする。
+    let registry = Registry::new(MemoryStorage::new());
+    registry.add_command("make deploy").expect("add");
+    let commands = vec!["make deploy".to_string()];
+    registry
+        .set_conditions(&commands, &condition("cwd=~/src/infra"))
+        .expect("set conditions");
+
+    let replacing = CommandDetails {
+        when: Some(condition("env=AWS_PROFILE")),
+        ..Default::default()
+    };
+    registry
+        .add_command_with_details("make deploy", None, &replacing, true)
+        .expect("replace conditions");
+    let details = registry.command_details().expect("details");
+    assert_eq!(
+        details["make deploy"].when.as_ref().map(ToString::to_string).as_deref(),
+        Some("env=AWS_PROFILE")
+    );
+
+    registry
+        .add_command_with_details("make deploy", None, &CommandDetails::default(), true)
+        .expect("clear conditions");
+    let cleared = registry.command_details().expect("details");
+    assert!(cleared.get("make deploy").is_none_or(|found| found.when.is_none()));
+}
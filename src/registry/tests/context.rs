@@ -0,0 +1,106 @@
+use super::super::context::{rank_by_context, UsageContext};
+use super::super::recent::record_recent;
+use super::super::storage::MemoryStorage;
+use super::super::usage::UsageRecord;
+use super::super::UsageSource;
+use std::collections::BTreeMap;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn context(cwd: &str, repo_root: Option<&str>) -> UsageContext {
+    UsageContext {
+        cwd: Some(cwd.to_string()),
+        repo_root: repo_root.map(str::to_string),
+    }
+}
+
+fn used_in(places: &[UsageContext]) -> UsageRecord {
+    let mut record = UsageRecord::default();
+    for place in places {
+        record.record(UsageSource::Search, 1);
+        record.record_context(place);
+    }
+    record
+}
+
+fn commands(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+#[test]
+fn commands_used_here_rank_first() {
+    // 同じディレクトリ、同じリポジトリで使ったものを先にし、それ以外は元の順を保つ。
+    let foo = context("/src/foo", Some("/src/foo"));
+    let mut usage = BTreeMap::new();
+    usage.insert(
+        "cargo nextest run -p foo".to_string(),
+        used_in(&[context("/src/foo/crates/a", Some("/src/foo"))]),
+    );
+    usage.insert("make".to_string(), used_in(&[foo.clone(), foo.clone()]));
+    usage.insert("ls".to_string(), used_in(&[context("/tmp", None)]));
+
+    let ranked = rank_by_context(
+        commands(&["ls", "git status", "cargo nextest run -p foo", "make"]),
+        &usage,
+        &foo,
+    );
+    assert_eq!(
+        ranked,
+        commands(&["make", "cargo nextest run -p foo", "ls", "git status"])
+    );
+}
+
+#[test]
+fn commands_bound_to_another_repo_rank_last() {
+    // 別のリポジトリでしか使っていないコマンドは、リポジトリの外では後ろに回す。
+    let mut usage = BTreeMap::new();
+    usage.insert(
+        "cargo nextest run -p foo".to_string(),
+        used_in(&[context("/src/foo", Some("/src/foo"))]),
+    );
+    usage.insert(
+        "git status".to_string(),
+        used_in(&[context("/src/foo", Some("/src/foo")), context("/etc", None)]),
+    );
+
+    let ranked = rank_by_context(
+        commands(&["cargo nextest run -p foo", "git status", "ls"]),
+        &usage,
+        &context("/home/user", None),
+    );
+    assert_eq!(ranked, commands(&["git status", "ls", "cargo nextest run -p foo"]));
+}
+
+#[test]
+fn record_stores_dir_and_repo_counts() {
+    // 使用のたびに、ディレクトリとリポジトリごとの回数を加算する。
+    let storage = MemoryStorage::new();
+    let place = context("/src/foo/crates/a", Some("/src/foo"));
+    record_recent(&storage, "make", UsageSource::Search, &place, 1).expect("record");
+    record_recent(&storage, "make", UsageSource::Search, &place, 2).expect("record");
+    record_recent(&storage, "make", UsageSource::Search, &UsageContext::default(), 3).expect("record");
+
+    let snapshot = super::super::recent::usage_snapshot(&storage).expect("snapshot");
+    let record = &snapshot.usage["make"];
+    assert_eq!(record.count, 3);
+    assert_eq!(record.dirs.get("/src/foo/crates/a"), Some(&2));
+    assert_eq!(record.repos.get("/src/foo"), Some(&2));
+}
+
+#[test]
+fn repo_root_is_found_in_ancestors() {
+    // 祖先ディレクトリの `.git` からリポジトリのルートを見つける。
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let root = std::env::temp_dir().join(format!("gclip-test-{}-{nanos}", std::process::id()));
+    let nested = root.join("crates").join("a");
+    fs::create_dir_all(&nested).expect("create dirs");
+    fs::create_dir(root.join(".git")).expect("create .git");
+
+    let found = UsageContext::for_dir(&nested);
+    assert_eq!(found.cwd, Some(nested.display().to_string()));
+    assert_eq!(found.repo_root, Some(root.display().to_string()));
+    let _ = fs::remove_dir_all(&root);
+}
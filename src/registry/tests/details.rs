@@ -0,0 +1,28 @@
+use super::super::details::{apply_details, normalize_tags};
+use super::super::remove::remove_matching;
+use super::super::RegisteredCommands;
+
+#[test]
+fn normalize_tags_splits_and_dedupes() {
+    // カンマと空白で区切り、`#` と重複を取り除くことを確認する。
+    let input = vec!["git, vcs".to_string(), "#git  ops".to_string(), " ".to_string()];
+    assert_eq!(normalize_tags(&input), vec!["git", "vcs", "ops"]);
+}
+
+#[test]
+fn apply_details_adds_tags_and_keeps_description_on_empty() {
+    // タグは追加され、空の説明では既存の説明が残ることを確認する。
+    let mut registered = RegisteredCommands {
+        commands: vec!["git status".to_string()],
+        ..Default::default()
+    };
+    assert!(apply_details(&mut registered, "git status", Some(" Show status "), &["git".to_string()]));
+    assert!(apply_details(&mut registered, "git status", Some(""), &["vcs,git".to_string()]));
+    assert!(!apply_details(&mut registered, "git status", None, &[]));
+
+    assert_eq!(registered.descriptions["git status"], "Show status");
+    assert_eq!(registered.tags["git status"], vec!["git", "vcs"]);
+
+    remove_matching(&mut registered, "git status");
+    assert!(registered.descriptions.is_empty() && registered.tags.is_empty());
+}
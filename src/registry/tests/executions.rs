@@ -0,0 +1,111 @@
+use super::super::storage::{FsStorage, MemoryStorage, Storage, StoreFile};
+use super::super::executions::rotate;
+use super::super::{Execution, Registry};
+use std::fs;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn execution(command: &str, exit: i32) -> Execution {
+    Execution {
+        command: command.to_string(),
+        cwd: Some("/work".to_string()),
+        exit,
+        duration_ms: 120,
+        at: 1_700_000_000,
+    }
+}
+
+#[test]
+fn executions_are_appended_in_order() {
+    // 1行1件で追記し、記録順に読み出せることを確認する。
+    let registry = Registry::in_memory();
+    registry.record_execution(&execution("make", 0)).expect("record");
+    registry.record_execution(&execution("mkae", 127)).expect("record");
+    registry.record_execution(&execution("  ", 0)).expect("record");
+
+    let executions = registry.executions().expect("executions");
+    assert_eq!(executions, vec![execution("make", 0), execution("mkae", 127)]);
+    assert!(!executions[1].succeeded());
+    let contents = registry
+        .storage()
+        .read(StoreFile::Executions)
+        .expect("read")
+        .expect("log exists");
+    assert_eq!(contents.lines().count(), 2);
+}
+
+#[test]
+fn broken_lines_are_skipped() {
+    // 書き込み途中の行があっても、残りの記録は読めることを確認する。
+    let registry = Registry::new(MemoryStorage::new().with_file(
+        StoreFile::Executions,
+        "{\"command\":\"ls\",\"exit\":0,\"at\":1}\n{\"command\":\"gi",
+    ));
+    let executions = registry.executions().expect("executions");
+    assert_eq!(executions.len(), 1);
+    assert_eq!(executions[0].command, "ls");
+    assert_eq!(executions[0].cwd, None);
+}
+
+#[test]
+fn file_storage_appends_without_rewriting() {
+    // ファイルの保存先では既存の内容の後ろに追記される。
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!("gclip-test-{}-{nanos}", std::process::id()));
+    let storage = FsStorage::new(&dir);
+    storage.append(StoreFile::Executions, "one\n").expect("append");
+    storage.append(StoreFile::Executions, "two\n").expect("append");
+    assert_eq!(
+        fs::read_to_string(dir.join("executions.jsonl")).expect("log exists"),
+        "one\ntwo\n"
+    );
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn rotation_keeps_only_recent_whole_lines() {
+    // 上限を超えたら末尾の行だけを残し、行の途中からは残さないことを確認する。
+    let storage = MemoryStorage::new().with_file(StoreFile::Executions, "aaaa\nbbbb\ncccc\n");
+    rotate(&storage, 20, 8).expect("rotate");
+    assert_eq!(storage.contents(StoreFile::Executions).as_deref(), Some("aaaa\nbbbb\ncccc\n"));
+
+    rotate(&storage, 12, 8).expect("rotate");
+    assert_eq!(storage.contents(StoreFile::Executions).as_deref(), Some("cccc\n"));
+}
+
+#[test]
+fn file_storage_rewrite_keeps_concurrent_appends() {
+    // 置き換えの最中に別スレッドから追記しても、行が失われないことを確認する。
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!("gclip-test-{}-{nanos}", std::process::id()));
+    let storage = FsStorage::new(&dir);
+    storage.append(StoreFile::Executions, "start\n").expect("append");
+    std::thread::scope(|scope| {
+        for writer in 0..4 {
+            let appender = &storage;
+            scope.spawn(move || {
+                for line in 0..100 {
+                    appender
+                        .append(StoreFile::Executions, &format!("{writer}-{line}\n"))
+                        .expect("append");
+                }
+            });
+        }
+        for _ in 0..20 {
+            // 読み込んでから書き戻すまでの間に追記が入りやすいよう、少し待つ。
+            let slow_copy = |contents: &str| {
+                std::thread::sleep(Duration::from_millis(1));
+                Some(contents.to_string())
+            };
+            storage.rewrite(StoreFile::Executions, &slow_copy).expect("rewrite");
+        }
+    });
+    let contents = fs::read_to_string(dir.join("executions.jsonl")).expect("log exists");
+    assert_eq!(contents.lines().count(), 401);
+    let _ = fs::remove_dir_all(&dir);
+}
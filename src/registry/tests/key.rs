@@ -0,0 +1,58 @@
+use super::super::key::{assign_key, drop_orphan_keys, validate_key};
+use super::super::RegisteredCommands;
+use crate::error::GclipError;
+
+#[test]
+fn validate_key_rejects_invalid_names() {
+    // 空文字列、先頭の `-`、空白を含むキーは拒否する。
+    assert!(validate_key(" ").is_err());
+    assert!(validate_key("-x").is_err());
+    assert!(validate_key("g s").is_err());
+    assert_eq!(validate_key(" gs ").expect("should be valid"), "gs");
+}
+
+#[test]
+fn assign_key_replaces_previous_key() {
+    // 同じコマンドに再割り当てすると古いキーは消えることを確認する。
+    let mut registered = RegisteredCommands {
+        commands: vec!["git status".to_string()],
+        ..Default::default()
+    };
+    assign_key(&mut registered, "git status", "gs").expect("assign should succeed");
+    assign_key(&mut registered, "git status", "gst").expect("assign should succeed");
+    assert_eq!(registered.keys.len(), 1);
+    assert_eq!(registered.keys.get("gst").map(String::as_str), Some("git status"));
+}
+
+#[test]
+fn assign_key_rejects_key_used_by_other_command() {
+    // 別のコマンドが使用中のキーは奪わない。
+    let mut registered = RegisteredCommands {
+        commands: vec!["git status".to_string(), "git stash".to_string()],
+        ..Default::default()
+    };
+    assign_key(&mut registered, "git status", "gs").expect("assign should succeed");
+    assert!(assign_key(&mut registered, "git stash", "gs").is_err());
+}
+
+#[test]
+fn drop_orphan_keys_removes_unregistered_targets() {
+    // 登録済みにないコマンドを指すキーは除去される。
+    let mut registered = RegisteredCommands {
+        commands: vec!["ls".to_string()],
+        ..Default::default()
+    };
+    registered.keys.insert("l".to_string(), "ls".to_string());
+    registered.keys.insert("gs".to_string(), "git status".to_string());
+    drop_orphan_keys(&mut registered);
+    assert_eq!(registered.keys.len(), 1);
+    assert!(registered.keys.contains_key("l"));
+}
+
+#[test]
+fn key_errors_are_invalid_input() {
+    // 呼び出し側が文言を解析せずに入力エラーと判別できることを確認する。
+    let err = validate_key("-x").expect_err("should be rejected");
+    assert!(matches!(err, GclipError::InvalidInput(_)));
+    assert_eq!(err.exit_code(), 2);
+}
@@ -6,6 +6,7 @@ fn adds_new_commands() {
     // 既存にないコマンドだけが追加されることを確認する。
     let mut registered = RegisteredCommands {
         commands: vec!["ls".to_string()],
+        ..Default::default()
     };
     let added = merge_commands(&mut registered, &["pwd".to_string()]);
     assert_eq!(added, 1);
@@ -17,6 +18,7 @@ fn does_not_add_duplicates() {
     // 既に登録済みのコマンドは追加されないことを確認する。
     let mut registered = RegisteredCommands {
         commands: vec!["ls".to_string()],
+        ..Default::default()
     };
     let added = merge_commands(&mut registered, &["ls".to_string()]);
     assert_eq!(added, 0);
@@ -28,6 +30,7 @@ fn no_change_on_empty_input() {
     // 入力が空の場合は何も変更されないことを確認する。
     let mut registered = RegisteredCommands {
         commands: vec!["ls".to_string()],
+        ..Default::default()
     };
     let added = merge_commands(&mut registered, &[]);
     assert_eq!(added, 0);
@@ -39,6 +42,7 @@ fn ignores_duplicate_inputs() {
     // 入力側に同じコマンドが複数あっても追加は1回になる。
     let mut registered = RegisteredCommands {
         commands: vec!["ls".to_string()],
+        ..Default::default()
     };
     let added = merge_commands(
         &mut registered,
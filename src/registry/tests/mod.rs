@@ -5,4 +5,14 @@ mod list;
 mod remove;
 mod doctor;
 mod recent;
-mod support;
+mod key;
+mod trash;
+mod repair;
+mod storage;
+mod details;
+mod executions;
+mod context;
+mod conditions;
+mod workdir;
+mod workflow;
+mod compose;
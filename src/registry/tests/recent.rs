@@ -1,26 +1,80 @@
-use super::support::TestHome;
-use super::super::recent::{record_recent, recent_commands};
+use super::super::recent::{record_recent, recent_commands, usage_snapshot};
+use super::super::storage::MemoryStorage;
+use super::super::usage::{keep_top, MAX_CONTEXT_ENTRIES};
+use super::super::{UsageContext, UsageSource};
+use std::collections::BTreeMap;
+
+const NOW: u64 = 1_760_832_000;
 
 #[test]
 fn record_recent_moves_command_to_front() {
     // 同じコマンドがあれば先頭に移動し、重複しないことを確認する。
-    let _home = TestHome::new();
-    record_recent("ls").expect("record should succeed");
-    record_recent("pwd").expect("record should succeed");
-    record_recent("ls").expect("record should succeed");
+    let storage = MemoryStorage::new();
+    record_recent(&storage, "ls", UsageSource::Search, &UsageContext::default(), NOW).expect("record should succeed");
+    record_recent(&storage, "pwd", UsageSource::Search, &UsageContext::default(), NOW).expect("record should succeed");
+    record_recent(&storage, "ls", UsageSource::Search, &UsageContext::default(), NOW).expect("record should succeed");
 
-    let recent = recent_commands(10).expect("recent should succeed");
+    let recent = recent_commands(&storage, 10).expect("recent should succeed");
     assert_eq!(recent, vec!["ls".to_string(), "pwd".to_string()]);
 }
 
 #[test]
 fn recent_commands_respects_limit() {
     // 取得件数の上限が守られることを確認する。
-    let _home = TestHome::new();
-    record_recent("one").expect("record should succeed");
-    record_recent("two").expect("record should succeed");
-    record_recent("three").expect("record should succeed");
+    let storage = MemoryStorage::new();
+    record_recent(&storage, "one", UsageSource::Search, &UsageContext::default(), NOW).expect("record should succeed");
+    record_recent(&storage, "two", UsageSource::Search, &UsageContext::default(), NOW).expect("record should succeed");
+    record_recent(&storage, "three", UsageSource::Search, &UsageContext::default(), NOW).expect("record should succeed");
 
-    let recent = recent_commands(2).expect("recent should succeed");
+    let recent = recent_commands(&storage, 2).expect("recent should succeed");
     assert_eq!(recent, vec!["three".to_string(), "two".to_string()]);
 }
+
+#[test]
+fn record_recent_counts_uses_by_source() {
+    // 使用回数・日時・経路・日別回数が記録されることを確認する。
+    let storage = MemoryStorage::new();
+    record_recent(&storage, "ls", UsageSource::Search, &UsageContext::default(), NOW).expect("record should succeed");
+    record_recent(&storage, "ls", UsageSource::Recent, &UsageContext::default(), NOW + 60).expect("record should succeed");
+
+    let snapshot = usage_snapshot(&storage).expect("snapshot should succeed");
+    let usage = &snapshot.usage["ls"];
+    assert_eq!(usage.count, 2);
+    assert_eq!((usage.search, usage.recent, usage.editor), (1, 1, 0));
+    assert_eq!((usage.first_used, usage.last_used), (NOW, NOW + 60));
+    assert_eq!(snapshot.daily.get("2025-10-19"), Some(&2));
+}
+
+#[test]
+fn record_recent_caps_usage_locations() {
+    // 使用したディレクトリは上限までに抑え、今回のディレクトリは必ず残す。
+    let storage = MemoryStorage::new();
+    for index in 0..=MAX_CONTEXT_ENTRIES {
+        let context = UsageContext {
+            cwd: Some(format!("/work/{index:02}")),
+            repo_root: None,
+        };
+        record_recent(&storage, "ls", UsageSource::Search, &context, NOW).expect("record should succeed");
+    }
+
+    let snapshot = usage_snapshot(&storage).expect("snapshot should succeed");
+    let dirs = &snapshot.usage["ls"].dirs;
+    assert_eq!(dirs.len(), MAX_CONTEXT_ENTRIES);
+    assert!(dirs.contains_key(&format!("/work/{MAX_CONTEXT_ENTRIES:02}")));
+}
+
+#[test]
+fn keep_top_drops_the_least_used() {
+    // 回数の少ないものから切り捨てる。
+    let mut counts = BTreeMap::from([
+        ("a".to_string(), 5),
+        ("b".to_string(), 1),
+        ("c".to_string(), 3),
+        ("d".to_string(), 1),
+    ]);
+    keep_top(&mut counts, "d", 3);
+    assert_eq!(
+        counts,
+        BTreeMap::from([("a".to_string(), 5), ("c".to_string(), 3), ("d".to_string(), 1)])
+    );
+}
@@ -1,5 +1,7 @@
-use super::super::remove::{normalize_command, remove_matching};
-use super::super::RegisteredCommands;
+use super::super::recent::{load_recent, record_recent};
+use super::super::remove::{normalize_command, remove_command, remove_matching};
+use super::super::storage::MemoryStorage;
+use super::super::{io, RegisteredCommands, UsageContext, UsageSource};
 
 #[test]
 fn normalize_command_trims_whitespace() {
@@ -19,6 +21,7 @@ fn remove_matching_removes_only_exact_match() {
     // 完全一致だけが削除されることを確認する。
     let mut registered = RegisteredCommands {
         commands: vec!["git status".to_string(), "git".to_string()],
+        ..Default::default()
     };
     let removed = remove_matching(&mut registered, "git");
     assert_eq!(removed, 1);
@@ -27,3 +30,20 @@ fn remove_matching_removes_only_exact_match() {
         vec!["git status".to_string()]
     );
 }
+
+#[test]
+fn remove_command_forgets_usage() {
+    // 削除したコマンドの使用回数の記録は残さない。
+    let storage = MemoryStorage::new();
+    let registered = RegisteredCommands {
+        commands: vec!["ls".to_string()],
+        ..Default::default()
+    };
+    io::write_registry(&storage, &registered).expect("write should succeed");
+    record_recent(&storage, "ls", UsageSource::Search, &UsageContext::default(), 10).expect("record should succeed");
+
+    remove_command(&storage, " ls ").expect("remove should succeed");
+    let recent = load_recent(&storage).expect("recent should load");
+    assert!(recent.commands.is_empty());
+    assert!(recent.usage.is_empty());
+}
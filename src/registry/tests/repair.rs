@@ -0,0 +1,103 @@
+use super::super::recent::RecentCommands;
+use super::super::repair::{
+    normalize_recent, normalize_whitespace, repair_commands, salvage_recent_commands,
+};
+use super::super::usage::UsageRecord;
+use super::super::RegisteredCommands;
+use std::collections::BTreeMap;
+
+#[test]
+fn normalize_whitespace_keeps_quoted_text() {
+    // 引用符の外の空白だけがまとめられることを確認する。
+    assert_eq!(normalize_whitespace("  git   status\t-s "), "git status -s");
+    assert_eq!(normalize_whitespace("echo 'a   b'  \"c  d\""), "echo 'a   b' \"c  d\"");
+    assert_eq!(normalize_whitespace(r"touch a\  b"), r"touch a\  b");
+}
+
+#[test]
+fn repair_commands_dedupes_and_drops_empty() {
+    // 空コマンドを除き、正規化後の重複は最初のものを残す。
+    let mut registered = RegisteredCommands {
+        commands: ["ls", "  ", "git  status", "git status", "ls"]
+            .map(String::from)
+            .to_vec(),
+        keys: BTreeMap::from([("gs".to_string(), "git  status".to_string())]),
+        added: BTreeMap::from([("git  status".to_string(), 10)]),
+        ..Default::default()
+    };
+    let actions = repair_commands(&mut registered);
+
+    assert_eq!(registered.commands, vec!["ls".to_string(), "git status".to_string()]);
+    assert_eq!(registered.keys.get("gs").map(String::as_str), Some("git status"));
+    assert_eq!(registered.added.get("git status"), Some(&10));
+    assert_eq!(actions.len(), 4);
+    assert!(actions.iter().any(|action| action == "Removed 1 empty command(s)"));
+}
+
+#[test]
+fn repair_commands_keeps_metadata_of_the_first_occurrence() {
+    // 空白違いが統合されるときは、先に出現したものの説明を残し、ないものだけ後ろから補う。
+    let mut registered = RegisteredCommands {
+        commands: ["git  status", "git status", "ls", "ls  -a", "ls -a"]
+            .map(String::from)
+            .to_vec(),
+        descriptions: BTreeMap::from([
+            ("git  status".to_string(), "first".to_string()),
+            ("git status".to_string(), "second".to_string()),
+            ("ls  -a".to_string(), "first".to_string()),
+            ("ls -a".to_string(), "second".to_string()),
+        ]),
+        tags: BTreeMap::from([("git status".to_string(), vec!["git".to_string()])]),
+        ..Default::default()
+    };
+    repair_commands(&mut registered);
+
+    assert_eq!(registered.descriptions.get("git status").map(String::as_str), Some("first"));
+    assert_eq!(registered.descriptions.get("ls -a").map(String::as_str), Some("first"));
+    assert_eq!(registered.tags.get("git status"), Some(&vec!["git".to_string()]));
+}
+
+#[test]
+fn repair_commands_reports_nothing_for_clean_registry() {
+    // 問題がなければ処理は空になる。
+    let mut registered = RegisteredCommands {
+        commands: vec!["ls".to_string()],
+        ..Default::default()
+    };
+    assert!(repair_commands(&mut registered).is_empty());
+}
+
+#[test]
+fn salvage_keeps_readable_commands() {
+    // スキーマに合わない直近ファイルから、文字列のコマンドだけを取り出す。
+    let contents = "commands = [\"ls\", 3, \"ls\", \"pwd\"]\nusage = 1\n";
+    assert_eq!(salvage_recent_commands(contents), vec!["ls".to_string(), "pwd".to_string()]);
+    assert!(salvage_recent_commands("commands = [").is_empty());
+}
+
+#[test]
+fn normalize_recent_moves_usage_to_the_repaired_command() {
+    // 登録ファイルの修復で正規化したコマンドへ使用回数を付け替え、重なった記録は合算する。
+    let record = |count, first_used, last_used| UsageRecord {
+        count,
+        first_used,
+        last_used,
+        search: count,
+        ..Default::default()
+    };
+    let mut recent = RecentCommands {
+        commands: ["git  status", "git status", "ls"].map(String::from).to_vec(),
+        usage: BTreeMap::from([
+            ("git  status".to_string(), record(2, 10, 20)),
+            ("git status".to_string(), record(1, 5, 15)),
+            ("ls".to_string(), record(1, 1, 1)),
+        ]),
+        ..Default::default()
+    };
+    assert_eq!(normalize_recent(&mut recent), 2);
+
+    assert_eq!(recent.commands, vec!["git status".to_string(), "ls".to_string()]);
+    assert_eq!(recent.usage.len(), 2);
+    assert_eq!(recent.usage.get("git status"), Some(&record(3, 5, 20)));
+    assert_eq!(normalize_recent(&mut recent), 0);
+}
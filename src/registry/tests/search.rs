@@ -23,6 +23,7 @@ fn filter_commands_matches_substring_in_order() {
             "ls -la".to_string(),
             "git commit -m test".to_string(),
         ],
+        ..Default::default()
     };
     let results = filter_commands(&registered, "git");
     assert_eq!(
@@ -36,6 +37,7 @@ fn filter_commands_returns_empty_when_no_match() {
     // 一致がない場合は空配列を返す。
     let registered = RegisteredCommands {
         commands: vec!["ls -la".to_string()],
+        ..Default::default()
     };
     let results = filter_commands(&registered, "git");
     assert!(results.is_empty());
@@ -0,0 +1,43 @@
+use super::super::storage::{FsStorage, MemoryStorage, Storage, StoreFile};
+use super::super::Registry;
+use crate::error::GclipError;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[test]
+fn registry_can_be_pointed_at_a_directory() {
+    // HOMEを書き換えずに、指定したディレクトリへ保存できることを確認する。
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!("gclip-test-{}-{nanos}", std::process::id()));
+    let registry = Registry::at(&dir);
+
+    let (registry_path, added) = registry.add_command("git status").expect("add should succeed");
+    assert_eq!(added, 1);
+    assert_eq!(registry_path, dir.join("registered.toml"));
+    assert!(fs::read_to_string(&registry_path)
+        .expect("registry file should exist")
+        .contains("git status"));
+    assert_eq!(
+        FsStorage::new(&dir).read(StoreFile::Recent).expect("read should succeed"),
+        None
+    );
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn corrupt_memory_file_is_reported_and_repaired() {
+    // 解析できない内容はCorruptになり、修復で退避されることを確認する。
+    let registry = Registry::new(
+        MemoryStorage::new().with_file(StoreFile::Registry, "commands = ["),
+    );
+    let err = registry.list_commands().expect_err("should be corrupt");
+    assert!(matches!(err, GclipError::Corrupt { .. }));
+
+    let actions = registry.repair().expect("repair should succeed");
+    assert_eq!(actions.len(), 1);
+    assert!(registry.list_commands().expect("list should succeed").is_empty());
+    assert!(registry.storage().read(StoreFile::Registry).expect("read").is_none());
+}
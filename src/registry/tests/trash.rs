@@ -0,0 +1,140 @@
+use super::super::recent::{load_recent, record_recent};
+use super::super::storage::MemoryStorage;
+use super::super::trash::{backfill_added, load_trash, move_to_trash, restore_from_trash};
+use super::super::{io, RegisteredCommands, UsageContext, UsageSource};
+use std::collections::BTreeMap;
+
+#[test]
+fn move_to_trash_removes_and_records_entries() {
+    // 登録から除かれ、キーと理由付きでゴミ箱に追記されることを確認する。
+    let storage = MemoryStorage::new();
+    let registered = RegisteredCommands {
+        commands: vec!["ls".to_string(), "git status".to_string()],
+        keys: BTreeMap::from([("gs".to_string(), "git status".to_string())]),
+        ..Default::default()
+    };
+    io::write_registry(&storage, &registered).expect("write should succeed");
+
+    let request = vec![
+        ("git status".to_string(), "unused".to_string()),
+        ("missing".to_string(), "unused".to_string()),
+    ];
+    let (_, moved) = move_to_trash(&storage, &request, 100).expect("move should succeed");
+    assert_eq!(moved, 1);
+
+    let remaining = io::load_registry(&storage).expect("load should succeed");
+    assert_eq!(remaining.commands, vec!["ls".to_string()]);
+    assert!(remaining.keys.is_empty());
+
+    let trash = load_trash(&storage).expect("trash should load");
+    assert_eq!(trash.entries.len(), 1);
+    assert_eq!(trash.entries[0].key.as_deref(), Some("gs"));
+    assert_eq!(trash.entries[0].removed_at, 100);
+}
+
+#[test]
+fn backfill_dates_only_undated_commands() {
+    // 登録日時がないコマンドだけに記録されることを確認する。
+    let storage = MemoryStorage::new();
+    let registered = RegisteredCommands {
+        commands: vec!["ls".to_string(), "pwd".to_string()],
+        added: BTreeMap::from([("ls".to_string(), 5)]),
+        ..Default::default()
+    };
+    io::write_registry(&storage, &registered).expect("write should succeed");
+
+    assert_eq!(backfill_added(&storage, 50).expect("backfill should succeed"), 1);
+    let dated = io::load_registry(&storage).expect("load should succeed");
+    assert_eq!(dated.added.get("ls"), Some(&5));
+    assert_eq!(dated.added.get("pwd"), Some(&50));
+}
+
+#[test]
+fn restore_brings_back_metadata() {
+    // 説明やタグ、作業ディレクトリ、手順がゴミ箱を経由しても失われないことを確認する。
+    let storage = MemoryStorage::new();
+    let workflow = "make && make test".to_string();
+    let registered = RegisteredCommands {
+        commands: vec![workflow.clone()],
+        keys: BTreeMap::from([("mt".to_string(), workflow.clone())]),
+        added: BTreeMap::from([(workflow.clone(), 7)]),
+        descriptions: BTreeMap::from([(workflow.clone(), "build and test".to_string())]),
+        tags: BTreeMap::from([(workflow.clone(), vec!["build".to_string()])]),
+        cwd: BTreeMap::from([(workflow.clone(), "/src/app".to_string())]),
+        steps: BTreeMap::from([(
+            workflow.clone(),
+            vec!["make".to_string(), "make test".to_string()],
+        )]),
+        ..Default::default()
+    };
+    io::write_registry(&storage, &registered).expect("write should succeed");
+
+    move_to_trash(&storage, &[(workflow.clone(), "unused".to_string())], 100)
+        .expect("move should succeed");
+    let trash = load_trash(&storage).expect("trash should load");
+    assert_eq!(trash.entries[0].description.as_deref(), Some("build and test"));
+    assert_eq!(trash.entries[0].steps.len(), 2);
+
+    let (_, restored) = restore_from_trash(&storage, &[0]).expect("restore should succeed");
+    assert_eq!(restored.count, 1);
+    assert!(restored.taken_keys.is_empty());
+    let result = io::load_registry(&storage).expect("load should succeed");
+    assert_eq!(result.commands, registered.commands);
+    assert_eq!(result.keys, registered.keys);
+    assert_eq!(result.added, registered.added);
+    assert_eq!(result.descriptions, registered.descriptions);
+    assert_eq!(result.tags, registered.tags);
+    assert_eq!(result.cwd, registered.cwd);
+    assert_eq!(result.steps, registered.steps);
+    assert!(load_trash(&storage).expect("trash should load").entries.is_empty());
+}
+
+#[test]
+fn restore_skips_key_taken_by_another_command() {
+    // 短縮キーが別のコマンドに使われていれば、コマンドだけを戻してキーを報告する。
+    let storage = MemoryStorage::new();
+    let registered = RegisteredCommands {
+        commands: vec!["git status".to_string(), "ls".to_string()],
+        keys: BTreeMap::from([("gs".to_string(), "git status".to_string())]),
+        ..Default::default()
+    };
+    io::write_registry(&storage, &registered).expect("write should succeed");
+    move_to_trash(&storage, &[("git status".to_string(), "unused".to_string())], 100)
+        .expect("move should succeed");
+
+    let mut retaken = io::load_registry(&storage).expect("load should succeed");
+    retaken.keys.insert("gs".to_string(), "ls".to_string());
+    io::write_registry(&storage, &retaken).expect("write should succeed");
+
+    let (_, restored) = restore_from_trash(&storage, &[0, 5]).expect("restore should succeed");
+    assert_eq!(restored.count, 1);
+    assert_eq!(restored.taken_keys, vec!["gs".to_string()]);
+    let result = io::load_registry(&storage).expect("load should succeed");
+    assert!(result.commands.contains(&"git status".to_string()));
+    assert_eq!(result.keys.get("gs").map(String::as_str), Some("ls"));
+}
+
+#[test]
+fn usage_follows_commands_into_and_out_of_trash() {
+    // ゴミ箱へ移すと使用回数の記録も移り、戻すと直近使用コマンドのファイルへ戻る。
+    let storage = MemoryStorage::new();
+    let registered = RegisteredCommands {
+        commands: vec!["ls".to_string(), "pwd".to_string()],
+        ..Default::default()
+    };
+    io::write_registry(&storage, &registered).expect("write should succeed");
+    record_recent(&storage, "ls", UsageSource::Search, &UsageContext::default(), 10).expect("record should succeed");
+    record_recent(&storage, "pwd", UsageSource::Search, &UsageContext::default(), 20).expect("record should succeed");
+
+    move_to_trash(&storage, &[("ls".to_string(), "unused".to_string())], 100)
+        .expect("move should succeed");
+    let recent = load_recent(&storage).expect("recent should load");
+    assert_eq!(recent.commands, vec!["pwd".to_string()]);
+    assert!(!recent.usage.contains_key("ls"));
+    let trash = load_trash(&storage).expect("trash should load");
+    assert_eq!(trash.entries[0].usage.as_ref().map(|usage| usage.count), Some(1));
+
+    restore_from_trash(&storage, &[0]).expect("restore should succeed");
+    let restored = load_recent(&storage).expect("recent should load");
+    assert_eq!(restored.usage.get("ls").map(|usage| usage.last_used), Some(10));
+}
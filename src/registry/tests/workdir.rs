@@ -0,0 +1,58 @@
+use super::super::workdir::{display_dir, is_within, with_cd_prefix};
+use super::super::{MemoryStorage, Registry};
+use std::path::Path;
+
+#[test]
+fn prefix_uses_home_relative_path_when_safe() {
+    // ホーム配下で引用符が不要なら `~/...` のまま付ける。
+    let home = Some(Path::new("/home/user"));
+    assert_eq!(
+        with_cd_prefix("make deploy", Path::new("/home/user/src/infra"), home),
+        "cd ~/src/infra && make deploy"
+    );
+    assert_eq!(
+        with_cd_prefix("ls", Path::new("/srv/app-1.2"), home),
+        "cd /srv/app-1.2 && ls"
+    );
+}
+
+#[test]
+fn prefix_quotes_unusual_paths() {
+    // 空白や引用符を含むパスは絶対パスをシングルクォートで囲む。
+    let home = Some(Path::new("/home/user"));
+    assert_eq!(
+        with_cd_prefix("ls", Path::new("/home/user/My Projects"), home),
+        "cd '/home/user/My Projects' && ls"
+    );
+    assert_eq!(
+        with_cd_prefix("ls", Path::new("/tmp/it's"), None),
+        r"cd '/tmp/it'\''s' && ls"
+    );
+}
+
+#[test]
+fn inside_the_directory_or_below_counts_as_within() {
+    // 作業ディレクトリ自身とその配下では、`cd` は不要とみなす。
+    let dir = Path::new("/home/user/src/infra");
+    assert!(is_within(Path::new("/home/user/src/infra"), dir));
+    assert!(is_within(Path::new("/home/user/src/infra/modules"), dir));
+    assert!(!is_within(Path::new("/home/user/src/infra-old"), dir));
+    assert!(!is_within(Path::new("/home/user"), dir));
+    assert_eq!(display_dir(Path::new("/home/user"), Some(Path::new("/home/user"))), "~");
+}
+
+#[test]
+fn workdir_is_stored_and_removed_with_the_command() {
+    // 作業ディレクトリは登録済みコマンドにだけ設定でき、削除すると一緒に消える。
+    let registry = Registry::new(MemoryStorage::new());
+    registry.add_command("make deploy").expect("add");
+    let commands = vec!["make deploy".to_string()];
+    registry.set_workdir(&commands, "/srv/infra").expect("set workdir");
+    assert!(registry.set_workdir(&["ls".to_string()], "/srv").is_err());
+
+    let details = registry.command_details().expect("details");
+    assert_eq!(details["make deploy"].cwd.as_deref(), Some("/srv/infra"));
+
+    registry.remove_command("make deploy").expect("remove");
+    assert!(registry.command_details().expect("details").is_empty());
+}
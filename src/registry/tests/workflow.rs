@@ -0,0 +1,108 @@
+use super::super::recent;
+use super::super::workflow::{self, WorkflowStep};
+use super::super::{CommandDetails, MemoryStorage, Registry};
+use crate::error::GclipError;
+use std::path::PathBuf;
+
+fn steps(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+/// 付加情報を付けずにワークフローを登録する。
+fn add_workflow(
+    registry: &Registry,
+    items: &[&str],
+    key: Option<&str>,
+) -> Result<(PathBuf, usize, String), GclipError> {
+    registry.add_workflow(&steps(items), key, &CommandDetails::default(), false)
+}
+
+#[test]
+fn workflow_is_registered_as_joined_command() {
+    // 手順は `&&` でつないだ1件として登録され、手順そのものも残る。
+    let registry = Registry::new(MemoryStorage::new());
+    let (_, added, command) = add_workflow(&registry, &[" cargo build ", "", "cargo test"], None)
+        .expect("add workflow");
+    assert_eq!(added, 1);
+    assert_eq!(command, "cargo build && cargo test");
+    assert_eq!(registry.list_commands().expect("list"), vec![command.clone()]);
+    assert_eq!(
+        registry.command_details().expect("details")[&command].steps,
+        steps(&["cargo build", "cargo test"])
+    );
+
+    registry.remove_command(&command).expect("remove");
+    assert!(registry.command_details().expect("details").is_empty());
+}
+
+#[test]
+fn workflow_needs_two_steps() {
+    // 手順が1つだけなら通常のコマンドと変わらないため拒否する。
+    let registry = Registry::new(MemoryStorage::new());
+    assert!(add_workflow(&registry, &["make", "  "], None).is_err());
+    assert!(registry.set_steps("make", &steps(&["make", "make install"])).is_err());
+}
+
+#[test]
+fn steps_advance_until_the_last_one() {
+    // 最初の手順の挿入後、呼ぶたびに次の手順が返り、最後まで進むと終わる。
+    let registry = Registry::new(MemoryStorage::new());
+    let storage = registry.storage();
+    let (_, _, command) = add_workflow(&registry, &["build", "test", "push"], None)
+        .expect("add workflow");
+    workflow::start(storage, &command, 100).expect("start");
+
+    let second = workflow::next_step(storage, 200).expect("next").expect("second step");
+    assert_eq!(
+        second,
+        WorkflowStep {
+            command: command.clone(),
+            step: "test".to_string(),
+            number: 2,
+            total: 3,
+        }
+    );
+    workflow::advance(storage, &second, 200).expect("advance");
+    let third = workflow::next_step(storage, 300).expect("next").expect("third step");
+    assert_eq!(third.step, "push");
+    workflow::advance(storage, &third, 300).expect("advance");
+    assert_eq!(workflow::next_step(storage, 400).expect("next"), None);
+}
+
+#[test]
+fn stale_or_removed_progress_is_ignored() {
+    // 時間が経った場合や、ワークフローが削除された場合は途中とみなさない。
+    let registry = Registry::new(MemoryStorage::new());
+    let storage = registry.storage();
+    let (_, _, command) = add_workflow(&registry, &["build", "test"], None).expect("add workflow");
+    workflow::start(storage, &command, 100).expect("start");
+    assert!(workflow::next_step(storage, 100 + 60 * 60).expect("next").is_some());
+    assert!(workflow::next_step(storage, 101 + 60 * 60).expect("next").is_none());
+
+    assert_eq!(recent::load_recent(storage).expect("recent").workflow, None);
+
+    workflow::start(storage, &command, 100).expect("start");
+    registry.remove_command(&command).expect("remove");
+    assert!(workflow::next_step(storage, 100).expect("next").is_none());
+}
+
+#[test]
+fn workflow_key_is_checked_before_registering() {
+    // 不正なキーや使用中のキーでは、ワークフロー自体も登録しない。
+    let registry = Registry::new(MemoryStorage::new());
+    registry
+        .add_command_with_key("git status", "gs")
+        .expect("add keyed command");
+
+    assert!(add_workflow(&registry, &["make", "make test"], Some("-bad")).is_err());
+    assert!(add_workflow(&registry, &["make", "make test"], Some("gs")).is_err());
+    assert_eq!(registry.list_commands().expect("list"), vec!["git status".to_string()]);
+
+    let (_, added, command) = add_workflow(&registry, &["make", "make test"], Some("mt"))
+        .expect("add workflow");
+    assert_eq!(added, 1);
+    assert!(registry
+        .keyed_commands()
+        .expect("keys")
+        .contains(&("mt".to_string(), command)));
+}
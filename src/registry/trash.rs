@@ -0,0 +1,223 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::conditions::Condition;
+use super::storage::{Storage, StoreFile};
+use super::usage::UsageRecord;
+use super::{io, recent, remove, RegisteredCommands};
+use crate::error::GclipError;
+
+/// ゴミ箱ファイルのスキーマ。
+///
+/// 削除したコマンドを `[[entries]]` の配列として追記し、
+/// `gclip restore` で登録ファイルへ戻せるようにする。
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(super) struct Trash {
+    #[serde(default)]
+    pub(super) entries: Vec<TrashEntry>,
+}
+
+/// ゴミ箱に移した1件分。
+///
+/// 戻したときに元どおりになるよう、短縮キーや説明などの付加情報もまとめて保存する。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub reason: String,
+    /// 移した日時（UNIX秒）。
+    pub removed_at: u64,
+    /// 登録日時（UNIX秒）。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub added: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<Condition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<String>,
+    /// 移したときの使用回数の記録。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<UsageRecord>,
+}
+
+/// ゴミ箱から戻した結果。
+///
+/// `taken_keys` は別のコマンドに割り当て済みだったため戻せなかった短縮キー。
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Restored {
+    pub count: usize,
+    pub taken_keys: Vec<String>,
+}
+
+/// 登録日時の記録がないコマンドに現在時刻を記録する。
+///
+/// 登録日時を記録する前から登録されていたコマンドを、
+/// ここから経過日数を数え始められるようにする。記録した件数を返す。
+pub(super) fn backfill_added(storage: &dyn Storage, now: u64) -> Result<usize, GclipError> {
+    let mut registered = io::load_registry(storage)?;
+
+    let mut dated = 0;
+    for command in &registered.commands {
+        if !registered.added.contains_key(command) {
+            registered.added.insert(command.clone(), now);
+            dated += 1;
+        }
+    }
+    if dated > 0 {
+        io::write_registry(storage, &registered)?;
+    }
+    Ok(dated)
+}
+
+/// 登録済みコマンドを理由付きでゴミ箱へ移す。
+///
+/// ゴミ箱を先に書き込み、途中で失敗してもコマンドが失われないようにする。
+/// 使用回数の記録は直近使用コマンドのファイルからゴミ箱のエントリへ移す。
+/// ゴミ箱ファイルのパスと移した件数を返す。
+pub(super) fn move_to_trash(
+    storage: &dyn Storage,
+    commands: &[(String, String)],
+    now: u64,
+) -> Result<(PathBuf, usize), GclipError> {
+    let mut registered = io::load_registry(storage)?;
+    let mut trash = load_trash(storage)?;
+    let usage = recent::load_recent(storage)?.usage;
+    let mut moved = Vec::new();
+    for (command, reason) in commands {
+        let mut entry = take_entry(&registered, command, reason, now);
+        if remove::remove_matching(&mut registered, command) == 0 {
+            continue;
+        }
+        entry.usage = usage.get(command).cloned();
+        trash.entries.push(entry);
+        moved.push(command.as_str());
+    }
+
+    if !moved.is_empty() {
+        io::save_file(storage, StoreFile::Trash, &trash)?;
+        io::write_registry(storage, &registered)?;
+        recent::forget_usage(storage, &moved)?;
+    }
+    Ok((storage.location(StoreFile::Trash), moved.len()))
+}
+
+/// ゴミ箱に移す前のコマンドから、付加情報を含むエントリを作る。
+fn take_entry(registered: &RegisteredCommands, command: &str, reason: &str, now: u64) -> TrashEntry {
+    TrashEntry {
+        command: command.to_string(),
+        key: registered
+            .keys
+            .iter()
+            .find(|(_, keyed)| *keyed == command)
+            .map(|(key, _)| key.clone()),
+        reason: reason.to_string(),
+        removed_at: now,
+        added: registered.added.get(command).copied(),
+        description: registered.descriptions.get(command).cloned(),
+        tags: registered.tags.get(command).cloned().unwrap_or_default(),
+        when: registered.when.get(command).cloned(),
+        cwd: registered.cwd.get(command).cloned(),
+        steps: registered.steps.get(command).cloned().unwrap_or_default(),
+        usage: None,
+    }
+}
+
+/// ゴミ箱の指定した位置（0始まり）のエントリを登録ファイルへ戻す。
+///
+/// - 登録済みのコマンドは、ない付加情報だけを補う
+/// - 短縮キーが別のコマンドに割り当て済みなら、キーは戻さない
+/// - 使用回数の記録は直近使用コマンドのファイルへ戻す
+///
+/// 登録ファイルを先に書き込み、途中で失敗してもコマンドが失われないようにする。
+pub(super) fn restore_from_trash(
+    storage: &dyn Storage,
+    positions: &[usize],
+) -> Result<(PathBuf, Restored), GclipError> {
+    let mut registered = io::load_registry(storage)?;
+    let mut trash = load_trash(storage)?;
+    let mut restored = Restored::default();
+    let mut usage = Vec::new();
+    for entry in positions.iter().filter_map(|position| trash.entries.get(*position)) {
+        if let Some(key) = restore_entry(&mut registered, entry) {
+            restored.taken_keys.push(key);
+        }
+        if let Some(record) = &entry.usage {
+            usage.push((entry.command.clone(), record.clone()));
+        }
+        restored.count += 1;
+    }
+
+    if restored.count > 0 {
+        io::write_registry(storage, &registered)?;
+        recent::restore_usage(storage, usage)?;
+        let mut position = 0;
+        trash.entries.retain(|_| {
+            let kept = !positions.contains(&position);
+            position += 1;
+            kept
+        });
+        io::save_file(storage, StoreFile::Trash, &trash)?;
+    }
+    Ok((storage.location(StoreFile::Registry), restored))
+}
+
+/// 1件分を登録ファイルへ戻す。戻せなかった短縮キーがあれば返す。
+fn restore_entry(registered: &mut RegisteredCommands, entry: &TrashEntry) -> Option<String> {
+    let command = &entry.command;
+    if !registered.commands.contains(command) {
+        registered.commands.push(command.clone());
+    }
+    if let Some(added) = entry.added {
+        registered.added.entry(command.clone()).or_insert(added);
+    }
+    if let Some(description) = &entry.description {
+        registered
+            .descriptions
+            .entry(command.clone())
+            .or_insert_with(|| description.clone());
+    }
+    if !entry.tags.is_empty() {
+        registered
+            .tags
+            .entry(command.clone())
+            .or_insert_with(|| entry.tags.clone());
+    }
+    if let Some(condition) = &entry.when {
+        registered
+            .when
+            .entry(command.clone())
+            .or_insert_with(|| condition.clone());
+    }
+    if let Some(dir) = &entry.cwd {
+        registered.cwd.entry(command.clone()).or_insert_with(|| dir.clone());
+    }
+    if !entry.steps.is_empty() {
+        registered
+            .steps
+            .entry(command.clone())
+            .or_insert_with(|| entry.steps.clone());
+    }
+
+    let key = entry.key.as_ref()?;
+    match registered.keys.get(key) {
+        Some(owner) if owner != command => Some(key.clone()),
+        Some(_) => None,
+        None => {
+            registered.keys.insert(key.clone(), command.clone());
+            None
+        }
+    }
+}
+
+/// ゴミ箱ファイルを読み込む。
+///
+/// 未作成または空の場合は空の状態を返す。
+pub(super) fn load_trash(storage: &dyn Storage) -> Result<Trash, GclipError> {
+    io::load_file(storage, StoreFile::Trash)
+}
@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::context::UsageContext;
+use crate::clock;
+
+/// 日別の使用回数を保持する日数の上限。
+///
+/// これより古い日の集計は記録時に切り捨てる。
+const DAILY_RETENTION_DAYS: i64 = 400;
+
+/// 1コマンドあたりに保持する、使用したディレクトリとリポジトリそれぞれの件数の上限。
+///
+/// 超えた分は回数の少ないものから切り捨てる。
+pub(super) const MAX_CONTEXT_ENTRIES: usize = 20;
+
+/// コマンドを挿入した経路。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageSource {
+    /// `gclip <QUERY>` の検索結果から選んだ。
+    Search,
+    /// `gclip` 単体の直近使用コマンドから選んだ。
+    Recent,
+    /// `gclip serve` 経由でエディタから記録された。
+    Editor,
+}
+
+/// 1コマンド分の使用回数と日時。
+///
+/// 日時はUNIX秒で保存する。
+/// `dirs` / `repos` は使用したディレクトリとgitリポジトリのルートごとの回数。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub count: usize,
+    pub first_used: u64,
+    pub last_used: u64,
+    #[serde(default)]
+    pub search: usize,
+    #[serde(default)]
+    pub recent: usize,
+    #[serde(default)]
+    pub editor: usize,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dirs: BTreeMap<String, usize>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub repos: BTreeMap<String, usize>,
+}
+
+/// 統計表示のために読み出す使用状況一式。
+///
+/// - `commands`: 登録済みコマンド（登録順）
+/// - `added`: 登録日時（記録がある場合のみ）
+/// - `usage`: コマンドごとの使用回数
+/// - `daily`: 日別（`YYYY-MM-DD`、UTC）の使用回数
+#[derive(Debug, Clone, Default)]
+pub struct UsageSnapshot {
+    pub commands: Vec<String>,
+    pub added: BTreeMap<String, u64>,
+    pub usage: BTreeMap<String, UsageRecord>,
+    pub daily: BTreeMap<String, usize>,
+}
+
+impl UsageRecord {
+    /// 1回分の使用を反映する。
+    pub(super) fn record(&mut self, source: UsageSource, now: u64) {
+        if self.count == 0 {
+            self.first_used = now;
+        }
+        self.count += 1;
+        self.last_used = now;
+        match source {
+            UsageSource::Search => self.search += 1,
+            UsageSource::Recent => self.recent += 1,
+            UsageSource::Editor => self.editor += 1,
+        }
+    }
+
+    /// 1回分の使用場所を反映する。
+    ///
+    /// 場所の件数が上限を超えたら、今回の場所を残して回数の少ないものから切り捨てる。
+    pub(super) fn record_context(&mut self, context: &UsageContext) {
+        if let Some(cwd) = &context.cwd {
+            *self.dirs.entry(cwd.clone()).or_default() += 1;
+            keep_top(&mut self.dirs, cwd, MAX_CONTEXT_ENTRIES);
+        }
+        if let Some(root) = &context.repo_root {
+            *self.repos.entry(root.clone()).or_default() += 1;
+            keep_top(&mut self.repos, root, MAX_CONTEXT_ENTRIES);
+        }
+    }
+
+    /// 同じコマンドとして扱うことになった別の記録を合算する。
+    pub(super) fn merge(&mut self, other: UsageRecord) {
+        if other.count == 0 {
+            return;
+        }
+        self.first_used = if self.count == 0 {
+            other.first_used
+        } else {
+            self.first_used.min(other.first_used)
+        };
+        self.last_used = self.last_used.max(other.last_used);
+        self.count += other.count;
+        self.search += other.search;
+        self.recent += other.recent;
+        self.editor += other.editor;
+        for (dir, count) in other.dirs {
+            *self.dirs.entry(dir).or_default() += count;
+        }
+        for (root, count) in other.repos {
+            *self.repos.entry(root).or_default() += count;
+        }
+    }
+}
+
+/// 回数の多い順に `limit` 件だけ残す。`current` は回数にかかわらず残す。
+///
+/// 回数が同じ場合は名前順で先のものを残す。
+pub(super) fn keep_top(counts: &mut BTreeMap<String, usize>, current: &str, limit: usize) {
+    if counts.len() <= limit {
+        return;
+    }
+    let kept = counts.remove(current);
+    let mut ranked: Vec<(String, usize)> = std::mem::take(counts).into_iter().collect();
+    ranked.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    ranked.truncate(limit.saturating_sub(usize::from(kept.is_some())));
+    counts.extend(ranked);
+    if let Some(count) = kept {
+        counts.insert(current.to_string(), count);
+    }
+}
+
+/// 日別の使用回数に1回分を加え、保持期間を過ぎた日を取り除く。
+pub(super) fn record_daily(daily: &mut BTreeMap<String, usize>, now: u64) {
+    let today = clock::days_from_secs(now);
+    *daily.entry(clock::format_days(today)).or_default() += 1;
+    daily.retain(|date, _| {
+        clock::parse_date(date).is_some_and(|days| today - days < DAILY_RETENTION_DAYS)
+    });
+}
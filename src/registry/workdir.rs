@@ -0,0 +1,81 @@
+use std::path::{Component, Path};
+
+use super::io;
+use super::storage::Storage;
+use crate::error::GclipError;
+use crate::export::single_quote;
+
+/// 登録済みコマンドに作業ディレクトリを設定する。
+///
+/// 既存の設定は上書きする。未登録のコマンドはエラーにする。
+pub(super) fn set_workdir(
+    storage: &dyn Storage,
+    commands: &[String],
+    dir: &str,
+) -> Result<(), GclipError> {
+    let mut registered = io::load_registry(storage)?;
+    if let Some(missing) = commands
+        .iter()
+        .find(|command| !registered.commands.contains(command))
+    {
+        return Err(GclipError::InvalidInput(format!(
+            "command is not registered: {missing}"
+        )));
+    }
+
+    let mut changed = false;
+    for command in commands {
+        if registered.cwd.get(command).map(String::as_str) != Some(dir) {
+            registered.cwd.insert(command.clone(), dir.to_string());
+            changed = true;
+        }
+    }
+    if changed {
+        io::write_registry(storage, &registered)?;
+    }
+    Ok(())
+}
+
+/// カレントディレクトリが作業ディレクトリ（またはその配下）かを返す。
+pub fn is_within(cwd: &Path, dir: &Path) -> bool {
+    cwd.starts_with(dir)
+}
+
+/// 作業ディレクトリへ移動してから実行する形にする。
+///
+/// ホームディレクトリ配下で引用符が不要なら `~/...` と表示し、
+/// それ以外はシングルクォートで囲む。
+pub fn with_cd_prefix(command: &str, dir: &Path, home: Option<&Path>) -> String {
+    format!("cd {} && {command}", shell_dir(dir, home))
+}
+
+/// 作業ディレクトリを一覧で表示する形にする。
+///
+/// ホームディレクトリ配下は `~/...` と短く表示する。
+pub fn display_dir(dir: &Path, home: Option<&Path>) -> String {
+    match home.and_then(|home_dir| dir.strip_prefix(home_dir).ok()) {
+        Some(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+        Some(rest) => format!("~/{}", rest.display()),
+        None => dir.display().to_string(),
+    }
+}
+
+/// `cd` の引数として使える形にする。
+fn shell_dir(dir: &Path, home: Option<&Path>) -> String {
+    let shown = display_dir(dir, home);
+    let is_plain = dir.components().all(|component| match component {
+        Component::Normal(name) => name.to_str().is_some_and(is_plain_word),
+        _ => true,
+    });
+    if is_plain {
+        shown
+    } else {
+        single_quote(&dir.display().to_string())
+    }
+}
+
+/// 引用符なしでシェルに渡せる文字だけでできているかを返す。
+fn is_plain_word(word: &str) -> bool {
+    word.chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.' | '/' | '+' | ',' | '@'))
+}
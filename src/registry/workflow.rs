@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+
+use std::path::PathBuf;
+
+use super::storage::{Storage, StoreFile};
+use super::details::CommandDetails;
+use super::{add, io, key, merge, recent};
+use crate::error::GclipError;
+
+/// 1段階ずつ挿入している途中のワークフローを破棄するまでの秒数。
+const PROGRESS_TIMEOUT_SECS: u64 = 60 * 60;
+
+/// 1段階ずつ挿入しているワークフローの進み具合。
+///
+/// 直近使用コマンドのファイルに `[workflow]` テーブルとして保存する。
+/// `next` は次に挿入する手順の位置（0始まり）、`at` は最後に挿入した時刻。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkflowProgress {
+    pub command: String,
+    pub next: usize,
+    pub at: u64,
+}
+
+/// 次に挿入するワークフローの手順。
+///
+/// `number` は1始まりの手順番号、`total` は手順の数。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkflowStep {
+    pub command: String,
+    pub step: String,
+    pub number: usize,
+    pub total: usize,
+}
+
+/// ワークフローの手順を `&&` でつないだ1行にする。
+///
+/// 登録済みコマンドとしてはこの形で保存し、検索や一覧にもこの形で現れる。
+pub fn join_steps(steps: &[String]) -> String {
+    steps.join(" && ")
+}
+
+/// 手順の入力を正規化する。
+///
+/// 前後の空白を除き、空の手順は取り除く。2つ未満ならエラーにする。
+pub(super) fn normalize_steps(steps: &[String]) -> Result<Vec<String>, GclipError> {
+    let normalized: Vec<String> = steps
+        .iter()
+        .map(|step| step.trim())
+        .filter(|step| !step.is_empty())
+        .map(str::to_string)
+        .collect();
+    if normalized.len() < 2 {
+        return Err(GclipError::InvalidInput(
+            "a workflow needs at least two steps".to_string(),
+        ));
+    }
+    Ok(normalized)
+}
+
+/// 手順をつないだコマンドを登録し、手順と短縮キーを設定する。
+///
+/// キーを先に検証し、登録と割り当て、説明などの付加情報の設定を1回の書き込みで行う。
+/// キーが不正または使用中なら、ワークフローも登録しない。
+pub(super) fn add_workflow(
+    storage: &dyn Storage,
+    steps: &[String],
+    key: Option<&str>,
+    extra: &CommandDetails,
+    replace_when: bool,
+) -> Result<(PathBuf, usize, String), GclipError> {
+    let normalized = normalize_steps(steps)?;
+    let valid_key = key.map(key::validate_key).transpose()?;
+    let command = join_steps(&normalized);
+
+    let mut registered = io::load_registry(storage)?;
+    let added = merge::merge_commands(&mut registered, std::slice::from_ref(&command));
+    if let Some(short_key) = valid_key.as_deref() {
+        key::assign_key(&mut registered, &command, short_key)?;
+    }
+    registered.steps.insert(command.clone(), normalized);
+    add::apply_extra(&mut registered, &command, extra, replace_when);
+    io::write_registry(storage, &registered)?;
+
+    Ok((storage.location(StoreFile::Registry), added, command))
+}
+
+/// 登録済みコマンドに手順を設定し、ワークフローにする。
+///
+/// 未登録のコマンドはエラーにする。
+pub(super) fn set_steps(
+    storage: &dyn Storage,
+    command: &str,
+    steps: &[String],
+) -> Result<(), GclipError> {
+    let normalized = normalize_steps(steps)?;
+    let mut registered = io::load_registry(storage)?;
+    if !registered.commands.iter().any(|item| item == command) {
+        return Err(GclipError::InvalidInput(format!(
+            "command is not registered: {command}"
+        )));
+    }
+
+    if registered.steps.get(command) != Some(&normalized) {
+        registered.steps.insert(command.to_string(), normalized);
+        io::write_registry(storage, &registered)?;
+    }
+    Ok(())
+}
+
+/// 途中のワークフローの次の手順を返す。
+///
+/// 途中のものがない、時間が経ちすぎた、またはワークフローが変更・削除された場合は `None`。
+/// 使えなくなった途中の状態はここで消す。
+pub(super) fn next_step(storage: &dyn Storage, now: u64) -> Result<Option<WorkflowStep>, GclipError> {
+    let Some(progress) = recent::load_recent(storage)?.workflow else {
+        return Ok(None);
+    };
+    let registered = io::load_registry(storage)?;
+    let step = registered
+        .steps
+        .get(&progress.command)
+        .filter(|_| now.saturating_sub(progress.at) <= PROGRESS_TIMEOUT_SECS)
+        .and_then(|steps| {
+            steps.get(progress.next).map(|step| WorkflowStep {
+                command: progress.command.clone(),
+                step: step.clone(),
+                number: progress.next + 1,
+                total: steps.len(),
+            })
+        });
+    if step.is_none() {
+        save_progress(storage, None)?;
+    }
+    Ok(step)
+}
+
+/// 1段階ずつの挿入を始め、次の手順を2番目にする。
+pub(super) fn start(storage: &dyn Storage, command: &str, now: u64) -> Result<(), GclipError> {
+    save_progress(
+        storage,
+        Some(WorkflowProgress {
+            command: command.to_string(),
+            next: 1,
+            at: now,
+        }),
+    )
+}
+
+/// 挿入した手順の次へ進める。最後の手順なら途中の状態を消す。
+pub(super) fn advance(storage: &dyn Storage, step: &WorkflowStep, now: u64) -> Result<(), GclipError> {
+    let progress = (step.number < step.total).then(|| WorkflowProgress {
+        command: step.command.clone(),
+        next: step.number,
+        at: now,
+    });
+    save_progress(storage, progress)
+}
+
+/// 途中のワークフローを破棄する。途中のものがなければ何もしない。
+pub(super) fn clear(storage: &dyn Storage) -> Result<(), GclipError> {
+    if recent::load_recent(storage)?.workflow.is_none() {
+        return Ok(());
+    }
+    save_progress(storage, None)
+}
+
+/// 途中の状態を直近使用コマンドのファイルへ保存する。
+fn save_progress(
+    storage: &dyn Storage,
+    progress: Option<WorkflowProgress>,
+) -> Result<(), GclipError> {
+    let mut recent = recent::load_recent(storage)?;
+    recent.workflow = progress;
+    recent::save_recent(storage, &recent)
+}
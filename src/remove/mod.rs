@@ -1,3 +1,5 @@
+use crate::error::GclipError;
+use crate::output::display_safe;
 use crate::registry::Registry;
 use crate::selection;
 use std::path::Path;
@@ -5,13 +7,16 @@ use std::path::Path;
 /// 登録済みコマンドを削除する機能。
 ///
 /// `gclip --rm "query"` で部分一致検索し、選択して削除する。
-pub fn run(query: &str) -> Result<(), String> {
-    let matches = Registry::search_commands(query)?;
+/// `--select` / `--first` が指定された場合はプロンプトを出さない。
+pub fn run(query: &str, select: Option<&str>) -> Result<(), GclipError> {
+    let matches = Registry::open()?.search_commands(query)?;
     ensure_matches(&matches)?;
-    print_matches(query, &matches);
+    if select.is_none() {
+        print_matches(query, &matches);
+    }
 
     let selection =
-        selection::prompt_single_selection(matches.len(), "Select command to remove")?;
+        selection::resolve_single_selection(select, matches.len(), "Select command to remove")?;
     handle_selection(&matches, selection)?;
     Ok(())
 }
@@ -19,9 +24,11 @@ pub fn run(query: &str) -> Result<(), String> {
 /// 検索結果が空でないことを確認する。
 ///
 /// 一致がない場合はエラーにして終了する。
-fn ensure_matches(matches: &[String]) -> Result<(), String> {
+fn ensure_matches(matches: &[String]) -> Result<(), GclipError> {
     if matches.is_empty() {
-        Err("no registered commands match the query".to_string())
+        Err(GclipError::NoMatches(
+            "no registered commands match the query".to_string(),
+        ))
     } else {
         Ok(())
     }
@@ -33,19 +40,18 @@ fn ensure_matches(matches: &[String]) -> Result<(), String> {
 fn print_matches(query: &str, matches: &[String]) {
     eprintln!("Matches for \"{query}\":");
     for (index, command) in matches.iter().enumerate() {
-        eprintln!("{:>2}. {}", index + 1, command);
+        eprintln!("{:>2}. {}", index + 1, display_safe(command));
     }
 }
 
 /// 選択結果に応じて削除処理を行う。
-fn handle_selection(matches: &[String], selection: Option<usize>) -> Result<(), String> {
+fn handle_selection(matches: &[String], selection: Option<usize>) -> Result<(), GclipError> {
     let Some(index) = selection else {
-        print_cancelled();
-        return Ok(());
+        return Err(GclipError::Cancelled);
     };
 
     let command = command_at_index(matches, index)?;
-    let (registry_path, removed) = Registry::remove_command(command)?;
+    let (registry_path, removed) = Registry::open()?.remove_command(command)?;
     print_result(command, removed, &registry_path);
     Ok(())
 }
@@ -53,25 +59,21 @@ fn handle_selection(matches: &[String], selection: Option<usize>) -> Result<(),
 /// 選択されたコマンドを取得する。
 ///
 /// 範囲外の場合はエラーにする。
-fn command_at_index(matches: &[String], index: usize) -> Result<&str, String> {
+fn command_at_index(matches: &[String], index: usize) -> Result<&str, GclipError> {
     matches
         .get(index - 1)
         .map(|command| command.as_str())
-        .ok_or_else(|| format!("out of range: {index}"))
+        .ok_or_else(|| GclipError::InvalidInput(format!("out of range: {index}")))
 }
 
 /// 削除結果を標準出力へ表示する。
 ///
 /// 削除件数と登録先を明示する。
 fn print_result(command: &str, removed: usize, registry_path: &Path) {
+    let shown = display_safe(command);
     if removed == 0 {
-        println!("Not found: \"{command}\"");
+        println!("Not found: \"{shown}\"");
     } else {
-        println!("Removed \"{command}\" from {}", registry_path.display());
+        println!("Removed \"{shown}\" from {}", registry_path.display());
     }
 }
-
-/// キャンセル時のメッセージを標準エラーに出力する。
-fn print_cancelled() {
-    eprintln!("Cancelled.");
-}
@@ -0,0 +1,55 @@
+use std::collections::BTreeMap;
+
+use crate::error::GclipError;
+use crate::guard::Guard;
+use crate::registry::{self, CommandDetails, Registry};
+
+/// 一覧の表示と挿入に使う、登録済みコマンドの付加情報。
+///
+/// 危険なコマンドの判定、説明や作業ディレクトリ、`@キー` の参照先をまとめて読み込む。
+pub(super) struct Catalog {
+    guard: Guard,
+    details: BTreeMap<String, CommandDetails>,
+    references: BTreeMap<String, String>,
+}
+
+impl Catalog {
+    /// 設定と登録ファイルから読み込む。
+    pub(super) fn load(registry: &Registry) -> Result<Self, GclipError> {
+        Ok(Self {
+            guard: Guard::load()?,
+            details: registry.command_details()?,
+            references: registry.reference_table()?,
+        })
+    }
+
+    /// コマンドの説明などを返す。
+    pub(super) fn details(&self, command: &str) -> Option<&CommandDetails> {
+        self.details.get(command)
+    }
+
+    /// コマンドに設定された作業ディレクトリを返す。
+    pub(super) fn bound_dir(&self, command: &str) -> Option<&str> {
+        self.details(command).and_then(|detail| detail.cwd.as_deref())
+    }
+
+    /// コマンドがワークフローなら手順を返す。そうでなければ空を返す。
+    pub(super) fn steps(&self, command: &str) -> &[String] {
+        self.details(command)
+            .map(|detail| detail.steps.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// `@キー` の参照を展開する。
+    pub(super) fn expand(&self, command: &str) -> Result<String, GclipError> {
+        registry::expand_references(command, &self.references)
+    }
+
+    /// 危険なコマンドなら理由を返す。
+    ///
+    /// 参照を展開した後のコマンドで判定する。展開できない場合は元のコマンドで判定する。
+    pub(super) fn risk(&self, command: &str) -> Option<String> {
+        let expanded = self.expand(command).unwrap_or_else(|_| command.to_string());
+        self.guard.classify(&expanded).map(|found| found.reason)
+    }
+}
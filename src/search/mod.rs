@@ -1,33 +1,95 @@
+mod catalog;
 mod widget;
+mod workflow;
 
-use crate::registry::Registry;
+use crate::config::{Config, WorkdirMode, WorkflowMode};
+use crate::error::GclipError;
+use crate::output::{self, display_safe, has_control_chars, OutputOptions};
+use crate::registry::{self, Registry, UsageContext, UsageSource, WhenContext};
 use crate::selection;
+use catalog::Catalog;
+use serde::Serialize;
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// `gclip` 単体で表示する直近使用コマンドの件数。
+const RECENT_DISPLAY_LIMIT: usize = 10;
+
+/// 機械処理向けに出力する検索結果の1件分。
+///
+/// 危険なコマンドと判定された場合は `risk` に理由が入る。
+/// 作業ディレクトリが設定されていれば `cwd` に、ワークフローなら `steps` に手順が入る。
+#[derive(Debug, Serialize)]
+struct MatchItem<'a> {
+    index: usize,
+    command: &'a str,
+    risk: Option<String>,
+    cwd: Option<&'a str>,
+    steps: &'a [String],
+}
 
 /// 登録済みコマンドから検索する機能。
 ///
 /// `gclip <QUERY>` で部分一致検索を行う。
-pub fn run(query: &str) -> Result<(), String> {
-    let matches = Registry::search_commands(query)?;
+/// カレントディレクトリやリポジトリで使ったコマンドを先に並べ、
+/// `when` の条件を満たさないコマンドは後ろに回す。
+/// 作業ディレクトリが設定されたコマンドは、一覧でそのディレクトリを添える。
+/// `@キー` の参照は挿入時に展開する。
+/// 機械処理向けの形式では、選択の指定がなければ一覧だけを出力する。
+pub fn run(query: &str, options: &OutputOptions) -> Result<(), GclipError> {
+    let registry = Registry::open()?;
+    let found = registry.search_commands(query)?;
+    let by_place = registry.rank_by_context(found, &UsageContext::current())?;
+    let matches = registry.rank_by_conditions(by_place, &WhenContext::current())?;
     ensure_matches(&matches)?;
-    print_matches(query, &matches);
+    let catalog = Catalog::load(&registry)?;
+    if options.list_only() {
+        return output::print_items(&options.format, &to_items(&matches, &catalog));
+    }
+    if options.select.is_none() {
+        print_matches(query, &matches, &catalog);
+    }
 
-    let selection =
-        selection::prompt_single_selection(matches.len(), "Select command to insert")?;
-    handle_selection(&matches, selection)?;
+    let selection = selection::resolve_single_selection(
+        options.select.as_deref(),
+        matches.len(),
+        "Select command to insert",
+    )?;
+    handle_selection(&matches, selection, options, &catalog, UsageSource::Search)?;
     Ok(())
 }
 
 /// 直近使用コマンドから選択して挿入する。
 ///
 /// `gclip` 単体で呼び出した場合の挙動。
-pub fn run_recent() -> Result<(), String> {
-    let matches = Registry::recent_commands(10)?;
+/// 直近使用コマンド全体を場所と条件に応じて並べ替えてから、先頭の10件を表示する。
+/// ワークフローを1段階ずつ挿入している途中なら、一覧を出さずに次の手順を挿入する。
+pub fn run_recent(options: &OutputOptions) -> Result<(), GclipError> {
+    let registry = Registry::open()?;
+    if is_interactive(options)
+        && let Some(step) = registry.next_workflow_step()?
+    {
+        return workflow::insert_next_step(&registry, &step, options);
+    }
+    let recent = registry.recent_commands(usize::MAX)?;
+    let by_place = registry.rank_by_context(recent, &UsageContext::current())?;
+    let mut matches = registry.rank_by_conditions(by_place, &WhenContext::current())?;
+    matches.truncate(RECENT_DISPLAY_LIMIT);
     ensure_recent_matches(&matches)?;
-    print_recent_matches(&matches);
+    let catalog = Catalog::load(&registry)?;
+    if options.list_only() {
+        return output::print_items(&options.format, &to_items(&matches, &catalog));
+    }
+    if options.select.is_none() {
+        print_recent_matches(&matches, &catalog);
+    }
 
-    let selection =
-        selection::prompt_single_selection(matches.len(), "Select recent command to insert")?;
-    handle_selection(&matches, selection)?;
+    let selection = selection::resolve_single_selection(
+        options.select.as_deref(),
+        matches.len(),
+        "Select recent command to insert",
+    )?;
+    handle_selection(&matches, selection, options, &catalog, UsageSource::Recent)?;
     Ok(())
 }
 
@@ -38,29 +100,38 @@ pub fn print_zsh_widget() {
     widget::print_zsh_widget();
 }
 
+/// 途中のワークフローを表示し、`cancel` なら破棄する。
+///
+/// `gclip workflow [--cancel]` で実行する。
+pub fn run_workflow(cancel: bool) -> Result<(), GclipError> {
+    workflow::run(&Registry::open()?, cancel)
+}
+
 /// セットアップ用のスクリプトを標準出力へ出力する。
 ///
 /// `.zshrc` から評価されることを想定している。
-pub fn print_init_script() {
-    widget::print_init_script();
+/// `record` なら実行したコマンドを記録するフックも出力する。
+pub fn print_init_script(record: bool) {
+    widget::print_init_script(record);
 }
 
-
 /// 検索結果が空でないことを確認する。
 ///
 /// 一致がない場合はエラーにして終了する。
-fn ensure_matches(matches: &[String]) -> Result<(), String> {
+fn ensure_matches(matches: &[String]) -> Result<(), GclipError> {
     if matches.is_empty() {
-        Err("no registered commands match the query".to_string())
+        Err(GclipError::NoMatches(
+            "no registered commands match the query".to_string(),
+        ))
     } else {
         Ok(())
     }
 }
 
 /// 直近使用コマンドが空でないことを確認する。
-fn ensure_recent_matches(matches: &[String]) -> Result<(), String> {
+fn ensure_recent_matches(matches: &[String]) -> Result<(), GclipError> {
     if matches.is_empty() {
-        Err("no recent commands found".to_string())
+        Err(GclipError::NoMatches("no recent commands found".to_string()))
     } else {
         Ok(())
     }
@@ -69,18 +140,45 @@ fn ensure_recent_matches(matches: &[String]) -> Result<(), String> {
 /// 検索結果を標準エラーへ表示する。
 ///
 /// 標準出力は挿入するコマンドのために空けておく。
-fn print_matches(query: &str, matches: &[String]) {
+fn print_matches(query: &str, matches: &[String], catalog: &Catalog) {
     eprintln!("Matches for \"{query}\":");
-    for (index, command) in matches.iter().enumerate() {
-        eprintln!("{:>2}. {}", index + 1, command);
-    }
+    print_numbered(matches, catalog);
 }
 
 /// 直近使用コマンドの一覧を標準エラーへ表示する。
-fn print_recent_matches(matches: &[String]) {
+fn print_recent_matches(matches: &[String], catalog: &Catalog) {
     eprintln!("Recent commands:");
+    print_numbered(matches, catalog);
+}
+
+/// 番号付きの一覧を標準エラーへ表示する。
+///
+/// 作業ディレクトリが設定されていれば `(in ~/dir)` を、ワークフローなら手順の数を添える。
+/// 危険なコマンド（`@キー` の参照先を含む）には末尾に `(!)` と理由を付ける。
+/// 制御文字は見える形に置き換えて表示する。
+fn print_numbered(matches: &[String], catalog: &Catalog) {
+    let home = home_dir();
     for (index, command) in matches.iter().enumerate() {
-        eprintln!("{:>2}. {}", index + 1, command);
+        let shown = display_safe(command);
+        let workdir = catalog
+            .bound_dir(command)
+            .map(|dir| {
+                let dir_shown = registry::display_dir(Path::new(dir), home.as_deref());
+                format!("  (in {})", display_safe(&dir_shown))
+            })
+            .unwrap_or_default();
+        let steps = catalog.steps(command);
+        let kind = if steps.is_empty() {
+            String::new()
+        } else {
+            format!("  (workflow, {} steps)", steps.len())
+        };
+        match catalog.risk(command) {
+            Some(reason) => {
+                eprintln!("{:>2}. {shown}{kind}{workdir}  (!) {reason}", index + 1);
+            }
+            None => eprintln!("{:>2}. {shown}{kind}{workdir}", index + 1),
+        }
     }
 }
 
@@ -88,26 +186,187 @@ fn print_recent_matches(matches: &[String]) {
 ///
 /// 空入力はキャンセル扱いとして `None` を返す。
 /// 選択結果に応じて、出力または実行を行う。
-fn handle_selection(matches: &[String], selection: Option<usize>) -> Result<(), String> {
+/// `@キー` の参照を展開し、展開後のコマンドで危険かどうかを判定する。
+/// 危険なコマンドや制御文字を含むコマンドは挿入前に追加の確認を求める。
+/// ワークフローは `[workflow] mode` に従い、つないだ1行、最初の手順、チェックリストのいずれかにする。
+/// ただし1段階ずつの挿入は対話的な呼び出しに限り、それ以外はつないだ1行にする。
+/// 作業ディレクトリの外で挿入する場合は、設定に従って `cd <dir> && ` を付ける。
+/// 使用履歴には元のコマンドを記録する。
+fn handle_selection(
+    matches: &[String],
+    selection: Option<usize>,
+    options: &OutputOptions,
+    catalog: &Catalog,
+    source: UsageSource,
+) -> Result<(), GclipError> {
     let Some(index) = selection else {
-        print_cancelled();
-        return Ok(());
+        return Err(GclipError::Cancelled);
     };
 
     let command = command_at_index(matches, index)?;
-    Registry::record_recent(command)?;
-    print_selected_command(command);
+    let config = Config::load()?;
+    let workdir = catalog.bound_dir(command);
+    let steps = catalog.steps(command);
+    let mode = match config.workflow.mode {
+        _ if steps.is_empty() => WorkflowMode::Joined,
+        WorkflowMode::Steps if !is_interactive(options) => WorkflowMode::Joined,
+        configured => configured,
+    };
+    let body = match (mode, steps.first()) {
+        (WorkflowMode::Steps, Some(first)) => catalog.expand(first)?,
+        _ => catalog.expand(command)?,
+    };
+    let risk = catalog.risk(&body);
+    let shows_checklist = mode == WorkflowMode::Checklist && options.format.is_text();
+    if !shows_checklist {
+        confirm_insertion(&body, risk.as_deref(), options)?;
+    }
+
+    let registry = Registry::open()?;
+    registry.record_recent_in(command, source, &UsageContext::current())?;
+    if mode == WorkflowMode::Steps {
+        registry.start_workflow(command)?;
+    } else {
+        registry.clear_workflow()?;
+    }
+
+    if shows_checklist {
+        let expanded: Vec<String> = steps
+            .iter()
+            .map(|step| catalog.expand(step))
+            .collect::<Result<_, _>>()?;
+        workflow::print_checklist(catalog.details(command), &expanded);
+        return Ok(());
+    }
+    let inserted = match workdir {
+        Some(dir) => with_workdir(&body, Path::new(dir), config.workdir.mode, options.assume_yes)?,
+        None => body,
+    };
+    if options.format.is_text() {
+        print_selected_command(&inserted);
+        return Ok(());
+    }
+    let item = MatchItem {
+        index,
+        command: &inserted,
+        risk,
+        cwd: workdir,
+        steps,
+    };
+    output::print_item(&options.format, &item)
+}
+
+/// 危険なコマンドや制御文字を含むコマンドを挿入してよいか確認する。
+///
+/// `--yes` の場合は確認しない。同意が得られなければキャンセルにする。
+fn confirm_insertion(
+    command: &str,
+    risk: Option<&str>,
+    options: &OutputOptions,
+) -> Result<(), GclipError> {
+    if options.assume_yes {
+        return Ok(());
+    }
+    if let Some(reason) = risk
+        && !confirm_dangerous(command, reason)?
+    {
+        return Err(GclipError::Cancelled);
+    }
+    if has_control_chars(command) && !confirm_control_chars(command)? {
+        return Err(GclipError::Cancelled);
+    }
     Ok(())
 }
 
+/// 作業ディレクトリの外にいる場合、`cd <dir> && ` を付けたコマンドを返す。
+///
+/// `[workdir] mode` が `ask` なら付けるかを確認し、`ignore` ならそのまま返す。
+/// `--yes` の場合は確認せずに付ける。
+fn with_workdir(
+    command: &str,
+    dir: &Path,
+    mode: WorkdirMode,
+    assume_yes: bool,
+) -> Result<String, GclipError> {
+    let inside = env::current_dir().is_ok_and(|cwd| registry::is_within(&cwd, dir));
+    if inside {
+        return Ok(command.to_string());
+    }
+
+    let home = home_dir();
+    let prefixed = registry::with_cd_prefix(command, dir, home.as_deref());
+    match mode {
+        WorkdirMode::Prefix => Ok(prefixed),
+        WorkdirMode::Ignore => Ok(command.to_string()),
+        WorkdirMode::Ask if assume_yes => Ok(prefixed),
+        WorkdirMode::Ask => {
+            let dir_shown = registry::display_dir(dir, home.as_deref());
+            eprintln!("This command runs in {}.", display_safe(&dir_shown));
+            if selection::confirm("Prefix it with cd?")? {
+                Ok(prefixed)
+            } else {
+                Ok(command.to_string())
+            }
+        }
+    }
+}
+
+/// 選択結果をそのまま挿入する対話的な呼び出しかを返す。
+///
+/// 機械処理向けの形式や `--select` の呼び出しでは、ワークフローを1段階ずつ挿入しない。
+fn is_interactive(options: &OutputOptions) -> bool {
+    options.format.is_text() && options.select.is_none()
+}
+
+/// 表示用のホームディレクトリを返す。
+fn home_dir() -> Option<PathBuf> {
+    env::var_os("HOME").map(PathBuf::from)
+}
+
+/// 危険なコマンドを挿入してよいか確認する。
+///
+/// 挿入されるコマンドをそのまま表示し、明示的な同意だけを受け付ける。
+fn confirm_dangerous(command: &str, reason: &str) -> Result<bool, GclipError> {
+    eprintln!("Warning: this command looks dangerous ({reason}):");
+    eprintln!("  {}", display_safe(command));
+    selection::confirm("Insert it anyway?")
+}
+
+/// 制御文字を含むコマンドを挿入してよいか確認する。
+///
+/// 表示は見える形に置き換えたものだが、挿入されるのは元のバイト列になる。
+fn confirm_control_chars(command: &str) -> Result<bool, GclipError> {
+    eprintln!("Warning: this command contains control characters (shown as ^X or <U+XXXX>):");
+    eprintln!("  {}", display_safe(command));
+    eprintln!("The exact bytes will be inserted.");
+    selection::confirm("Insert it anyway?")
+}
+
+/// 検索結果を機械処理向けの出力項目に変換する。
+///
+/// 番号は選択プロンプトと同じ1始まりにする。
+fn to_items<'a>(matches: &'a [String], catalog: &'a Catalog) -> Vec<MatchItem<'a>> {
+    matches
+        .iter()
+        .enumerate()
+        .map(|(index, command)| MatchItem {
+            index: index + 1,
+            command,
+            risk: catalog.risk(command),
+            cwd: catalog.bound_dir(command),
+            steps: catalog.steps(command),
+        })
+        .collect()
+}
+
 /// 選択されたコマンドを取得する。
 ///
 /// 範囲外の場合はエラーにする。
-fn command_at_index(matches: &[String], index: usize) -> Result<&str, String> {
+fn command_at_index(matches: &[String], index: usize) -> Result<&str, GclipError> {
     matches
         .get(index - 1)
         .map(|command| command.as_str())
-        .ok_or_else(|| format!("out of range: {index}"))
+        .ok_or_else(|| GclipError::InvalidInput(format!("out of range: {index}")))
 }
 
 /// 選択されたコマンドを標準出力へ出力する。
@@ -117,7 +376,5 @@ fn print_selected_command(command: &str) {
     println!("{command}");
 }
 
-/// キャンセル時のメッセージを標準エラーに出力する。
-fn print_cancelled() {
-    eprintln!("Cancelled.");
-}
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,34 @@
+use super::super::workflow::format_checklist;
+use crate::registry::CommandDetails;
+use std::path::Path;
+
+fn steps(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+#[test]
+fn checklist_lists_numbered_steps_as_comments() {
+    // 説明がなければ見出しは手順の数になり、すべての行がコメントになる。
+    assert_eq!(
+        format_checklist(None, &steps(&["cargo build", "cargo test"]), None),
+        "# Workflow: 2 steps\n# [ ] 1. cargo build\n# [ ] 2. cargo test\n"
+    );
+}
+
+#[test]
+fn checklist_title_uses_description_and_directory() {
+    // 説明と作業ディレクトリがあれば見出しに入れ、改行を含む手順も1行に収める。
+    let details = CommandDetails {
+        description: Some("Release".to_string()),
+        cwd: Some("/home/user/src/app".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        format_checklist(
+            Some(&details),
+            &steps(&["make", "echo a\necho b"]),
+            Some(Path::new("/home/user"))
+        ),
+        "# Workflow: Release (in ~/src/app)\n# [ ] 1. make\n# [ ] 2. echo a^Jecho b\n"
+    );
+}
@@ -1,4 +1,4 @@
-/// zsh用の挿入ウィジェットを標準出力へ出力する。
+/// zsh用の挿入ウィジェットと保存ウィジェットを標準出力へ出力する。
 ///
 /// 出力されたスクリプトを `.zshrc` で読み込むことで、
 /// Ctrl+gと`gclip`の両方で挿入できるようにする。
@@ -10,8 +10,12 @@ pub(super) fn print_zsh_widget() {
 /// セットアップ用のスクリプトを標準出力へ出力する。
 ///
 /// `.zshrc` に評価させることで、ウィジェットとキー割り当てを有効化する。
-pub(super) fn print_init_script() {
-    let script = build_zsh_script(true);
+/// `record` なら `preexec`/`precmd` フックも追加する。
+pub(super) fn print_init_script(record: bool) {
+    let mut script = build_zsh_script(true);
+    if record {
+        script.push_str(ZSH_RECORD_HOOKS);
+    }
     print!("{script}");
 }
 
@@ -22,6 +26,7 @@ fn build_zsh_script(include_bindkey: bool) -> String {
     let mut script = String::from(ZSH_SCRIPT_BASE);
     if include_bindkey {
         script.push_str("bindkey '^g' gclip_insert\n");
+        script.push_str("bindkey '^x^g' gclip_save\n");
     }
     script
 }
@@ -31,6 +36,18 @@ const ZSH_SCRIPT_BASE: &str = r#"# gclip zsh integration
 _gclip_use_alt_screen=0
 _gclip_use_tput=0
 
+# gclip本体を呼び出す。
+# zshはHISTFILEを環境変数として公開しないため、suggestなどが同じ履歴を読めるよう渡す。
+_gclip_command() {
+  command env HISTFILE="${HISTFILE-}" gclip "$@"
+}
+
+# エイリアス、関数、組み込みコマンド、予約語の名前を1行1件で出力する。
+# prune などがPATHにないだけのコマンドを見つからないものと誤判定しないよう、本体へ渡す。
+_gclip_shell_names() {
+  print -rl -- ${(k)aliases} ${(k)functions} ${(k)builtins} ${(k)reswords}
+}
+
 # 画面切替の開始処理を共通化する。
 _gclip_begin_ui() {
   _gclip_use_alt_screen=0
@@ -57,17 +74,21 @@ _gclip_end_ui() {
 }
 
 # gclip本体を呼び出し、選択されたコマンドをREPLYへ格納する。
+# 失敗時はgclip本体の終了コードをそのまま返す。
 _gclip_pick_command() {
   local query="$1"
   local cmd
+  local code
 
   _gclip_begin_ui
   if [[ -z "$query" ]]; then
-    cmd="$(command gclip)" || { _gclip_end_ui; return 1; }
+    cmd="$(_gclip_command)"
   else
-    cmd="$(command gclip "$query")" || { _gclip_end_ui; return 1; }
+    cmd="$(_gclip_command -- "$query")"
   fi
+  code=$?
   _gclip_end_ui
+  (( code == 0 )) || return $code
 
   if [[ -z "$cmd" ]]; then
     return 1
@@ -76,6 +97,34 @@ _gclip_pick_command() {
   return 0
 }
 
+# ワークフローのチェックリストのように、すべての行がコメントの出力は挿入せずに表示する。
+_gclip_is_note() {
+  local line
+  for line in "${(@f)1}"; do
+    [[ "$line" == '#'* ]] || return 1
+  done
+  return 0
+}
+
+# 選択結果をコマンドラインの編集バッファへ積む。コメントだけなら表示にとどめる。
+_gclip_push() {
+  if _gclip_is_note "$1"; then
+    print -r -- "$1"
+  else
+    print -z -- "$1"
+  fi
+}
+
+# 終了コードに応じた短いメッセージをプロンプトの下に表示する。
+# キャンセル(4)は利用者の操作なので何も表示しない。
+_gclip_report_failure() {
+  case "$1" in
+    3) zle -M "gclip: no matching commands" ;;
+    5) zle -M "gclip: a data file is corrupt (run: gclip doctor --fix)" ;;
+    6) zle -M "gclip: failed to read or write a data file" ;;
+  esac
+}
+
 # Ctrl+gから呼び出す挿入ウィジェット。
 gclip_insert() {
   local original_lbuffer="$LBUFFER"
@@ -89,9 +138,18 @@ gclip_insert() {
   if _gclip_pick_command "$query"; then
     cmd="$REPLY"
   else
+    local code=$?
     LBUFFER="$original_lbuffer"
     RBUFFER="$original_rbuffer"
     zle -R
+    _gclip_report_failure $code
+    return 0
+  fi
+
+  if _gclip_is_note "$cmd"; then
+    LBUFFER="$original_lbuffer"
+    RBUFFER="$original_rbuffer"
+    zle -M "$cmd"
     return 0
   fi
 
@@ -106,18 +164,42 @@ gclip_insert() {
 }
 zle -N gclip_insert
 
+# 入力中のコマンドラインをそのまま登録するウィジェット（^x^g）。
+# `zstyle ':gclip:save' ask yes` で説明とタグを/dev/ttyから入力できる。
+gclip_save() {
+  local buffer="$BUFFER"
+  local -a ask
+  local code
+
+  if [[ -z "${buffer//[[:space:]]/}" ]]; then
+    zle -M "gclip: nothing to save"
+    return 0
+  fi
+
+  zstyle -t ':gclip:save' ask && ask=(--ask)
+  zle -I
+  _gclip_command add "${ask[@]}" -- "$buffer" </dev/tty >/dev/tty 2>&1
+  code=$?
+  zle -R
+  if (( code != 0 )); then
+    _gclip_report_failure $code
+  fi
+  return 0
+}
+zle -N gclip_save
+
 # gclipコマンドとして呼び出した場合も挿入できるようにする。
 gclip() {
   local cmd
 
   if [[ ! -o interactive ]]; then
-    command gclip "$@"
+    _gclip_command "$@"
     return
   fi
 
   if (( $# == 0 )); then
     if _gclip_pick_command ""; then
-      print -z -- "$REPLY"
+      _gclip_push "$REPLY"
     fi
     return
   fi
@@ -125,18 +207,73 @@ gclip() {
   if [[ "$1" == "--" ]]; then
     shift
     if _gclip_pick_command "$*"; then
-      print -z -- "$REPLY"
+      _gclip_push "$REPLY"
     fi
     return
   fi
 
+  # 名前の衝突を調べるサブコマンドには、シェルのエイリアスや関数の一覧を渡す。
+  case "$1" in
+    prune|--prune|export-aliases|--export-aliases)
+      GCLIP_SHELL_NAMES="$(_gclip_shell_names)" _gclip_command "$@"
+      return
+      ;;
+  esac
+
   if [[ "$1" == -* ]]; then
-    command gclip "$@"
+    _gclip_command "$@"
     return
   fi
 
+  # サブコマンドはそのまま本体へ渡す。
+  case "$1" in
+    add|ls|list|rm|remove|suggest|doctor|stats|restore|workflow|init|widget|completions|serve|record|help)
+      _gclip_command "$@"
+      return
+      ;;
+  esac
+
   if _gclip_pick_command "$*"; then
-    print -z -- "$REPLY"
+    _gclip_push "$REPLY"
   fi
 }
 "#;
+
+const ZSH_RECORD_HOOKS: &str = r#"
+# 実行したコマンドを終了コードと所要時間つきでgclipの実行履歴に記録する。
+# `setopt HIST_IGNORE_SPACE` の場合は先頭が空白のコマンドを記録しない。
+zmodload zsh/datetime 2>/dev/null
+typeset -g _gclip_record_command=""
+typeset -g _gclip_record_cwd=""
+typeset -g _gclip_record_started=""
+
+_gclip_record_preexec() {
+  if [[ -o hist_ignore_space && "$1" == [[:space:]]* ]]; then
+    _gclip_record_command=""
+    return
+  fi
+  _gclip_record_command="$1"
+  _gclip_record_cwd="$PWD"
+  _gclip_record_started="$EPOCHREALTIME"
+}
+
+_gclip_record_precmd() {
+  local code=$?
+  local -i duration=0
+
+  [[ -n "$_gclip_record_command" ]] || return
+  if [[ -n "$_gclip_record_started" && -n "$EPOCHREALTIME" ]]; then
+    # 整数の変数へ代入してミリ秒未満を切り捨てる。
+    (( duration = (EPOCHREALTIME - _gclip_record_started) * 1000 ))
+  fi
+  # プロンプトを待たせないよう、バックグラウンドで記録する。
+  command gclip record --exit "$code" --duration "$duration" --cwd "$_gclip_record_cwd" \
+    -- "$_gclip_record_command" >/dev/null 2>&1 &!
+  _gclip_record_command=""
+}
+
+autoload -Uz add-zsh-hook
+add-zsh-hook preexec _gclip_record_preexec
+# 他のフックが終了コードを上書きする前に読むため、先頭に追加する。
+precmd_functions=(_gclip_record_precmd "${precmd_functions[@]:#_gclip_record_precmd}")
+"#;
@@ -0,0 +1,94 @@
+use std::path::Path;
+
+use super::catalog::Catalog;
+use super::{confirm_insertion, home_dir, print_selected_command};
+use crate::error::GclipError;
+use crate::output::{display_safe, OutputOptions};
+use crate::registry::{self, CommandDetails, Registry, WorkflowStep};
+
+/// 途中のワークフローの次の手順を挿入し、その次へ進める。
+///
+/// `@キー` の参照を展開し、手順ごとに危険なコマンドの確認を行う。
+/// 確認で断られた場合や展開できない場合は、同じ手順を繰り返し出さないよう途中の状態を消す。
+/// 進み具合は標準エラーに表示する。
+pub(super) fn insert_next_step(
+    registry: &Registry,
+    step: &WorkflowStep,
+    options: &OutputOptions,
+) -> Result<(), GclipError> {
+    let catalog = Catalog::load(registry)?;
+    let prepared = catalog.expand(&step.step).and_then(|inserted| {
+        confirm_insertion(&inserted, catalog.risk(&inserted).as_deref(), options)?;
+        Ok(inserted)
+    });
+    let inserted = match prepared {
+        Ok(inserted) => inserted,
+        Err(err) => {
+            registry.clear_workflow()?;
+            eprintln!("Stopped the workflow at step {}/{}.", step.number, step.total);
+            return Err(err);
+        }
+    };
+
+    registry.advance_workflow(step)?;
+    eprintln!(
+        "Workflow step {}/{}: {}  (gclip workflow --cancel to stop)",
+        step.number,
+        step.total,
+        display_safe(&step.step)
+    );
+    print_selected_command(&inserted);
+    Ok(())
+}
+
+/// 途中のワークフローを表示し、`cancel` なら破棄する。
+pub(super) fn run(registry: &Registry, cancel: bool) -> Result<(), GclipError> {
+    let Some(step) = registry.next_workflow_step()? else {
+        println!("No workflow in progress.");
+        return Ok(());
+    };
+    if cancel {
+        registry.clear_workflow()?;
+        println!("Cancelled the workflow: {}", display_safe(&step.command));
+        return Ok(());
+    }
+    println!("Workflow in progress: {}", display_safe(&step.command));
+    println!(
+        "Next step {}/{}: {}",
+        step.number,
+        step.total,
+        display_safe(&step.step)
+    );
+    Ok(())
+}
+
+/// ワークフローの手順をチェックリストとして標準出力へ出力する。
+///
+/// 各行をシェルのコメントにしておき、zshのウィジェットは挿入せずに表示する。
+pub(super) fn print_checklist(details: Option<&CommandDetails>, steps: &[String]) {
+    print!("{}", format_checklist(details, steps, home_dir().as_deref()));
+}
+
+/// チェックリストの文字列を作る。
+///
+/// 見出しには説明（なければ手順の数）と作業ディレクトリを入れる。
+pub(super) fn format_checklist(
+    details: Option<&CommandDetails>,
+    steps: &[String],
+    home: Option<&Path>,
+) -> String {
+    let title = details
+        .and_then(|detail| detail.description.as_deref())
+        .map(|description| display_safe(description).into_owned())
+        .unwrap_or_else(|| format!("{} steps", steps.len()));
+    let mut checklist = format!("# Workflow: {title}");
+    if let Some(dir) = details.and_then(|detail| detail.cwd.as_deref()) {
+        let shown = registry::display_dir(Path::new(dir), home);
+        checklist.push_str(&format!(" (in {})", display_safe(&shown)));
+    }
+    checklist.push('\n');
+    for (index, step) in steps.iter().enumerate() {
+        checklist.push_str(&format!("# [ ] {}. {}\n", index + 1, display_safe(step)));
+    }
+    checklist
+}
@@ -0,0 +1,163 @@
+mod review;
+mod rules;
+
+use std::ops::Range;
+
+pub use review::{review_for_registration, review_without_prompt};
+
+/// エントロピー判定で秘密情報とみなす下限（1文字あたりのビット数）。
+///
+/// 16進数のハッシュ（最大4.0）を誤検出しないよう、これを超えるものだけを対象にする。
+const ENTROPY_THRESHOLD: f64 = 4.0;
+
+/// コマンド中に見つかった秘密情報らしき文字列。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretFinding {
+    /// 検出の種類（`GitHub token` など）。
+    pub kind: &'static str,
+    /// 置き換え時に参照する環境変数名。
+    pub env_var: &'static str,
+    /// コマンド文字列中のバイト範囲。
+    pub range: Range<usize>,
+}
+
+/// コマンド文字列から秘密情報らしき部分を探す。
+///
+/// 正規表現のルールで検出した後、残りの部分をエントロピーで判定する。
+/// 結果は重なりのない範囲を先頭から順に返す。
+pub fn scan(command: &str) -> Vec<SecretFinding> {
+    let mut findings: Vec<SecretFinding> = Vec::new();
+    for rule in rules::rules() {
+        for captures in rule.regex.captures_iter(command) {
+            let Some(secret) = captures.name("secret") else {
+                continue;
+            };
+            push_if_free(&mut findings, rule.kind, rule.env_var, secret.range());
+        }
+    }
+
+    for candidate in rules::entropy_candidates().find_iter(command) {
+        if looks_random(candidate.as_str()) {
+            push_if_free(&mut findings, "high-entropy string", "SECRET", candidate.range());
+        }
+    }
+
+    findings.sort_by_key(|finding| finding.range.start);
+    findings
+}
+
+/// 検出した範囲を `${環境変数名}` の参照に置き換えたコマンドを返す。
+///
+/// シングルクォートの中では展開されないため、`'"${NAME}"'` のように
+/// いったんクォートを閉じてから参照を置く。
+pub fn replace_with_env_refs(command: &str, findings: &[SecretFinding]) -> String {
+    let mut replaced = String::new();
+    let mut cursor = 0;
+    for finding in findings {
+        replaced.push_str(&command[cursor..finding.range.start]);
+        if in_single_quotes(command, finding.range.start) {
+            replaced.push_str(&format!("'\"${{{}}}\"'", finding.env_var));
+        } else {
+            replaced.push_str(&format!("${{{}}}", finding.env_var));
+        }
+        cursor = finding.range.end;
+    }
+    replaced.push_str(&command[cursor..]);
+    replaced
+}
+
+/// `position` がシングルクォートの中にあるかを判定する。
+///
+/// ダブルクォート内の `'` とバックスラッシュでエスケープされた文字はクォートとして扱わない。
+fn in_single_quotes(command: &str, position: usize) -> bool {
+    let (mut single, mut double, mut escaped) = (false, false, false);
+    for ch in command[..position].chars() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match ch {
+            '\\' if !single => escaped = true,
+            '\'' if !double => single = !single,
+            '"' if !single => double = !double,
+            _ => {}
+        }
+    }
+    single
+}
+
+/// 秘密情報らしき部分を伏せたコマンドを返す。
+///
+/// 何も検出されなかった場合は `None` を返す。
+pub fn redact(command: &str) -> Option<String> {
+    let findings = scan(command);
+    if findings.is_empty() {
+        return None;
+    }
+    let mut redacted = String::new();
+    let mut cursor = 0;
+    for finding in &findings {
+        redacted.push_str(&command[cursor..finding.range.start]);
+        redacted.push_str(&mask(&command[finding.range.clone()]));
+        cursor = finding.range.end;
+    }
+    redacted.push_str(&command[cursor..]);
+    Some(redacted)
+}
+
+/// 表示用に秘密情報の大部分を伏せる。
+///
+/// 先頭4文字だけを残し、それ以降は `*` にする。
+pub fn mask(secret: &str) -> String {
+    let visible: String = secret.chars().take(4).collect();
+    let hidden = secret.chars().count().saturating_sub(4).min(8);
+    format!("{visible}{}", "*".repeat(hidden.max(3)))
+}
+
+/// 既存の検出と重ならない場合だけ追加する。
+fn push_if_free(
+    findings: &mut Vec<SecretFinding>,
+    kind: &'static str,
+    env_var: &'static str,
+    range: Range<usize>,
+) {
+    let overlaps = findings
+        .iter()
+        .any(|found| found.range.start < range.end && range.start < found.range.end);
+    if !overlaps {
+        findings.push(SecretFinding {
+            kind,
+            env_var,
+            range,
+        });
+    }
+}
+
+/// ランダムな文字列らしいかをエントロピーで判定する。
+///
+/// 英字と数字の両方を含み、シャノンエントロピーが閾値を超えるものを対象にする。
+pub(crate) fn looks_random(candidate: &str) -> bool {
+    let has_alpha = candidate.chars().any(|ch| ch.is_ascii_alphabetic());
+    let has_digit = candidate.chars().any(|ch| ch.is_ascii_digit());
+    has_alpha && has_digit && shannon_entropy(candidate) > ENTROPY_THRESHOLD
+}
+
+/// 1文字あたりのシャノンエントロピーを計算する。
+fn shannon_entropy(text: &str) -> f64 {
+    let mut counts = [0usize; 256];
+    for byte in text.bytes() {
+        counts[byte as usize] += 1;
+    }
+    let len = text.len() as f64;
+    counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let p = *count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests;
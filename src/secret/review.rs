@@ -0,0 +1,82 @@
+use super::{mask, replace_with_env_refs, scan, SecretFinding};
+use crate::config::SecretMode;
+use crate::error::GclipError;
+use crate::output::display_safe;
+use crate::selection;
+
+/// 登録前にコマンドを検査し、登録する文字列を決める。
+///
+/// - 検出がなければそのまま返す。
+/// - `warn`: 警告を表示してそのまま返す。
+/// - `refuse`: エラーにする。
+/// - `prompt`: 環境変数参照への置き換え/そのまま/スキップを選んでもらう。
+///   端末がなく選べない場合は `refuse` と同じく拒否する。
+///
+/// スキップした場合は `None` を返す。
+pub fn review_for_registration(command: &str, mode: SecretMode) -> Result<Option<String>, GclipError> {
+    let findings = scan(command);
+    if findings.is_empty() {
+        return Ok(Some(command.to_string()));
+    }
+
+    print_findings(command, &findings);
+    match mode {
+        SecretMode::Warn => Ok(Some(command.to_string())),
+        SecretMode::Refuse => Err(refused(&findings)),
+        SecretMode::Prompt if !selection::tty_available() => Err(refused(&findings)),
+        SecretMode::Prompt => prompt_action(command, &findings),
+    }
+}
+
+/// 対話できない経路（`gclip --serve` など）で登録前にコマンドを検査する。
+///
+/// 置き換えを選んでもらえないため、`warn` 以外では検出があれば拒否する。
+pub fn review_without_prompt(command: &str, mode: SecretMode) -> Result<(), GclipError> {
+    let findings = scan(command);
+    if findings.is_empty() || mode == SecretMode::Warn {
+        return Ok(());
+    }
+    Err(refused(&findings))
+}
+
+/// 登録を拒否するエラーを作る。
+fn refused(findings: &[SecretFinding]) -> GclipError {
+    GclipError::Refused(format!(
+        "refusing to register a command that contains secrets: {}",
+        describe_kinds(findings)
+    ))
+}
+
+/// 検出内容を標準エラーへ表示する。
+///
+/// 秘密情報そのものは伏せて表示する。
+fn print_findings(command: &str, findings: &[SecretFinding]) {
+    eprintln!("Warning: the command looks like it contains secrets:");
+    for finding in findings {
+        let secret = &command[finding.range.clone()];
+        eprintln!("  - {}: {}", finding.kind, display_safe(&mask(secret)));
+    }
+}
+
+/// 置き換え/そのまま登録/スキップを選んでもらう。
+///
+/// 空入力や不明な入力はスキップとして扱う。
+fn prompt_action(command: &str, findings: &[SecretFinding]) -> Result<Option<String>, GclipError> {
+    let replaced = replace_with_env_refs(command, findings);
+    eprintln!("  [r] register as: {}", display_safe(&replaced));
+    eprintln!("  [k] keep the command as is");
+    eprintln!("  [s] skip (default)");
+    let answer = selection::prompt_line("Choose r/k/s")?;
+    match answer.trim().to_ascii_lowercase().as_str() {
+        "r" => Ok(Some(replaced)),
+        "k" => Ok(Some(command.to_string())),
+        _ => Ok(None),
+    }
+}
+
+/// 検出の種類を重複なしで連結する。
+fn describe_kinds(findings: &[SecretFinding]) -> String {
+    let mut kinds: Vec<&str> = findings.iter().map(|finding| finding.kind).collect();
+    kinds.dedup();
+    kinds.join(", ")
+}
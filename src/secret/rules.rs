@@ -0,0 +1,94 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// 1件の検出ルール。
+///
+/// 正規表現の `secret` グループに一致した範囲を秘密情報とみなす。
+pub(super) struct SecretRule {
+    pub(super) kind: &'static str,
+    pub(super) env_var: &'static str,
+    pub(super) regex: Regex,
+}
+
+/// ルールの定義。`(種類, 置き換え先の環境変数名, 正規表現)` の組。
+///
+/// 置き換え時は `${環境変数名}` を使う。
+const RULE_DEFINITIONS: &[(&str, &str, &str)] = &[
+    (
+        "private key",
+        "PRIVATE_KEY",
+        r"(?P<secret>-----BEGIN [A-Z ]*PRIVATE KEY-----)",
+    ),
+    (
+        "AWS access key",
+        "AWS_ACCESS_KEY_ID",
+        r"\b(?P<secret>(?:AKIA|ASIA)[0-9A-Z]{16})\b",
+    ),
+    (
+        "AWS secret key",
+        "AWS_SECRET_ACCESS_KEY",
+        r#"(?i)aws_secret_access_key["']?\s*[=:]\s*["']?(?P<secret>[A-Za-z0-9/+=]{40})"#,
+    ),
+    (
+        "GitHub token",
+        "GITHUB_TOKEN",
+        r"\b(?P<secret>(?:gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,}))",
+    ),
+    (
+        "Slack token",
+        "SLACK_TOKEN",
+        r"\b(?P<secret>xox[abprs]-[A-Za-z0-9-]{10,})",
+    ),
+    (
+        "bearer token",
+        "API_TOKEN",
+        r"(?i)\bbearer\s+(?P<secret>[A-Za-z0-9._~+/=-]{8,})",
+    ),
+    (
+        "URL credentials",
+        "URL_PASSWORD",
+        r"://[^/\s:@]+:(?P<secret>[^/\s@$]+)@",
+    ),
+    (
+        "password option",
+        "PASSWORD",
+        r#"(?:^|\s)--password(?:=|\s+)["']?(?P<secret>[^\s"'$][^\s"']*)"#,
+    ),
+    (
+        "password option",
+        "DB_PASSWORD",
+        r#"^(?:sudo\s+)?(?:mysql|mysqldump|mysqladmin|mariadb|sshpass)\b.*?\s-p\s*["']?(?P<secret>[^\s"'$-][^\s"']*)"#,
+    ),
+    (
+        "secret assignment",
+        "SECRET",
+        r#"(?i)\b[A-Z0-9_]*(?:TOKEN|SECRET|PASSWORD|PASSWD|API_?KEY)[A-Z0-9_]*\s*=\s*["']?(?P<secret>[^\s"'$][^\s"']*)"#,
+    ),
+];
+
+/// コンパイル済みのルール一覧を返す。
+///
+/// 正規表現のコンパイルは初回の呼び出し時に1回だけ行う。
+pub(super) fn rules() -> &'static [SecretRule] {
+    static RULES: OnceLock<Vec<SecretRule>> = OnceLock::new();
+    RULES.get_or_init(|| {
+        RULE_DEFINITIONS
+            .iter()
+            .map(|(kind, env_var, pattern)| SecretRule {
+                kind,
+                env_var,
+                regex: Regex::new(pattern).expect("built-in secret rule should compile"),
+            })
+            .collect()
+    })
+}
+
+/// エントロピー判定の候補になる文字列を探す正規表現を返す。
+///
+/// 英数字と `+` `_` `-` の24文字以上の連続（末尾の `=` パディングを含む）を対象にする。
+pub(super) fn entropy_candidates() -> &'static Regex {
+    static CANDIDATES: OnceLock<Regex> = OnceLock::new();
+    CANDIDATES.get_or_init(|| {
+        Regex::new(r"[A-Za-z0-9+_-]{24,}={0,2}").expect("entropy candidate pattern should compile")
+    })
+}
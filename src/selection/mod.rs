@@ -3,11 +3,13 @@ use std::io::{self, BufRead, BufReader, Write};
 use std::mem::MaybeUninit;
 use std::os::unix::io::AsRawFd;
 
+use crate::error::GclipError;
+
 /// 単一選択の入力を促し、選択されたインデックスを返す。
 ///
 /// - 空入力はキャンセル扱いとして `None` を返す。
 /// - 不正な入力は再入力を促す。
-pub(crate) fn prompt_single_selection(max: usize, label: &str) -> Result<Option<usize>, String> {
+pub(crate) fn prompt_single_selection(max: usize, label: &str) -> Result<Option<usize>, GclipError> {
     loop {
         print_prompt(label, max)?;
         let input = read_input_line()?;
@@ -20,29 +22,83 @@ pub(crate) fn prompt_single_selection(max: usize, label: &str) -> Result<Option<
     }
 }
 
+/// 事前指定があればそれを使い、なければ選択プロンプトを表示する。
+///
+/// `--select` / `--first` で渡された入力はプロンプトと同じ規則で解析し、
+/// 不正な場合は再入力できないためエラーにする。
+pub(crate) fn resolve_single_selection(
+    preselect: Option<&str>,
+    max: usize,
+    label: &str,
+) -> Result<Option<usize>, GclipError> {
+    let Some(input) = preselect else {
+        return prompt_single_selection(max, label);
+    };
+
+    match parse_selection_input(input, max)? {
+        Some(index) => Ok(Some(index)),
+        None => Err(GclipError::InvalidInput("selection is empty".to_string())),
+    }
+}
+
+/// `[y/N]` の確認を求め、同意されたかを返す。
+///
+/// `y` / `yes` 以外（空入力を含む）は拒否として扱う。
+pub(crate) fn confirm(message: &str) -> Result<bool, GclipError> {
+    eprint!("{message} [y/N]: ");
+    io::stderr()
+        .flush()
+        .map_err(|err| GclipError::io("failed to flush stderr", err))?;
+    let input = read_input_line()?;
+    Ok(is_yes(&input))
+}
+
+/// ラベルを表示して1行の入力を受け取る。
+///
+/// 選択肢の解釈は呼び出し側で行う。
+pub(crate) fn prompt_line(label: &str) -> Result<String, GclipError> {
+    eprint!("{label}: ");
+    io::stderr()
+        .flush()
+        .map_err(|err| GclipError::io("failed to flush stderr", err))?;
+    read_input_line()
+}
+
+/// 入力を受け付ける `/dev/tty` が開けるかを返す。
+///
+/// パイプやエディタから起動された場合など、対話できないときは偽になる。
+pub(crate) fn tty_available() -> bool {
+    File::open("/dev/tty").is_ok()
+}
+
+/// 確認入力が同意かどうかを判定する。
+pub(super) fn is_yes(input: &str) -> bool {
+    matches!(input.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
 /// 選択プロンプトを標準エラーへ出力し、フラッシュする。
 ///
 /// 標準出力は挿入対象のコマンド出力に使うため、混ぜないようにする。
-fn print_prompt(label: &str, max: usize) -> Result<(), String> {
+fn print_prompt(label: &str, max: usize) -> Result<(), GclipError> {
     eprint!("{label} (1-{max}, empty to cancel): ");
     io::stderr()
         .flush()
-        .map_err(|err| format!("failed to flush stderr: {err}"))
+        .map_err(|err| GclipError::io("failed to flush stderr", err))
 }
 
 /// 標準入力から1行読み取って返す。
 ///
 /// 入力が読めない場合はエラーにする。
-fn read_input_line() -> Result<String, String> {
+fn read_input_line() -> Result<String, GclipError> {
     // zleウィジェット内から呼ぶ場合、stdinが無効になることがあるため、
     // 明示的に /dev/tty から入力を読む。
-    let tty = File::open("/dev/tty").map_err(|err| format!("failed to open /dev/tty: {err}"))?;
+    let tty = File::open("/dev/tty").map_err(|err| GclipError::io("failed to open /dev/tty", err))?;
     let _guard = enable_tty_echo(&tty);
     let mut reader = BufReader::new(tty);
     let mut input = String::new();
     reader
         .read_line(&mut input)
-        .map_err(|err| format!("failed to read input: {err}"))?;
+        .map_err(|err| GclipError::io("failed to read input", err))?;
     Ok(input)
 }
 
@@ -88,7 +144,7 @@ impl Drop for TtyModeGuard {
 ///
 /// - 空入力: `None`
 /// - 数値1〜max: `Some(index)`
-pub(super) fn parse_selection_input(input: &str, max: usize) -> Result<Option<usize>, String> {
+pub(super) fn parse_selection_input(input: &str, max: usize) -> Result<Option<usize>, GclipError> {
     let trimmed = input.trim();
     if trimmed.is_empty() {
         return Ok(None);
@@ -96,7 +152,7 @@ pub(super) fn parse_selection_input(input: &str, max: usize) -> Result<Option<us
 
     let index = parse_index(trimmed)?;
     if index == 0 || index > max {
-        return Err(format!("out of range: {index}"));
+        return Err(GclipError::InvalidInput(format!("out of range: {index}")));
     }
     Ok(Some(index))
 }
@@ -104,11 +160,11 @@ pub(super) fn parse_selection_input(input: &str, max: usize) -> Result<Option<us
 /// 数値の選択インデックスを解析する。
 ///
 /// 数値以外はエラーにする。
-fn parse_index(token: &str) -> Result<usize, String> {
+fn parse_index(token: &str) -> Result<usize, GclipError> {
     token
         .trim()
         .parse::<usize>()
-        .map_err(|_| format!("not a number: {token}"))
+        .map_err(|_| GclipError::InvalidInput(format!("not a number: {token}")))
 }
 
 #[cfg(test)]
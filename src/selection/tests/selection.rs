@@ -1,4 +1,4 @@
-use super::super::parse_selection_input;
+use super::super::{is_yes, parse_selection_input};
 
 #[test]
 fn empty_input_returns_none() {
@@ -26,3 +26,12 @@ fn non_numeric_is_error() {
     // 数値に変換できない入力は無効として扱う。
     assert!(parse_selection_input("a", 3).is_err());
 }
+
+#[test]
+fn confirmation_accepts_only_yes() {
+    // y/yesだけを同意とし、空入力は拒否として扱う。
+    assert!(is_yes("y\n"));
+    assert!(is_yes(" YES "));
+    assert!(!is_yes(""));
+    assert!(!is_yes("n"));
+}
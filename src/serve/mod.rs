@@ -0,0 +1,189 @@
+pub mod protocol;
+
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{self, BufRead, Write};
+
+use crate::config::{Config, SecretMode};
+use crate::error::GclipError;
+use crate::registry::{Registry, UsageSource};
+use crate::secret;
+use crate::suggest;
+use protocol::{
+    ChangeResult, CommandsResult, Request, RequestEnvelope, Response, SuggestResult, Suggestion,
+    VersionResult, HANDLER_ERROR, INVALID_PARAMS, INVALID_REQUEST, JSONRPC_VERSION,
+    METHOD_NOT_FOUND, METHODS, PARSE_ERROR, PROTOCOL_VERSION,
+};
+
+/// `recent` で `limit` が省略された場合の件数。
+const DEFAULT_RECENT_LIMIT: usize = 10;
+
+/// `suggest` で `limit` が省略された場合の件数。
+const DEFAULT_SUGGEST_LIMIT: usize = 10;
+
+/// 標準入出力で行区切りJSONのリクエストを処理し続ける。
+///
+/// `gclip --serve` で実行し、標準入力が閉じられたら終了する。
+/// 1件の処理が失敗してもエラーレスポンスを返して処理を続ける。
+/// 通知（`id` のないリクエスト）にはレスポンスを返さない。
+pub fn run() -> Result<(), GclipError> {
+    let registry = Registry::open()?;
+    let secrets = Config::load()?.secrets.mode;
+    let stdin = io::stdin();
+    let mut stdout = io::stdout().lock();
+    for read in stdin.lock().lines() {
+        let line = read.map_err(|err| GclipError::io("failed to read stdin", err))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(response) = handle_line(&registry, secrets, &line) {
+            write_response(&mut stdout, &response)?;
+        }
+    }
+    Ok(())
+}
+
+/// 1行分のリクエストを解析して処理し、返すレスポンスを返す。
+///
+/// - JSONとして解析できない: parse error
+/// - オブジェクトでない、`method` が文字列でない、`jsonrpc` が `"2.0"` でない: invalid request
+/// - 未知のメソッド: method not found
+/// - パラメータが足りない、または型が違う: invalid params
+///
+/// 通知（`id` のないリクエスト）は処理だけを行い、失敗しても `None` を返す。
+/// ただし通知かどうか分からないほど壊れたリクエストにはエラーを返す。
+/// `secrets` は `add` で秘密情報を検出したときの扱いに使う。
+pub(crate) fn handle_line(registry: &Registry, secrets: SecretMode, line: &str) -> Option<Response> {
+    let mut value = match serde_json::from_str::<Value>(line) {
+        Ok(value) => value,
+        Err(err) => {
+            return Some(Response::failure(None, PARSE_ERROR, format!("parse error: {err}")));
+        }
+    };
+    let Some(method) = request_method(&value) else {
+        let id = value.get("id").cloned();
+        return Some(Response::failure(id, INVALID_REQUEST, "invalid request".to_string()));
+    };
+    let is_notification = value.get("id").is_none();
+    let id = value.get("id").cloned();
+    let response = if METHODS.contains(&method.as_str()) {
+        fill_default_params(&mut value);
+        match serde_json::from_value::<RequestEnvelope>(value) {
+            Ok(envelope) => match dispatch(registry, secrets, envelope.request) {
+                Ok(result) => Response::success(id, result),
+                Err(err) => Response::failure(id, HANDLER_ERROR, err.to_string()),
+            },
+            Err(err) => Response::failure(id, INVALID_PARAMS, format!("invalid params: {err}")),
+        }
+    } else {
+        Response::failure(id, METHOD_NOT_FOUND, format!("method not found: {method}"))
+    };
+    (!is_notification).then_some(response)
+}
+
+/// リクエストとして正しい形なら、メソッド名を返す。
+///
+/// オブジェクトで、`method` が文字列で、`jsonrpc` があれば `"2.0"` であることを確かめる。
+fn request_method(value: &Value) -> Option<String> {
+    let fields = value.as_object()?;
+    if let Some(version) = fields.get("jsonrpc")
+        && version.as_str() != Some(JSONRPC_VERSION)
+    {
+        return None;
+    }
+    fields.get("method")?.as_str().map(str::to_string)
+}
+
+/// `params` が省略されたリクエストに空のパラメータを補う。
+///
+/// 省略可能なパラメータだけを持つメソッドを `params` なしで呼べるようにする。
+/// `null` も省略と同じ扱いにする。
+fn fill_default_params(value: &mut Value) {
+    if let Value::Object(fields) = value {
+        let params = fields.entry("params").or_insert(Value::Null);
+        if params.is_null() {
+            *params = Value::Object(Default::default());
+        }
+    }
+}
+
+/// メソッドごとの処理へ振り分ける。
+///
+/// 各処理は `Registry` / `History` の公開APIを呼び出すだけにとどめる。
+/// `add` は対話できないため、秘密情報を検出したら置き換えを選ばせずに拒否する。
+fn dispatch(registry: &Registry, secrets: SecretMode, request: Request) -> Result<Value, GclipError> {
+    match request {
+        Request::Version {} => to_value(VersionResult {
+            protocol: PROTOCOL_VERSION,
+            gclip: env!("CARGO_PKG_VERSION").to_string(),
+        }),
+        Request::Search { query } => to_value(CommandsResult {
+            commands: registry.search_commands(&query)?,
+        }),
+        Request::List {} => to_value(CommandsResult {
+            commands: registry.list_commands()?,
+        }),
+        Request::Add { command, key } => {
+            secret::review_without_prompt(command.trim(), secrets)?;
+            let (registry_path, added) = match key {
+                Some(short_key) => registry.add_command_with_key(&command, &short_key)?,
+                None => registry.add_command(&command)?,
+            };
+            to_value(ChangeResult {
+                changed: added,
+                registry_path: registry_path.display().to_string(),
+            })
+        }
+        Request::Remove { command } => {
+            let (registry_path, removed) = registry.remove_command(&command)?;
+            to_value(ChangeResult {
+                changed: removed,
+                registry_path: registry_path.display().to_string(),
+            })
+        }
+        Request::RecordUse { command } => {
+            registry.record_recent(&command, UsageSource::Editor)?;
+            Ok(Value::Null)
+        }
+        Request::Recent { limit } => to_value(CommandsResult {
+            commands: registry.recent_commands(limit.unwrap_or(DEFAULT_RECENT_LIMIT))?,
+        }),
+        Request::Suggest { limit } => {
+            let (history_path, top) = suggest::collect_recommendations(
+                limit.unwrap_or(DEFAULT_SUGGEST_LIMIT),
+                &suggest::HistorySource::default(),
+            )?;
+            to_value(SuggestResult {
+                history_path,
+                suggestions: top
+                    .into_iter()
+                    .map(|rec| Suggestion {
+                        command: rec.command,
+                        count: rec.count,
+                    })
+                    .collect(),
+            })
+        }
+    }
+}
+
+/// 結果の構造体をJSONの値へ変換する。
+fn to_value<T: Serialize>(result: T) -> Result<Value, GclipError> {
+    serde_json::to_value(result)
+        .map_err(|err| GclipError::Other(format!("failed to serialize result: {err}")))
+}
+
+/// レスポンスを1行のJSONとして書き込み、フラッシュする。
+///
+/// クライアントが1行ずつ待つため、毎回フラッシュする。
+fn write_response(stdout: &mut impl Write, response: &Response) -> Result<(), GclipError> {
+    let serialized = serde_json::to_string(response)
+        .map_err(|err| format!("failed to serialize response: {err}"))?;
+    writeln!(stdout, "{serialized}")
+        .and_then(|_| stdout.flush())
+        .map_err(|err| GclipError::io("failed to write stdout", err))
+}
+
+#[cfg(test)]
+mod tests;
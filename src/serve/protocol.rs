@@ -0,0 +1,178 @@
+//! `gclip --serve` で使う行区切りJSONプロトコルの型定義。
+//!
+//! 1行に1つのリクエストを受け取り、1行に1つのレスポンスを返す。
+//! JSON-RPC 2.0に従う。ただしリクエストの `jsonrpc` フィールドは省略できる。
+//! `id` のないリクエストは通知として扱い、レスポンスを返さない。
+//!
+//! ```text
+//! -> {"jsonrpc":"2.0","id":1,"method":"search","params":{"query":"git"}}
+//! <- {"jsonrpc":"2.0","id":1,"result":{"commands":["git status"]}}
+//! -> {"jsonrpc":"2.0","id":2,"method":"remove","params":{"command":"nope"}}
+//! <- {"jsonrpc":"2.0","id":2,"error":{"code":-32000,"message":"..."}}
+//! -> {"jsonrpc":"2.0","method":"record-use","params":{"command":"ls"}}
+//! ```
+//!
+//! 互換性のない変更を加える場合は `PROTOCOL_VERSION` を上げる。
+//! クライアントは最初に `version` を呼び出して確認する想定。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// プロトコルのバージョン。
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// `jsonrpc` フィールドの値。
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// 呼び出せるメソッドの名前。
+pub const METHODS: [&str; 8] = [
+    "version",
+    "search",
+    "list",
+    "add",
+    "remove",
+    "record-use",
+    "recent",
+    "suggest",
+];
+
+/// JSONとして解析できなかった場合のエラーコード。
+pub const PARSE_ERROR: i64 = -32700;
+
+/// リクエストの形が正しくない（オブジェクトでない、`method` がないなど）場合のエラーコード。
+pub const INVALID_REQUEST: i64 = -32600;
+
+/// 未知のメソッドの場合のエラーコード。
+pub const METHOD_NOT_FOUND: i64 = -32601;
+
+/// パラメータが足りない、または型が違う場合のエラーコード。
+pub const INVALID_PARAMS: i64 = -32602;
+
+/// 処理中のエラー（登録ファイルの読み書き失敗など）のエラーコード。
+pub const HANDLER_ERROR: i64 = -32000;
+
+/// 1行分のリクエスト。
+///
+/// `id` はそのままレスポンスへ返すため、数値でも文字列でもよい。省略すると通知になる。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestEnvelope {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jsonrpc: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    #[serde(flatten)]
+    pub request: Request,
+}
+
+/// 呼び出せるメソッドとそのパラメータ。
+///
+/// `params` は省略でき、その場合は空のオブジェクトとして扱う。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "kebab-case")]
+pub enum Request {
+    /// プロトコルとgclip本体のバージョンを返す。
+    Version {},
+    /// 登録済みコマンドを部分一致で検索する。
+    Search { query: String },
+    /// 登録済みコマンドを一覧で返す。
+    List {},
+    /// コマンドを登録する。`key` があれば短縮キーも割り当てる。
+    Add {
+        command: String,
+        #[serde(default)]
+        key: Option<String>,
+    },
+    /// 登録済みコマンドを完全一致で削除する。
+    Remove { command: String },
+    /// コマンドを使用したことを記録する（直近使用コマンドへ追加）。
+    RecordUse { command: String },
+    /// 直近使用コマンドを返す。
+    Recent {
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+    /// 履歴から頻出コマンドを推薦する。登録は行わない。
+    Suggest {
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+}
+
+/// 1行分のレスポンス。
+///
+/// `result` と `error` のどちらか一方だけが入る。
+/// `id` が分からない（解析できなかった）場合は `null` になる。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Response {
+    pub jsonrpc: String,
+    pub id: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorObject>,
+}
+
+/// エラー内容。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorObject {
+    pub code: i64,
+    pub message: String,
+}
+
+/// `version` の結果。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionResult {
+    pub protocol: u32,
+    pub gclip: String,
+}
+
+/// `search` / `list` / `recent` の結果。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandsResult {
+    pub commands: Vec<String>,
+}
+
+/// `add` / `remove` の結果。
+///
+/// `changed` は追加または削除された件数。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeResult {
+    pub changed: usize,
+    pub registry_path: String,
+}
+
+/// `suggest` の結果の1件分。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Suggestion {
+    pub command: String,
+    pub count: usize,
+}
+
+/// `suggest` の結果。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestResult {
+    pub history_path: String,
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl Response {
+    /// 成功レスポンスを組み立てる。
+    pub fn success(id: Option<Value>, result: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    /// エラーレスポンスを組み立てる。
+    pub fn failure(id: Option<Value>, code: i64, message: String) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result: None,
+            error: Some(ErrorObject { code, message }),
+        }
+    }
+}
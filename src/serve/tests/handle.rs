@@ -0,0 +1,174 @@
+use super::super::handle_line;
+use super::super::protocol::{
+    ChangeResult, CommandsResult, Request, RequestEnvelope, Response, HANDLER_ERROR,
+    INVALID_PARAMS, INVALID_REQUEST, JSONRPC_VERSION, METHOD_NOT_FOUND, METHODS, PARSE_ERROR,
+    PROTOCOL_VERSION,
+};
+use crate::config::SecretMode;
+use crate::registry::Registry;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+/// 応答が返るリクエストを処理する。
+fn respond(registry: &Registry, line: &str) -> Response {
+    handle_line(registry, SecretMode::default(), line).expect("request with id should get a response")
+}
+
+/// プロトコルの型でリクエストを組み立てて送り、レスポンスを1行のJSONから読み直す。
+fn round_trip<T: DeserializeOwned>(registry: &Registry, id: u64, request: Request) -> T {
+    let envelope = RequestEnvelope {
+        jsonrpc: Some(JSONRPC_VERSION.to_string()),
+        id: Some(json!(id)),
+        request,
+    };
+    let line = serde_json::to_string(&envelope).expect("serialize request");
+    let written = serde_json::to_string(&respond(registry, &line)).expect("serialize response");
+    let response: Response = serde_json::from_str(&written).expect("parse response");
+    assert_eq!(response.jsonrpc, JSONRPC_VERSION);
+    assert_eq!(response.id, Some(json!(id)));
+    assert!(response.error.is_none(), "unexpected error: {:?}", response.error);
+    serde_json::from_value(response.result.expect("result")).expect("parse result")
+}
+
+#[test]
+fn version_returns_protocol_version() {
+    // versionはidをそのまま返し、プロトコルのバージョンを含むことを確認する。
+    let response = respond(&Registry::in_memory(), r#"{"id":7,"method":"version"}"#);
+    assert_eq!(response.id, Some(json!(7)));
+    assert_eq!(response.jsonrpc, JSONRPC_VERSION);
+    let result = response.result.expect("version should succeed");
+    assert_eq!(result["protocol"], json!(PROTOCOL_VERSION));
+}
+
+#[test]
+fn invalid_json_is_parse_error() {
+    // JSONとして解析できない行はparse errorになり、idはnullで返す。
+    let response = respond(&Registry::in_memory(), "{not json");
+    let error = response.error.as_ref().expect("should be error");
+    assert_eq!(error.code, PARSE_ERROR);
+    assert!(response.id.is_none());
+    let written = serde_json::to_value(&response).expect("serialize");
+    assert_eq!(written["id"], json!(null));
+    assert_eq!(written["jsonrpc"], json!("2.0"));
+}
+
+#[test]
+fn malformed_request_is_invalid_request() {
+    // オブジェクトでないもの、methodのないもの、jsonrpcが2.0でないものはinvalid requestになる。
+    let registry = Registry::in_memory();
+    for line in [
+        "[1,2]",
+        r#"{"id":1}"#,
+        r#"{"id":1,"method":5}"#,
+        r#"{"jsonrpc":"1.0","id":1,"method":"list"}"#,
+    ] {
+        let error = respond(&registry, line).error.expect("should be error");
+        assert_eq!(error.code, INVALID_REQUEST, "{line}");
+    }
+}
+
+#[test]
+fn unknown_method_is_method_not_found() {
+    // 未知のメソッドでもidは返し、method not foundになる。
+    let response = respond(&Registry::in_memory(), r#"{"id":"a","method":"explode"}"#);
+    assert_eq!(response.id, Some(json!("a")));
+    let error = response.error.expect("should be error");
+    assert_eq!(error.code, METHOD_NOT_FOUND);
+}
+
+#[test]
+fn bad_params_are_invalid_params() {
+    // 必須パラメータの欠落や型の違いはinvalid paramsになる。
+    let registry = Registry::in_memory();
+    for line in [
+        r#"{"id":1,"method":"search"}"#,
+        r#"{"id":1,"method":"add","params":{"command":3}}"#,
+        r#"{"id":1,"method":"recent","params":[10]}"#,
+    ] {
+        let error = respond(&registry, line).error.expect("should be error");
+        assert_eq!(error.code, INVALID_PARAMS, "{line}");
+    }
+}
+
+#[test]
+fn every_listed_method_is_dispatched() {
+    // METHODSの名前はすべてメソッドとして受け付けられる。
+    let registry = Registry::in_memory();
+    for method in METHODS {
+        let line = json!({"id": 1, "method": method}).to_string();
+        let response = respond(&registry, &line);
+        let code = response.error.map(|error| error.code);
+        assert_ne!(code, Some(METHOD_NOT_FOUND), "{method}");
+    }
+}
+
+#[test]
+fn notifications_get_no_response() {
+    // idのないリクエストは処理だけを行い、失敗してもレスポンスを返さない。
+    let registry = Registry::in_memory();
+    let recorded = handle_line(
+        &registry,
+        SecretMode::default(),
+        r#"{"jsonrpc":"2.0","method":"record-use","params":{"command":"ls"}}"#,
+    );
+    assert!(recorded.is_none());
+    assert_eq!(registry.recent_commands(10).expect("recent"), vec!["ls".to_string()]);
+    assert!(handle_line(&registry, SecretMode::default(), r#"{"method":"explode"}"#).is_none());
+    assert!(handle_line(&registry, SecretMode::default(), r#"{"method":"search"}"#).is_none());
+}
+
+#[test]
+fn add_search_and_remove_round_trip() {
+    // プロトコルの型で組み立てたリクエストが、同じ型で読めるレスポンスになることを確認する。
+    let registry = Registry::in_memory();
+    let added: ChangeResult = round_trip(
+        &registry,
+        1,
+        Request::Add {
+            command: "git status".to_string(),
+            key: Some("gs".to_string()),
+        },
+    );
+    assert_eq!(added.changed, 1);
+
+    let found: CommandsResult = round_trip(
+        &registry,
+        2,
+        Request::Search {
+            query: "git".to_string(),
+        },
+    );
+    assert_eq!(found.commands, vec!["git status".to_string()]);
+
+    let removed: ChangeResult = round_trip(
+        &registry,
+        3,
+        Request::Remove {
+            command: "git status".to_string(),
+        },
+    );
+    assert_eq!(removed.changed, 1);
+
+    let remaining: CommandsResult = round_trip(
+        &registry,
+        4,
+        Request::Search {
+            query: "git".to_string(),
+        },
+    );
+    assert!(remaining.commands.is_empty());
+}
+
+#[test]
+fn add_refuses_commands_with_secrets() {
+    // 対話できないため、秘密情報を含むコマンドは `warn` 以外ではエラーになり登録されない。
+    let registry = Registry::in_memory();
+    let line = r#"{"id":1,"method":"add","params":{"command":"curl -H 'Authorization: Bearer abcdef0123456789' https://x"}}"#;
+    let response = respond(&registry, line);
+    assert_eq!(response.error.expect("error").code, HANDLER_ERROR);
+    assert!(registry.list_commands().expect("list").is_empty());
+
+    let warned = handle_line(&registry, SecretMode::Warn, line).expect("response");
+    assert!(warned.error.is_none());
+    assert_eq!(registry.list_commands().expect("list").len(), 1);
+}
@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+use std::env;
+
+/// シェルが知っている名前を渡す環境変数。
+///
+/// `gclip init` の関数が、エイリアス、関数、組み込みコマンド、予約語を改行区切りで入れる。
+pub(crate) const SHELL_NAMES_VAR: &str = "GCLIP_SHELL_NAMES";
+
+/// zshとbashの組み込みコマンド。
+///
+/// PATHに実行ファイルがなくても動く。
+const BUILTINS: &[&str] = &[
+    "-", ".", ":", "[", "alias", "autoload", "bg", "bind", "bindkey", "break", "builtin", "bye",
+    "caller", "cd", "chdir", "command", "compadd", "compctl", "compdef", "compgen", "complete",
+    "continue", "declare", "dirs", "disable", "disown", "echo", "echotc", "echoti", "emulate",
+    "enable", "eval", "exec", "exit", "export", "false", "fc", "fg", "float", "functions",
+    "getln", "getopts", "hash", "help", "history", "integer", "jobs", "kill", "let", "limit",
+    "local", "logout", "mapfile", "noglob", "popd", "print", "printf", "private", "pushd",
+    "pushln", "pwd", "r", "read", "readarray", "readonly", "rehash", "return", "sched", "set",
+    "setopt", "shift", "shopt", "source", "suspend", "test", "times", "trap", "true", "ttyctl",
+    "type", "typeset", "ulimit", "umask", "unalias", "unfunction", "unhash", "unlimit", "unset",
+    "unsetopt", "vared", "wait", "whence", "where", "which", "zcompile", "zformat", "zle",
+    "zmodload", "zparseopts", "zregexparse", "zstyle",
+];
+
+/// zshとbashの予約語。
+const KEYWORDS: &[&str] = &[
+    "!", "[[", "]]", "{", "}", "case", "coproc", "do", "done", "elif", "else", "end", "esac",
+    "fi", "for", "foreach", "function", "if", "in", "nocorrect", "repeat", "select", "then",
+    "time", "until", "while",
+];
+
+/// 組み込みコマンドか予約語かを返す。
+pub(crate) fn is_builtin(name: &str) -> bool {
+    BUILTINS.contains(&name) || KEYWORDS.contains(&name)
+}
+
+/// 実行ファイル以外にシェルが解釈できる名前。
+///
+/// 組み込みコマンドと予約語に加え、`gclip init` の関数から渡されたエイリアスや関数を含む。
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ShellNames {
+    names: HashSet<String>,
+    provided: bool,
+}
+
+impl ShellNames {
+    /// 環境変数 `GCLIP_SHELL_NAMES` から読み込む。
+    pub(crate) fn from_env() -> Self {
+        match env::var(SHELL_NAMES_VAR) {
+            Ok(value) => Self::from_list(&value),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// 改行区切りの名前の一覧から作る。
+    pub(crate) fn from_list(list: &str) -> Self {
+        Self {
+            names: list
+                .lines()
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect(),
+            provided: true,
+        }
+    }
+
+    /// 実行ファイルでなくても使える名前かを返す。
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        is_builtin(name) || self.names.contains(name)
+    }
+
+    /// シェルから受け取った一覧に含まれる名前かを返す。組み込みの一覧は見ない。
+    pub(crate) fn is_defined(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    /// エイリアスや関数までシェルから受け取ったかを返す。
+    ///
+    /// 受け取っていなければ、PATHにないものが本当に使えないかは分からない。
+    pub(crate) fn is_provided(&self) -> bool {
+        self.provided
+    }
+}
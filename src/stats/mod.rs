@@ -0,0 +1,99 @@
+mod report;
+
+use crate::clock;
+use crate::error::GclipError;
+use crate::output::{self, display_safe, OutputFormat};
+use crate::registry::Registry;
+
+pub use report::{build_report, SourceShare, StatsReport, UnusedEntry, UsedEntry, WeeklyUses};
+
+/// 最多/最少使用の一覧に表示する件数。
+const TOP_LIMIT: usize = 5;
+
+/// 使用状況の集計を表示する幅（`#` の最大数）。
+const BAR_WIDTH: usize = 30;
+
+/// 登録済みコマンドの使用状況を集計して表示する。
+///
+/// `gclip stats` で実行する。
+pub fn run(format: &OutputFormat) -> Result<(), GclipError> {
+    let snapshot = Registry::open()?.usage_snapshot()?;
+    let today = clock::days_from_secs(clock::now_secs());
+    let report = build_report(&snapshot, today, TOP_LIMIT);
+    if !format.is_text() {
+        return output::print_item(format, &report);
+    }
+    print_report(&report);
+    Ok(())
+}
+
+/// 集計結果を標準出力へ表示する。
+fn print_report(report: &StatsReport) {
+    println!(
+        "Registered commands: {} (inserted {} time(s))",
+        report.registered, report.total_uses
+    );
+
+    println!("Most used:");
+    print_used(&report.most_used);
+    println!("Least used:");
+    print_used(&report.least_used);
+
+    println!("Never used ({}):", report.never_used.len());
+    for entry in &report.never_used {
+        match &entry.added {
+            Some(added) => println!("  - {} (added {added})", display_safe(&entry.command)),
+            None => println!("  - {}", display_safe(&entry.command)),
+        }
+    }
+
+    println!("Uses per week:");
+    let peak = report.weekly.iter().map(|week| week.uses).max().unwrap_or(0);
+    for week in &report.weekly {
+        println!("  {}  {:<BAR_WIDTH$} {}", week.week_start, bar(week.uses, peak), week.uses);
+    }
+
+    let sources = &report.sources;
+    println!(
+        "Inserted from: search {}, recent {}, editor {}",
+        share(sources.search, report.total_uses),
+        share(sources.recent, report.total_uses),
+        share(sources.editor, report.total_uses)
+    );
+}
+
+/// 使用回数付きの一覧を表示する。
+fn print_used(entries: &[UsedEntry]) {
+    if entries.is_empty() {
+        println!("  (none)");
+        return;
+    }
+    for (index, entry) in entries.iter().enumerate() {
+        println!(
+            "  {:>2}. {} ({}x, last {})",
+            index + 1,
+            display_safe(&entry.command),
+            entry.count,
+            entry.last_used
+        );
+    }
+}
+
+/// 最大値に対する割合を `#` の棒で表す。
+fn bar(value: usize, peak: usize) -> String {
+    if peak == 0 {
+        return String::new();
+    }
+    "#".repeat((value * BAR_WIDTH).div_ceil(peak))
+}
+
+/// 件数と全体に対する割合を表示用に整形する。
+fn share(count: usize, total: usize) -> String {
+    if total == 0 {
+        return format!("{count}");
+    }
+    format!("{count} ({}%)", count * 100 / total)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,134 @@
+use serde::Serialize;
+
+use crate::clock;
+use crate::registry::UsageSnapshot;
+
+/// 週ごとの集計を表示する週数。
+const WEEKS: i64 = 8;
+
+/// 使用状況の集計結果。
+#[derive(Debug, Serialize)]
+pub struct StatsReport {
+    /// 登録済みコマンドの件数。
+    pub registered: usize,
+    /// 記録されている挿入の総数（削除済みのコマンドを含む）。
+    pub total_uses: usize,
+    pub most_used: Vec<UsedEntry>,
+    pub least_used: Vec<UsedEntry>,
+    pub never_used: Vec<UnusedEntry>,
+    /// 古い週から順に並べた週ごとの挿入回数。
+    pub weekly: Vec<WeeklyUses>,
+    pub sources: SourceShare,
+}
+
+/// 使用されたことのある登録済みコマンド。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UsedEntry {
+    pub command: String,
+    pub count: usize,
+    /// 最後に使用した日（`YYYY-MM-DD`、UTC）。
+    pub last_used: String,
+}
+
+/// 登録後に一度も使用されていないコマンド。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnusedEntry {
+    pub command: String,
+    /// 登録日。記録がない古い登録では `null` になる。
+    pub added: Option<String>,
+}
+
+/// 1週間分の挿入回数。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WeeklyUses {
+    /// 週の初日（`YYYY-MM-DD`、UTC）。
+    pub week_start: String,
+    pub uses: usize,
+}
+
+/// 挿入の経路ごとの回数。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SourceShare {
+    pub search: usize,
+    pub recent: usize,
+    pub editor: usize,
+}
+
+/// 使用状況から集計結果を組み立てる。
+///
+/// - 最多/最少使用は登録済みで使用回数が1以上のものから `top` 件ずつ選ぶ
+/// - 最少使用には最多使用に含めたものを重ねて出さない
+/// - 週ごとの集計は `today` を含む直近の週から遡る
+pub fn build_report(snapshot: &UsageSnapshot, today: i64, top: usize) -> StatsReport {
+    let mut used: Vec<UsedEntry> = Vec::new();
+    let mut never_used = Vec::new();
+    for command in &snapshot.commands {
+        match snapshot.usage.get(command).filter(|record| record.count > 0) {
+            Some(record) => used.push(UsedEntry {
+                command: command.clone(),
+                count: record.count,
+                last_used: clock::format_date(record.last_used),
+            }),
+            None => never_used.push(UnusedEntry {
+                command: command.clone(),
+                added: snapshot.added.get(command).map(|secs| clock::format_date(*secs)),
+            }),
+        }
+    }
+
+    // 回数が同じ場合は最近使われたものを多い側に寄せる。
+    used.sort_by(|left, right| {
+        right
+            .count
+            .cmp(&left.count)
+            .then_with(|| right.last_used.cmp(&left.last_used))
+    });
+    let most_used: Vec<UsedEntry> = used.iter().take(top).cloned().collect();
+    let least_used: Vec<UsedEntry> = used
+        .iter()
+        .rev()
+        .take(top)
+        .filter(|entry| !most_used.contains(entry))
+        .cloned()
+        .collect();
+
+    let mut sources = SourceShare::default();
+    for record in snapshot.usage.values() {
+        sources.search += record.search;
+        sources.recent += record.recent;
+        sources.editor += record.editor;
+    }
+
+    StatsReport {
+        registered: snapshot.commands.len(),
+        total_uses: snapshot.usage.values().map(|record| record.count).sum(),
+        most_used,
+        least_used,
+        never_used,
+        weekly: weekly_uses(snapshot, today),
+        sources,
+    }
+}
+
+/// 日別の回数を週ごとにまとめる。
+fn weekly_uses(snapshot: &UsageSnapshot, today: i64) -> Vec<WeeklyUses> {
+    let first_day = today - WEEKS * 7 + 1;
+    let mut buckets = vec![0; WEEKS as usize];
+    for (date, uses) in &snapshot.daily {
+        let Some(day) = clock::parse_date(date) else {
+            continue;
+        };
+        if (first_day..=today).contains(&day) {
+            buckets[((day - first_day) / 7) as usize] += uses;
+        }
+    }
+
+    buckets
+        .into_iter()
+        .enumerate()
+        .map(|(index, uses)| WeeklyUses {
+            week_start: clock::format_days(first_day + index as i64 * 7),
+            uses,
+        })
+        .collect()
+}
@@ -0,0 +1,73 @@
+use super::super::build_report;
+use crate::clock;
+use crate::registry::{UsageRecord, UsageSnapshot};
+
+/// 2025-10-19（UTC）のエポックからの日数。
+const TODAY: i64 = 20_380;
+
+fn record(count: usize, search: usize, last_day: i64) -> UsageRecord {
+    UsageRecord {
+        count,
+        first_used: 0,
+        last_used: last_day as u64 * clock::SECONDS_PER_DAY,
+        search,
+        recent: count - search,
+        editor: 0,
+        ..Default::default()
+    }
+}
+
+fn snapshot() -> UsageSnapshot {
+    let mut snapshot = UsageSnapshot {
+        commands: ["git status", "ls", "make test", "unused"]
+            .map(String::from)
+            .to_vec(),
+        ..Default::default()
+    };
+    snapshot.usage.insert("git status".into(), record(5, 4, TODAY));
+    snapshot.usage.insert("ls".into(), record(1, 1, TODAY - 30));
+    snapshot.usage.insert("make test".into(), record(3, 0, TODAY - 1));
+    // 削除済みのコマンドも総数と経路には含まれる。
+    snapshot.usage.insert("removed".into(), record(2, 2, TODAY - 3));
+    snapshot
+        .added
+        .insert("unused".into(), 20_000 * clock::SECONDS_PER_DAY);
+    snapshot.daily.insert(clock::format_days(TODAY), 4);
+    snapshot.daily.insert(clock::format_days(TODAY - 7), 2);
+    snapshot.daily.insert(clock::format_days(TODAY - 100), 9);
+    snapshot
+}
+
+#[test]
+fn ranks_most_and_least_used() {
+    // 回数順に並び、最少使用には最多使用と重ならないものだけが入る。
+    let report = build_report(&snapshot(), TODAY, 2);
+    let most: Vec<&str> = report.most_used.iter().map(|entry| entry.command.as_str()).collect();
+    let least: Vec<&str> = report.least_used.iter().map(|entry| entry.command.as_str()).collect();
+    assert_eq!(most, vec!["git status", "make test"]);
+    assert_eq!(least, vec!["ls"]);
+    assert_eq!(report.most_used[0].last_used, "2025-10-19");
+}
+
+#[test]
+fn lists_never_used_entries_with_added_date() {
+    // 一度も使われていない登録済みコマンドが登録日付きで入る。
+    let report = build_report(&snapshot(), TODAY, 5);
+    assert_eq!(report.never_used.len(), 1);
+    assert_eq!(report.never_used[0].command, "unused");
+    assert_eq!(report.never_used[0].added.as_deref(), Some("2024-10-04"));
+}
+
+#[test]
+fn totals_sources_and_weekly_uses() {
+    // 経路ごとの回数と、直近8週の週ごとの回数を集計する。
+    let report = build_report(&snapshot(), TODAY, 5);
+    assert_eq!(report.total_uses, 11);
+    assert_eq!(report.sources.search, 7);
+    assert_eq!(report.sources.recent, 4);
+    assert_eq!(report.weekly.len(), 8);
+    assert_eq!(report.weekly[7].uses, 4);
+    assert_eq!(report.weekly[6].uses, 2);
+    assert_eq!(report.weekly.iter().map(|week| week.uses).sum::<usize>(), 6);
+    assert_eq!(report.weekly[7].week_start, "2025-10-13");
+}
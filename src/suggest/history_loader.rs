@@ -1,31 +1,214 @@
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 
-use crate::history::History;
+use crate::error::GclipError;
+use crate::history::{History, HistoryFormat};
+use crate::registry::{Execution, Registry, StoreFile};
+
+/// 推薦に使う履歴の読み込み元。
+///
+/// どちらも省略した場合は既定の履歴ファイル（`HISTFILE` など）をzshの書式で読む。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HistorySource {
+    /// 履歴ファイルのパス。`-` なら標準入力から読む。
+    pub path: Option<PathBuf>,
+    /// 履歴の書式。省略時は内容から推測する。
+    pub format: Option<HistoryFormat>,
+}
+
+impl HistorySource {
+    /// 標準入力から読む指定かを返す。
+    pub fn is_stdin(&self) -> bool {
+        self.path.as_deref() == Some(Path::new("-"))
+    }
+}
+
+/// 読み込んだ履歴。
+///
+/// `label` は表示用の読み込み元（パスまたは `stdin`）。
+/// `commands` は古い順のコマンド一覧。
+/// `durations` は実行履歴に記録された、成功したときの平均所要時間（ミリ秒）。
+pub(crate) struct LoadedHistory {
+    pub(crate) label: String,
+    pub(crate) commands: Vec<String>,
+    pub(crate) durations: HashMap<String, u64>,
+}
+
+/// 指定された読み込み元から履歴を読み込む。
+///
+/// パスが省略されていれば、既定の履歴ファイル（書式の指定がなければzshの書式）と
+/// `gclip init --record` の実行履歴を合わせて使う。どちらか一方しかなければそれだけを使う。
+/// パスが指定されていれば、書式が省略された場合は内容から推測し、表示用の名前に書式を添える。
+pub(crate) fn load_history(source: &HistorySource) -> Result<LoadedHistory, GclipError> {
+    let Some(path) = source.path.as_deref() else {
+        return load_default_history(source.format.unwrap_or(HistoryFormat::Zsh));
+    };
+
+    let (name, contents) = if source.is_stdin() {
+        ("stdin".to_string(), read_stdin()?)
+    } else {
+        (path.display().to_string(), load_history_contents(path)?)
+    };
+    let format = source
+        .format
+        .unwrap_or_else(|| History::detect_format(&contents));
+    Ok(LoadedHistory {
+        label: format!("{name} ({format})"),
+        commands: History::recent_commands_in(&contents, format, usize::MAX),
+        durations: HashMap::new(),
+    })
+}
+
+/// 既定の履歴ファイルとフックが記録した実行履歴を合わせて読み込む。
+///
+/// 実行履歴がなければ履歴ファイルだけを、履歴ファイルが見つからなければ実行履歴だけを使う。
+/// どちらもなければ履歴ファイルが見つからないエラーにする。
+fn load_default_history(format: HistoryFormat) -> Result<LoadedHistory, GclipError> {
+    let registry = Registry::open()?;
+    let executions = registry.executions()?;
+    let log_path = registry.storage().location(StoreFile::Executions);
+    let history = match resolve_history_path() {
+        Ok(history_path) => {
+            let contents = load_history_contents(&history_path)?;
+            Some((
+                history_path,
+                History::recent_commands_in(&contents, format, usize::MAX),
+            ))
+        }
+        Err(err) if executions.is_empty() => return Err(err),
+        Err(_) => None,
+    };
+
+    let failed = executions.iter().filter(|run| !run.succeeded()).count();
+    let durations = recorded_durations(&executions);
+    let (label, commands) = match history {
+        Some((history_path, commands)) if executions.is_empty() => {
+            (history_path.display().to_string(), commands)
+        }
+        Some((history_path, commands)) => (
+            format!(
+                "{} + {} ({failed} failed skipped)",
+                history_path.display(),
+                log_path.display()
+            ),
+            merge_recorded(commands, &executions),
+        ),
+        None => (
+            format!("{} (recorded, {failed} failed skipped)", log_path.display()),
+            merge_recorded(Vec::new(), &executions),
+        ),
+    };
+    Ok(LoadedHistory {
+        label,
+        commands,
+        durations,
+    })
+}
+
+/// 履歴ファイルのコマンドに、フックが記録した実行履歴を合わせる。
+///
+/// - 実行履歴で一度も成功していないコマンド（打ち間違いなど）は履歴ファイルからも除く
+/// - 成功したコマンドのうち履歴ファイルにないものは、記録順に後ろへ加える
+///   （別のシェルの履歴や、まだ履歴ファイルに書き出されていないもの）
+///
+/// 両方にあるコマンドは履歴ファイル側だけで数え、二重に数えない。
+pub(crate) fn merge_recorded(history: Vec<String>, executions: &[Execution]) -> Vec<String> {
+    let succeeded: HashSet<&str> = executions
+        .iter()
+        .filter(|run| run.succeeded())
+        .map(|run| run.command.as_str())
+        .collect();
+    let failed: HashSet<&str> = executions
+        .iter()
+        .filter(|run| !run.succeeded() && !succeeded.contains(run.command.as_str()))
+        .map(|run| run.command.as_str())
+        .collect();
+    let mut merged: Vec<String> = history
+        .into_iter()
+        .filter(|command| !failed.contains(command.as_str()))
+        .collect();
+    let known: HashSet<String> = merged.iter().cloned().collect();
+    merged.extend(
+        executions
+            .iter()
+            .filter(|run| run.succeeded() && !known.contains(&run.command))
+            .map(|run| run.command.clone()),
+    );
+    merged
+}
+
+/// 成功した実行の平均所要時間（ミリ秒）をコマンドごとに求める。
+///
+/// 所要時間が記録されていない（0の）実行は数えない。
+pub(crate) fn recorded_durations(executions: &[Execution]) -> HashMap<String, u64> {
+    let mut totals: HashMap<&str, (u64, u64)> = HashMap::new();
+    for run in executions
+        .iter()
+        .filter(|run| run.succeeded() && run.duration_ms > 0)
+    {
+        let total = totals.entry(run.command.as_str()).or_default();
+        total.0 += run.duration_ms;
+        total.1 += 1;
+    }
+    totals
+        .into_iter()
+        .map(|(command, (sum, runs))| (command.to_string(), sum / runs))
+        .collect()
+}
 
 /// 履歴ファイルのパスを解決する。
 ///
-/// macOSの標準である `~/.zsh_history` を前提にする。
-pub(crate) fn resolve_history_path() -> Result<PathBuf, String> {
-    History::find_history_file().ok_or("history file not found (~/.zsh_history)".to_string())
+/// 見つからない場合は、探した場所をエラーに含める。
+pub(crate) fn resolve_history_path() -> Result<PathBuf, GclipError> {
+    if let Some(path) = History::find_history_file() {
+        return Ok(path);
+    }
+    let message = match History::locate_history_file() {
+        Some(file) => format!(
+            "history file not found: {} (from {})",
+            file.path.display(),
+            file.origin.label()
+        ),
+        None => "history file not found (HOME not set)".to_string(),
+    };
+    Err(GclipError::Io(message))
 }
 
 /// 履歴ファイルを読み込み、文字列として返す。
 ///
 /// 非UTF-8文字が混ざる可能性があるため、損失変換で読み込む。
-pub(crate) fn load_history_contents(path: &PathBuf) -> Result<String, String> {
-    let bytes = fs::read(path).map_err(|err| format!("failed to read history file: {err}"))?;
+pub(crate) fn load_history_contents(path: &Path) -> Result<String, GclipError> {
+    let bytes = fs::read(path).map_err(|err| GclipError::io("failed to read history file", err))?;
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+/// 標準入力を最後まで読み込む。
+///
+/// 履歴ファイルと同じく損失変換で読み込む。
+fn read_stdin() -> Result<String, GclipError> {
+    let mut bytes = Vec::new();
+    io::stdin()
+        .read_to_end(&mut bytes)
+        .map_err(|err| GclipError::io("failed to read history from stdin", err))?;
     Ok(String::from_utf8_lossy(&bytes).to_string())
 }
 
 /// 履歴全体から直近 `limit` 件のコマンドを抽出する。
 ///
 /// 空の結果になった場合は、上位でエラーとして扱う。
-pub(crate) fn collect_recent_commands(contents: &str, limit: usize) -> Result<Vec<String>, String> {
-    let recent = History::recent_commands(contents, limit);
+pub(crate) fn collect_recent_commands(
+    history: &LoadedHistory,
+    limit: usize,
+) -> Result<Vec<String>, GclipError> {
+    let start = history.commands.len().saturating_sub(limit);
+    let recent = &history.commands[start..];
     if recent.is_empty() {
-        Err("no commands found in recent history".to_string())
+        Err(GclipError::NoMatches(
+            "no commands found in recent history".to_string(),
+        ))
     } else {
-        Ok(recent)
+        Ok(recent.to_vec())
     }
 }
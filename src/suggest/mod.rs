@@ -8,33 +8,68 @@ mod types;
 
 use config::{HISTORY_SAMPLE_SIZE, MAX_RECOMMENDATIONS};
 
+use crate::error::GclipError;
+use crate::output::OutputOptions;
+use types::Recommendation;
+
+pub use history_loader::HistorySource;
+pub(crate) use history_loader::{load_history_contents, resolve_history_path};
+pub(crate) use selection::parse_selection_input as parse_multi_selection;
+
 /// `--suggest` 機能の実行本体。
 ///
-/// 1. 履歴ファイルを特定して読み込む
+/// 1. 履歴ファイル（`source` の指定があればそのファイルか標準入力）を読み込む
+///    （指定がなければ `gclip init --record` の実行履歴も合わせ、失敗したコマンドを除く）
 /// 2. 直近100件のコマンドを抽出
 /// 3. 頻度順に並べて上位10件を推薦
 /// 4. 推薦結果を選択して登録する
-pub fn run() -> Result<(), String> {
-    let history_path = history_loader::resolve_history_path()?;
-    let contents = history_loader::load_history_contents(&history_path)?;
-    let recent = history_loader::collect_recent_commands(&contents, HISTORY_SAMPLE_SIZE)?;
-
-    let recommendations = recommend::build_recommendations(&recent);
-    let top = recommend::select_top(recommendations, MAX_RECOMMENDATIONS);
-    selection::ensure_recommendations(&top)?;
+///
+/// 機械処理向けの形式で選択の指定がなければ、推薦一覧の出力だけを行う。
+pub fn run(options: &OutputOptions, source: &HistorySource) -> Result<(), GclipError> {
+    let (history_label, top) = collect_recommendations(MAX_RECOMMENDATIONS, source)?;
 
-    output::print_recommendations(&history_path, &top, HISTORY_SAMPLE_SIZE);
+    if options.list_only() {
+        return output::print_recommendation_items(&options.format, &top);
+    }
+    if options.select.is_none() {
+        output::print_recommendations(&history_label, &top, HISTORY_SAMPLE_SIZE);
+    }
 
-    let selected = selection::prompt_selection(top.len())?;
+    let selected = match options.select.as_deref() {
+        Some(input) => selection::parse_selection_input(input, top.len())?,
+        None => selection::prompt_selection(top.len(), source.is_stdin())?,
+    };
     if selected.is_empty() {
-        output::print_selection_cancelled();
-        return Ok(());
+        return Err(GclipError::Cancelled);
     }
 
     let commands = selection::select_commands(&top, &selected);
     let (registry_path, added) = registry_ops::register_selected_commands(&commands)?;
-    output::print_registration_result(added, &registry_path);
-    Ok(())
+    if options.format.is_text() {
+        output::print_registration_result(added, &registry_path);
+        return Ok(());
+    }
+    output::print_registration_item(&options.format, added, &registry_path)
+}
+
+/// 履歴を読み込み、上位 `max` 件の推薦を返す。
+///
+/// 使用した履歴の表示用の名前も合わせて返す。
+/// `--suggest` と `--serve` の両方から使う。
+pub(crate) fn collect_recommendations(
+    max: usize,
+    source: &HistorySource,
+) -> Result<(String, Vec<Recommendation>), GclipError> {
+    let history = history_loader::load_history(source)?;
+    let recent = history_loader::collect_recent_commands(&history, HISTORY_SAMPLE_SIZE)?;
+
+    let recommendations = recommend::build_recommendations(&recent);
+    let mut top = recommend::select_top(recommendations, max);
+    selection::ensure_recommendations(&top)?;
+    for rec in &mut top {
+        rec.duration_ms = history.durations.get(&rec.command).copied();
+    }
+    Ok((history.label, top))
 }
 
 #[cfg(test)]
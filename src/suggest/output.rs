@@ -1,31 +1,66 @@
+use serde::Serialize;
 use std::path::Path;
 
 use super::types::Recommendation;
+use crate::error::GclipError;
+use crate::output::{self, display_safe, OutputFormat};
+
+/// 機械処理向けに出力する推薦の1件分。
+#[derive(Debug, Serialize)]
+struct RecommendationItem<'a> {
+    index: usize,
+    command: &'a str,
+    count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u64>,
+}
+
+/// 機械処理向けに出力する登録結果。
+#[derive(Debug, Serialize)]
+struct RegistrationItem<'a> {
+    added: usize,
+    registry_path: &'a Path,
+}
 
 /// 推薦一覧を表示し、選択を促すための出力を行う。
 ///
 /// - 使用した履歴ファイル
 /// - 推薦件数と対象件数
-/// - 各推薦の順位と出現回数
+/// - 各推薦の順位と出現回数（実行履歴に記録があれば平均所要時間も）
+///
+/// 履歴由来のコマンドは制御文字を見える形に置き換えて表示する。
 pub(crate) fn print_recommendations(
-    history_path: &Path,
+    history_label: &str,
     recommendations: &[Recommendation],
     history_sample_size: usize,
 ) {
-    println!("History file: {}", history_path.display());
+    println!("History file: {history_label}");
     println!(
         "Recommendations (top {} from last {} commands):",
         recommendations.len(),
         history_sample_size
     );
     for (index, rec) in recommendations.iter().enumerate() {
-        println!("{:>2}. {} ({}x)", index + 1, rec.command, rec.count);
+        let took = rec
+            .duration_ms
+            .map(|ms| format!(", ~{}", format_duration(ms)))
+            .unwrap_or_default();
+        println!(
+            "{:>2}. {} ({}x{took})",
+            index + 1,
+            display_safe(&rec.command),
+            rec.count
+        );
     }
 }
 
-/// ユーザーが選択をキャンセルした場合の表示。
-pub(crate) fn print_selection_cancelled() {
-    println!("No commands selected. Nothing was registered.");
+/// 所要時間を `850ms` や `3.2s`、`2m05s` の形にする。
+pub(crate) fn format_duration(ms: u64) -> String {
+    match ms {
+        0..1_000 => format!("{ms}ms"),
+        1_000..60_000 => format!("{:.1}s", ms as f64 / 1_000.0),
+        _ => format!("{}m{:02}s", ms / 60_000, ms % 60_000 / 1_000),
+    }
 }
 
 /// 登録結果を標準出力へ表示する。
@@ -38,3 +73,32 @@ pub(crate) fn print_registration_result(added: usize, registry_path: &Path) {
         registry_path.display()
     );
 }
+
+/// 推薦一覧を指定形式で出力する。
+///
+/// 番号は選択入力と同じ1始まりにする。
+pub(crate) fn print_recommendation_items(
+    format: &OutputFormat,
+    recommendations: &[Recommendation],
+) -> Result<(), GclipError> {
+    let items: Vec<RecommendationItem> = recommendations
+        .iter()
+        .enumerate()
+        .map(|(index, rec)| RecommendationItem {
+            index: index + 1,
+            command: &rec.command,
+            count: rec.count,
+            duration_ms: rec.duration_ms,
+        })
+        .collect();
+    output::print_items(format, &items)
+}
+
+/// 登録結果を指定形式で出力する。
+pub(crate) fn print_registration_item(
+    format: &OutputFormat,
+    added: usize,
+    registry_path: &Path,
+) -> Result<(), GclipError> {
+    output::print_item(format, &RegistrationItem { added, registry_path })
+}
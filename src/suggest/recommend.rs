@@ -31,6 +31,7 @@ fn to_recommendations(stats: HashMap<String, CommandStats>) -> Vec<Recommendatio
             command,
             count: stat.count,
             last_seen: stat.last_seen,
+            duration_ms: None,
         })
         .collect()
 }
@@ -1,10 +1,21 @@
 use std::path::PathBuf;
 
+use crate::config::Config;
+use crate::error::GclipError;
 use crate::registry::Registry;
+use crate::secret;
 
 /// 選択されたコマンドのみを登録ファイルへ追記する。
 ///
 /// Registryの詳細を呼び出し側から隠し、suggest機能の責務を明確にする。
-pub(crate) fn register_selected_commands(commands: &[String]) -> Result<(PathBuf, usize), String> {
-    Registry::register_commands(commands)
+/// 秘密情報らしき文字列を含むコマンドは、設定に従って置き換えやスキップを行う。
+pub(crate) fn register_selected_commands(commands: &[String]) -> Result<(PathBuf, usize), GclipError> {
+    let config = Config::load()?;
+    let mut reviewed = Vec::new();
+    for command in commands {
+        if let Some(accepted) = secret::review_for_registration(command, config.secrets.mode)? {
+            reviewed.push(accepted);
+        }
+    }
+    Registry::open()?.register_commands(&reviewed)
 }
@@ -1,14 +1,16 @@
 use std::collections::HashSet;
-use std::io::{self, Write};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
 
 use super::types::Recommendation;
+use crate::error::GclipError;
 
 /// 推薦結果が空でないことを確認する。
 ///
 /// ここで空なら、その後の選択処理が意味を持たないためエラーにする。
-pub(crate) fn ensure_recommendations(recommendations: &[Recommendation]) -> Result<(), String> {
+pub(crate) fn ensure_recommendations(recommendations: &[Recommendation]) -> Result<(), GclipError> {
     if recommendations.is_empty() {
-        Err("no commands to recommend".to_string())
+        Err(GclipError::NoMatches("no commands to recommend".to_string()))
     } else {
         Ok(())
     }
@@ -17,10 +19,11 @@ pub(crate) fn ensure_recommendations(recommendations: &[Recommendation]) -> Resu
 /// 対話的に登録対象を選択してもらう。
 ///
 /// 不正な入力があった場合は再入力を促す。
-pub(crate) fn prompt_selection(max: usize) -> Result<Vec<usize>, String> {
+/// 標準入力を履歴の読み込みに使った場合は `from_tty` を指定し、端末から読む。
+pub(crate) fn prompt_selection(max: usize, from_tty: bool) -> Result<Vec<usize>, GclipError> {
     loop {
         print_selection_prompt(max)?;
-        let input = read_input_line()?;
+        let input = read_input_line(from_tty)?;
         match parse_selection_input(&input, max) {
             Ok(indices) => return Ok(indices),
             Err(err) => {
@@ -44,24 +47,28 @@ pub(crate) fn select_commands(recommendations: &[Recommendation], indices: &[usi
 /// 選択プロンプトを表示し、標準出力をフラッシュする。
 ///
 /// 入力待ちの前に必ずフラッシュして表示漏れを防ぐ。
-fn print_selection_prompt(max: usize) -> Result<(), String> {
+fn print_selection_prompt(max: usize) -> Result<(), GclipError> {
     print!(
         "Select commands to register (1-{}, e.g. 1,3 or 1-3, 'all', empty to cancel): ",
         max
     );
     io::stdout()
         .flush()
-        .map_err(|err| format!("failed to flush stdout: {err}"))
+        .map_err(|err| GclipError::io("failed to flush stdout", err))
 }
 
-/// 標準入力から1行読み取って返す。
+/// 標準入力（`from_tty` なら端末）から1行読み取って返す。
 ///
 /// 入力が読めない場合はエラーにする。
-fn read_input_line() -> Result<String, String> {
+fn read_input_line(from_tty: bool) -> Result<String, GclipError> {
     let mut input = String::new();
-    io::stdin()
-        .read_line(&mut input)
-        .map_err(|err| format!("failed to read input: {err}"))?;
+    let result = if from_tty {
+        let tty = File::open("/dev/tty").map_err(|err| GclipError::io("failed to open /dev/tty", err))?;
+        BufReader::new(tty).read_line(&mut input)
+    } else {
+        io::stdin().read_line(&mut input)
+    };
+    result.map_err(|err| GclipError::io("failed to read input", err))?;
     Ok(input)
 }
 
@@ -70,7 +77,7 @@ fn read_input_line() -> Result<String, String> {
 /// - 空入力: 何も選ばない（空配列）
 /// - `all`: 全件選択
 /// - `1,3,5` または `1-3` のような形式に対応
-pub(crate) fn parse_selection_input(input: &str, max: usize) -> Result<Vec<usize>, String> {
+pub(crate) fn parse_selection_input(input: &str, max: usize) -> Result<Vec<usize>, GclipError> {
     let trimmed = input.trim();
     if trimmed.is_empty() {
         return Ok(Vec::new());
@@ -88,7 +95,7 @@ pub(crate) fn parse_selection_input(input: &str, max: usize) -> Result<Vec<usize
     }
 
     if indices.is_empty() {
-        Err("no valid selection".to_string())
+        Err(GclipError::InvalidInput("no valid selection".to_string()))
     } else {
         Ok(indices)
     }
@@ -113,7 +120,7 @@ fn add_indices_from_token(
     max: usize,
     indices: &mut Vec<usize>,
     seen: &mut HashSet<usize>,
-) -> Result<(), String> {
+) -> Result<(), GclipError> {
     if let Some((start, end)) = parse_range(token)? {
         for idx in start..=end {
             push_index(idx, max, indices, seen)?;
@@ -128,7 +135,7 @@ fn add_indices_from_token(
 /// `start-end` 形式の範囲指定を解析する。
 ///
 /// 該当しない場合は `Ok(None)` を返す。
-fn parse_range(token: &str) -> Result<Option<(usize, usize)>, String> {
+fn parse_range(token: &str) -> Result<Option<(usize, usize)>, GclipError> {
     let (start_str, end_str) = match token.split_once('-') {
         Some(parts) => parts,
         None => return Ok(None),
@@ -137,7 +144,7 @@ fn parse_range(token: &str) -> Result<Option<(usize, usize)>, String> {
     let start = parse_single_index(start_str)?;
     let end = parse_single_index(end_str)?;
     if start > end {
-        return Err(format!("invalid range: {token}"));
+        return Err(GclipError::InvalidInput(format!("invalid range: {token}")));
     }
     Ok(Some((start, end)))
 }
@@ -145,11 +152,11 @@ fn parse_range(token: &str) -> Result<Option<(usize, usize)>, String> {
 /// 単一の番号を解析する。
 ///
 /// 数値でない場合はエラーにする。
-fn parse_single_index(token: &str) -> Result<usize, String> {
+fn parse_single_index(token: &str) -> Result<usize, GclipError> {
     token
         .trim()
         .parse::<usize>()
-        .map_err(|_| format!("not a number: {token}"))
+        .map_err(|_| GclipError::InvalidInput(format!("not a number: {token}")))
 }
 
 /// 選択されたインデックスを追加する。
@@ -160,9 +167,9 @@ fn push_index(
     max: usize,
     indices: &mut Vec<usize>,
     seen: &mut HashSet<usize>,
-) -> Result<(), String> {
+) -> Result<(), GclipError> {
     if index == 0 || index > max {
-        return Err(format!("out of range: {index}"));
+        return Err(GclipError::InvalidInput(format!("out of range: {index}")));
     }
     if seen.insert(index) {
         indices.push(index);
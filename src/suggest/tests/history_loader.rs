@@ -0,0 +1,55 @@
+use super::super::history_loader::{merge_recorded, recorded_durations};
+use super::super::output::format_duration;
+use crate::registry::Execution;
+
+fn run(command: &str, exit: i32, duration_ms: u64) -> Execution {
+    Execution {
+        command: command.to_string(),
+        cwd: None,
+        exit,
+        duration_ms,
+        at: 1_700_000_000,
+    }
+}
+
+fn commands(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+#[test]
+fn recorded_runs_are_merged_into_history() {
+    // 成功したことのないコマンドは履歴ファイルからも除き、履歴ファイルにないものだけを加える。
+    let history = commands(&["ls", "mkae", "make", "make"]);
+    let executions = vec![
+        run("mkae", 127, 5),
+        run("make", 2, 900),
+        run("make", 0, 1_000),
+        run("cargo test", 0, 3_000),
+        run("cargo tset", 1, 5),
+    ];
+    assert_eq!(
+        merge_recorded(history, &executions),
+        commands(&["ls", "make", "make", "cargo test"])
+    );
+}
+
+#[test]
+fn durations_average_successful_runs() {
+    // 成功した実行のうち、所要時間が記録されたものだけで平均する。
+    let executions = vec![
+        run("make", 0, 1_000),
+        run("make", 0, 3_000),
+        run("make", 2, 10),
+        run("ls", 0, 0),
+    ];
+    let durations = recorded_durations(&executions);
+    assert_eq!(durations.get("make"), Some(&2_000));
+    assert_eq!(durations.get("ls"), None);
+}
+
+#[test]
+fn durations_are_formatted_by_scale() {
+    assert_eq!(format_duration(850), "850ms");
+    assert_eq!(format_duration(3_240), "3.2s");
+    assert_eq!(format_duration(125_000), "2m05s");
+}
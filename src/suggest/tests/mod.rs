@@ -1,2 +1,3 @@
+mod history_loader;
 mod recommend;
 mod selection;
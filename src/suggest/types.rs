@@ -6,6 +6,8 @@ pub(crate) struct Recommendation {
     pub(crate) command: String,
     pub(crate) count: usize,
     pub(crate) last_seen: usize,
+    /// 実行履歴に記録された平均所要時間（ミリ秒）。
+    pub(crate) duration_ms: Option<u64>,
 }
 
 /// 集計の途中で使う統計情報。
@@ -7,3 +7,17 @@ fn public_api_can_read_history() {
     let recent = History::recent_commands(&contents, 10);
     assert_eq!(recent, vec!["pwd".to_string(), "ls".to_string()]);
 }
+
+#[test]
+fn public_api_can_use_in_memory_registry() {
+    // 公開APIとしてメモリ上のRegistryが使えることを確認する簡易テスト。
+    use gclip_cli::registry::{MemoryStorage, Registry, StoreFile};
+
+    let registry = Registry::new(MemoryStorage::new().with_file(StoreFile::Registry, "commands = [\"ls\"]\n"));
+    registry.add_command_with_key("git status", "gs").expect("add should succeed");
+    assert_eq!(
+        registry.keyed_commands().expect("keys should load"),
+        vec![("gs".to_string(), "git status".to_string())]
+    );
+    assert_eq!(registry.list_commands().expect("list should load").len(), 2);
+}